@@ -178,6 +178,7 @@ impl RenderCache {
         GlyphDrawCommands {
             alpha_glyphs: alpha_cmd_map.into_values().collect(),
             color_glyphs: color_cmd_map.into_values().collect(),
+            subpixel_glyphs: Vec::new(),
         }
     }
 }
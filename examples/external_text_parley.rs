@@ -306,6 +306,7 @@ fn render_glyph_run<T: Renderer>(
         GlyphDrawCommands {
             alpha_glyphs: alpha_cmd_map.into_values().collect(),
             color_glyphs: color_cmd_map.into_values().collect(),
+            subpixel_glyphs: Vec::new(),
         },
         &Paint::color(color),
     );
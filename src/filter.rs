@@ -0,0 +1,473 @@
+use std::collections::HashMap;
+
+use crate::{
+    geometry::Rect, BlendMode, Color, ColorChannel, ComponentTransfer, EdgeMode, MorphologyOperator, TurbulenceKind,
+};
+
+/// Names the image a [`FilterEffect`] reads, mirroring the `in` and `in2`
+/// attributes of SVG filter primitives.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum FilterInput {
+    /// The result of the preceding primitive, or the source image for the
+    /// first primitive of a graph.
+    #[default]
+    Previous,
+    /// The source image the graph is applied to.
+    SourceGraphic,
+    /// The alpha channel of the source image, with black color channels.
+    SourceAlpha,
+    /// The result of the closest preceding primitive carrying this result
+    /// name. An unknown name reads the preceding primitive's result instead,
+    /// as the Filter Effects spec specifies.
+    Result(String),
+}
+
+impl From<&str> for FilterInput {
+    fn from(name: &str) -> Self {
+        Self::Result(name.to_string())
+    }
+}
+
+/// How [`FilterEffect::Composite`] combines its two inputs, the `operator`
+/// attribute of SVG `feComposite`. `input` is the source, `input2` the
+/// destination.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum FilterCompositeOperator {
+    /// `input` over `input2`.
+    #[default]
+    Over,
+    /// The part of `input` inside `input2`.
+    In,
+    /// The part of `input` outside `input2`.
+    Out,
+    /// The part of `input` inside `input2`, over `input2`.
+    Atop,
+    /// The parts of both inputs outside the other one.
+    Xor,
+    /// The sum of both inputs.
+    Lighter,
+    /// `k1 * i1 * i2 + k2 * i1 + k3 * i2 + k4` per premultiplied channel,
+    /// see [`ImageFilter::Arithmetic`](crate::ImageFilter::Arithmetic).
+    Arithmetic {
+        /// Weight of the product of both inputs.
+        k1: f32,
+        /// Weight of `input`.
+        k2: f32,
+        /// Weight of `input2`.
+        k3: f32,
+        /// Constant added to every channel.
+        k4: f32,
+    },
+}
+
+/// The operation of a [`FilterPrimitive`], one per SVG filter primitive
+/// element. All coordinates and lengths are in pixels of the source image.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum FilterEffect {
+    /// Blurs the input, SVG `feGaussianBlur`. A standard deviation of zero
+    /// passes the input through unchanged.
+    GaussianBlur {
+        /// The image to blur.
+        input: FilterInput,
+        /// The standard deviation of the blur.
+        std_deviation: f32,
+    },
+    /// Transforms the colors of the input with a 4x5 matrix, SVG
+    /// `feColorMatrix`. See [`ImageFilter::ColorMatrix`](crate::ImageFilter::ColorMatrix).
+    ColorMatrix {
+        /// The image to transform.
+        input: FilterInput,
+        /// Row-major 4x5 color matrix.
+        matrix: [f32; 20],
+    },
+    /// Erodes or dilates the input, SVG `feMorphology`. See
+    /// [`ImageFilter::Morphology`](crate::ImageFilter::Morphology). A negative
+    /// radius, or two zero radii, pass the input through unchanged.
+    Morphology {
+        /// The image to erode or dilate.
+        input: FilterInput,
+        /// Whether the image is thinned or fattened.
+        operator: MorphologyOperator,
+        /// Horizontal radius.
+        radius_x: f32,
+        /// Vertical radius.
+        radius_y: f32,
+    },
+    /// Convolves the input with a kernel, SVG `feConvolveMatrix`. See
+    /// [`ImageFilter::Convolve`](crate::ImageFilter::Convolve) for the fields.
+    Convolve {
+        /// The image to convolve.
+        input: FilterInput,
+        /// Row-major kernel values.
+        kernel: [f32; 25],
+        /// Columns and rows of the kernel.
+        size: (usize, usize),
+        /// Divides the weighted sum; zero selects the kernel sum.
+        divisor: f32,
+        /// Added to the result.
+        bias: f32,
+        /// How pixels beyond the image edges are sampled.
+        edge_mode: EdgeMode,
+        /// Whether only the color channels are convolved.
+        preserve_alpha: bool,
+    },
+    /// Remaps each channel of the input through a transfer function, SVG
+    /// `feComponentTransfer`.
+    ComponentTransfer {
+        /// The image to remap.
+        input: FilterInput,
+        /// The transfer functions of the four channels.
+        transfer: ComponentTransfer,
+    },
+    /// Shifts the input, SVG `feOffset`.
+    Offset {
+        /// The image to shift.
+        input: FilterInput,
+        /// Horizontal shift.
+        dx: f32,
+        /// Vertical shift.
+        dy: f32,
+    },
+    /// Fills the primitive subregion with a color, SVG `feFlood`.
+    Flood {
+        /// The fill color.
+        color: Color,
+    },
+    /// Fills the primitive subregion with Perlin noise, SVG `feTurbulence`.
+    /// See [`ImageFilter::Turbulence`](crate::ImageFilter::Turbulence) for
+    /// the fields. Stitching tiles the noise across the source image rather
+    /// than the primitive subregion.
+    Turbulence {
+        /// Whether the octaves are summed as turbulence or fractal noise.
+        kind: TurbulenceKind,
+        /// Frequency of the first octave, in cycles per pixel.
+        base_frequency: (f32, f32),
+        /// Number of octaves.
+        octaves: u32,
+        /// Seeds the random lattice.
+        seed: f32,
+        /// Whether the noise tiles seamlessly.
+        stitch_tiles: bool,
+    },
+    /// Composites the inputs over each other in order, the first one at the
+    /// bottom, SVG `feMerge`.
+    Merge {
+        /// The images to stack.
+        inputs: Vec<FilterInput>,
+    },
+    /// Combines two inputs with a Porter-Duff or arithmetic operator, SVG
+    /// `feComposite`.
+    Composite {
+        /// The source image.
+        input: FilterInput,
+        /// The destination image.
+        input2: FilterInput,
+        /// How the images are combined.
+        operator: FilterCompositeOperator,
+    },
+    /// Blends `input` over `input2` with a blend mode, SVG `feBlend`.
+    Blend {
+        /// The source image.
+        input: FilterInput,
+        /// The backdrop.
+        input2: FilterInput,
+        /// How the colors are mixed.
+        mode: BlendMode,
+    },
+    /// Moves the pixels of `input` by offsets read from `input2`, SVG
+    /// `feDisplacementMap`.
+    DisplacementMap {
+        /// The image to distort.
+        input: FilterInput,
+        /// The displacement map.
+        input2: FilterInput,
+        /// The largest displacement in pixels.
+        scale: f32,
+        /// The channel of the map that moves pixels horizontally.
+        x_channel: ColorChannel,
+        /// The channel of the map that moves pixels vertically.
+        y_channel: ColorChannel,
+    },
+    /// Repeats the primitive subregion of the input across the primitive
+    /// subregion of the tile, SVG `feTile`.
+    Tile {
+        /// The primitive whose subregion is the tile.
+        input: FilterInput,
+    },
+}
+
+impl FilterEffect {
+    fn inputs(&self) -> Vec<&FilterInput> {
+        match self {
+            Self::GaussianBlur { input, .. }
+            | Self::ColorMatrix { input, .. }
+            | Self::Morphology { input, .. }
+            | Self::Convolve { input, .. }
+            | Self::ComponentTransfer { input, .. }
+            | Self::Offset { input, .. }
+            | Self::Tile { input } => vec![input],
+            Self::Flood { .. } | Self::Turbulence { .. } => Vec::new(),
+            Self::Merge { inputs } => inputs.iter().collect(),
+            Self::Composite { input, input2, .. }
+            | Self::Blend { input, input2, .. }
+            | Self::DisplacementMap { input, input2, .. } => vec![input, input2],
+        }
+    }
+}
+
+/// One node of a [`FilterGraph`]: an effect, the part of the image it
+/// produces and the name later primitives refer to its result by.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilterPrimitive {
+    effect: FilterEffect,
+    subregion: Option<[f32; 4]>,
+    result: Option<String>,
+}
+
+impl FilterPrimitive {
+    /// Creates a primitive covering the default subregion: the union of the
+    /// subregions of the primitives it reads, or the whole filter region when
+    /// it reads a source input, has no inputs or tiles.
+    pub fn new(effect: FilterEffect) -> Self {
+        Self {
+            effect,
+            subregion: None,
+            result: None,
+        }
+    }
+
+    /// Restricts the primitive's output to a rectangle, SVG's `x`, `y`,
+    /// `width` and `height` primitive attributes. The rectangle is clipped to
+    /// the filter region.
+    pub fn with_subregion(mut self, x: f32, y: f32, width: f32, height: f32) -> Self {
+        self.subregion = Some([x, y, width, height]);
+        self
+    }
+
+    /// Names the result so later primitives can read it with
+    /// [`FilterInput::Result`].
+    pub fn with_result(mut self, name: impl Into<String>) -> Self {
+        self.result = Some(name.into());
+        self
+    }
+
+    /// Returns the primitive's effect.
+    pub fn effect(&self) -> &FilterEffect {
+        &self.effect
+    }
+
+    /// Returns the explicit subregion as `[x, y, width, height]`, if any.
+    pub fn subregion(&self) -> Option<[f32; 4]> {
+        self.subregion
+    }
+
+    /// Returns the result name, if any.
+    pub fn result(&self) -> Option<&str> {
+        self.result.as_deref()
+    }
+}
+
+impl From<FilterEffect> for FilterPrimitive {
+    fn from(effect: FilterEffect) -> Self {
+        Self::new(effect)
+    }
+}
+
+/// A declarative filter effect modeled on the SVG `<filter>` element, run by
+/// [`Canvas::filter_image_graph`](crate::Canvas::filter_image_graph).
+///
+/// The primitives run in order and the last one produces the output. Nothing
+/// is drawn outside the filter region. Colors are processed in sRGB space,
+/// as if `color-interpolation-filters` were `sRGB`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FilterGraph {
+    region: Option<[f32; 4]>,
+    primitives: Vec<FilterPrimitive>,
+}
+
+impl FilterGraph {
+    /// Creates an empty graph, which produces a transparent image.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the filter region, in pixels of the source image. Defaults to the
+    /// whole image.
+    pub fn with_region(mut self, x: f32, y: f32, width: f32, height: f32) -> Self {
+        self.region = Some([x, y, width, height]);
+        self
+    }
+
+    /// Appends a primitive.
+    pub fn with_primitive(mut self, primitive: impl Into<FilterPrimitive>) -> Self {
+        self.push(primitive);
+        self
+    }
+
+    /// Appends a primitive.
+    pub fn push(&mut self, primitive: impl Into<FilterPrimitive>) {
+        self.primitives.push(primitive.into());
+    }
+
+    /// Returns the filter region as `[x, y, width, height]`, if one was set.
+    pub fn region(&self) -> Option<[f32; 4]> {
+        self.region
+    }
+
+    /// Returns the primitives in execution order.
+    pub fn primitives(&self) -> &[FilterPrimitive] {
+        &self.primitives
+    }
+
+    // Resolves the inputs and subregions of every primitive for an image of
+    // the given bounds, returning the filter region and the planned nodes.
+    pub(crate) fn plan(&self, bounds: Rect) -> (Rect, Vec<PlannedPrimitive<'_>>) {
+        let region = self
+            .region
+            .map_or(bounds, |[x, y, w, h]| bounds.intersect(Rect::new(x, y, w, h)));
+
+        let mut named = HashMap::new();
+        let mut planned: Vec<PlannedPrimitive<'_>> = Vec::with_capacity(self.primitives.len());
+
+        for (index, primitive) in self.primitives.iter().enumerate() {
+            let previous = index
+                .checked_sub(1)
+                .map_or(FilterSlot::SourceGraphic, FilterSlot::Primitive);
+            let inputs: Vec<FilterSlot> = primitive
+                .effect
+                .inputs()
+                .into_iter()
+                .map(|input| match input {
+                    FilterInput::Previous => previous,
+                    FilterInput::SourceGraphic => FilterSlot::SourceGraphic,
+                    FilterInput::SourceAlpha => FilterSlot::SourceAlpha,
+                    FilterInput::Result(name) => named.get(name.as_str()).copied().unwrap_or(previous),
+                })
+                .collect();
+
+            let subregion = match primitive.subregion {
+                Some([x, y, w, h]) => region.intersect(Rect::new(x, y, w, h)),
+                None if matches!(primitive.effect, FilterEffect::Tile { .. }) => region,
+                None => inputs
+                    .iter()
+                    .try_fold(None, |union: Option<Rect>, slot| match slot {
+                        FilterSlot::Primitive(index) => {
+                            let subregion = planned[*index].subregion;
+                            Some(Some(union.map_or(subregion, |union| union.union(&subregion))))
+                        }
+                        _ => None,
+                    })
+                    .flatten()
+                    .unwrap_or(region),
+            };
+
+            if let Some(name) = &primitive.result {
+                named.insert(name.as_str(), FilterSlot::Primitive(index));
+            }
+
+            planned.push(PlannedPrimitive {
+                effect: &primitive.effect,
+                inputs,
+                subregion,
+            });
+        }
+
+        (region, planned)
+    }
+}
+
+// An image a planned primitive reads.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum FilterSlot {
+    SourceGraphic,
+    SourceAlpha,
+    Primitive(usize),
+}
+
+// A primitive with its inputs resolved, in the order `FilterEffect::inputs`
+// lists them, and its subregion clipped to the filter region.
+#[derive(Debug)]
+pub(crate) struct PlannedPrimitive<'a> {
+    pub effect: &'a FilterEffect,
+    pub inputs: Vec<FilterSlot>,
+    pub subregion: Rect,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offset(input: FilterInput) -> FilterEffect {
+        FilterEffect::Offset {
+            input,
+            dx: 1.0,
+            dy: 1.0,
+        }
+    }
+
+    #[test]
+    fn inputs_resolve_to_named_and_previous_results() {
+        let graph = FilterGraph::new()
+            .with_primitive(FilterPrimitive::new(offset(FilterInput::Previous)).with_result("a"))
+            .with_primitive(offset(FilterInput::SourceAlpha))
+            .with_primitive(FilterEffect::Composite {
+                input: "a".into(),
+                input2: "missing".into(),
+                operator: FilterCompositeOperator::In,
+            })
+            .with_primitive(FilterPrimitive::new(offset(FilterInput::Previous)).with_result("a"))
+            .with_primitive(offset("a".into()));
+
+        let (_, plan) = graph.plan(Rect::new(0.0, 0.0, 10.0, 10.0));
+        let inputs: Vec<_> = plan.iter().map(|primitive| primitive.inputs.clone()).collect();
+        assert_eq!(
+            inputs,
+            vec![
+                vec![FilterSlot::SourceGraphic],
+                vec![FilterSlot::SourceAlpha],
+                vec![FilterSlot::Primitive(0), FilterSlot::Primitive(1)],
+                vec![FilterSlot::Primitive(2)],
+                vec![FilterSlot::Primitive(3)],
+            ]
+        );
+    }
+
+    #[test]
+    fn subregions_default_to_the_union_of_their_inputs() {
+        let graph = FilterGraph::new()
+            .with_region(-5.0, 2.0, 100.0, 100.0)
+            .with_primitive(FilterPrimitive::new(FilterEffect::Flood { color: Color::black() }).with_result("a"))
+            .with_primitive(
+                FilterPrimitive::new(FilterEffect::Flood { color: Color::black() })
+                    .with_subregion(1.0, 3.0, 2.0, 2.0)
+                    .with_result("b"),
+            )
+            .with_primitive(
+                FilterPrimitive::new(FilterEffect::Flood { color: Color::black() })
+                    .with_subregion(6.0, 8.0, 10.0, 10.0),
+            )
+            .with_primitive(FilterEffect::Merge {
+                inputs: vec!["b".into(), FilterInput::Previous],
+            })
+            .with_primitive(FilterEffect::Tile {
+                input: FilterInput::Previous,
+            })
+            .with_primitive(offset(FilterInput::SourceGraphic));
+
+        let (region, plan) = graph.plan(Rect::new(0.0, 0.0, 10.0, 10.0));
+        assert_eq!(region, Rect::new(0.0, 2.0, 10.0, 8.0));
+        let subregions: Vec<_> = plan.iter().map(|primitive| primitive.subregion).collect();
+        assert_eq!(
+            subregions,
+            vec![
+                region,
+                Rect::new(1.0, 3.0, 2.0, 2.0),
+                Rect::new(6.0, 8.0, 4.0, 2.0),
+                Rect::new(1.0, 3.0, 9.0, 7.0),
+                region,
+                region,
+            ]
+        );
+    }
+}
@@ -510,6 +510,23 @@ impl Rect {
     pub fn is_empty(&self) -> bool {
         self.w <= 0. || self.h <= 0.
     }
+
+    // The bounding box of both rectangles; empty rectangles are ignored.
+    pub fn union(&self, other: &Self) -> Self {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let w = (self.x + self.w).max(other.x + other.w) - x;
+        let h = (self.y + self.h).max(other.y + other.h) - y;
+
+        Self { x, y, w, h }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
@@ -335,7 +335,7 @@ impl<T> ImageStore<T> {
 }
 
 /// Specifies the type of filter to apply to images with `crate::Canvas::filter_image`.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 #[non_exhaustive]
 pub enum ImageFilter {
     /// Applies a Gaussian blur filter with the specified standard deviation.
@@ -357,6 +357,302 @@ pub enum ImageFilter {
         /// Row-major 4x5 color matrix.
         matrix: [f32; 20],
     },
+    /// Blends the source image over `backdrop` with a blend mode, the
+    /// operation behind SVG `feBlend` (the source is `in`, the backdrop `in2`).
+    ///
+    /// The backdrop must have the same size, format and flags as the source.
+    /// Colors are blended in sRGB space, as the CSS compositing spec defines.
+    Blend {
+        /// How the colors of the source and the backdrop are mixed.
+        mode: BlendMode,
+        /// The image the source is blended over.
+        backdrop: ImageId,
+    },
+    /// Combines the source image `i1` with `backdrop` as `k1 * i1 * i2 +
+    /// k2 * i1 + k3 * i2 + k4` per premultiplied channel, the `arithmetic`
+    /// operator of SVG `feComposite`. The result is clamped to valid colors.
+    ///
+    /// The backdrop must have the same size, format and flags as the source.
+    Arithmetic {
+        /// Weight of the product of both images.
+        k1: f32,
+        /// Weight of the source image.
+        k2: f32,
+        /// Weight of the backdrop.
+        k3: f32,
+        /// Constant added to every channel.
+        k4: f32,
+        /// The second input, `i2`.
+        backdrop: ImageId,
+    },
+    /// Thins or fattens the source image, the operation behind SVG
+    /// `feMorphology`: every output pixel is the per-channel minimum (erode)
+    /// or maximum (dilate) of the premultiplied source over a
+    /// `2 * radius_x + 1` by `2 * radius_y + 1` rectangle around it.
+    ///
+    /// Radii are rounded to whole pixels and, like the blur kernel, clamped
+    /// to 32 pixels; pixels outside the image count as transparent black.
+    Morphology {
+        /// Whether the filter thins (erodes) or fattens (dilates).
+        operator: MorphologyOperator,
+        /// Horizontal radius of the rectangle.
+        radius_x: f32,
+        /// Vertical radius of the rectangle.
+        radius_y: f32,
+    },
+    /// Convolves the source image with a kernel of up to 25 values, the
+    /// operation behind SVG `feConvolveMatrix` (sharpen, emboss, edge
+    /// detection, ...). See [`ImageFilter::convolve()`] for a constructor.
+    ///
+    /// As in SVG the kernel is rotated by 180 degrees when applied, and it is
+    /// centered on each pixel (the target is `size / 2`, rounded down). The
+    /// result is `sum / divisor + bias`, clamped to valid colors.
+    Convolve {
+        /// Row-major kernel values; only the first `columns * rows` are used.
+        kernel: [f32; 25],
+        /// Columns and rows of the kernel. Sizes with more than 25 values
+        /// render transparent black.
+        size: (usize, usize),
+        /// Divides the weighted sum. Zero selects the SVG default: the sum
+        /// of the kernel values, or 1 when that sum is zero.
+        divisor: f32,
+        /// Added to the result, scaled by the alpha of the source pixel.
+        bias: f32,
+        /// How pixels beyond the image edges are sampled.
+        edge_mode: EdgeMode,
+        /// Convolves only the color channels, in unpremultiplied space, and
+        /// keeps the source alpha.
+        preserve_alpha: bool,
+    },
+    /// Fills the target with Perlin noise, the operation behind SVG
+    /// `feTurbulence`. The source image only provides the size; it is not
+    /// sampled, but must still differ from the target.
+    ///
+    /// Noise is evaluated at the pixel coordinates of the target and comes
+    /// out upright for the target's orientation. Octaves beyond 10 are
+    /// dropped; they add less than 1/1024 to each channel.
+    Turbulence {
+        /// Whether the octaves are summed as turbulence or fractal noise.
+        kind: TurbulenceKind,
+        /// Horizontal and vertical frequency of the first octave, in cycles
+        /// per pixel. Negative values render transparent black.
+        base_frequency: (f32, f32),
+        /// Number of octaves summed, each at twice the frequency and half
+        /// the amplitude of the previous one.
+        octaves: u32,
+        /// Seeds the random lattice; truncated towards zero as in SVG.
+        seed: f32,
+        /// Adjusts the frequencies so that the noise tiles seamlessly across
+        /// the edges of the image.
+        stitch_tiles: bool,
+    },
+    /// Moves every pixel of the source image by an offset read from `map`,
+    /// the operation behind SVG `feDisplacementMap` (ripples, heat haze,
+    /// glass). The output pixel at `(x, y)` is the source pixel at
+    /// `(x + scale * (X - 0.5), y + scale * (Y - 0.5))`, where `X` and `Y`
+    /// are the selected unpremultiplied channels of the map at `(x, y)`.
+    ///
+    /// The map must have the same size, format and flags as the source.
+    /// Pixels displaced from outside the image are transparent black.
+    DisplacementMap {
+        /// The image the offsets are read from (SVG `in2`).
+        map: ImageId,
+        /// The largest displacement in pixels, reached at channel values of
+        /// 0 and 1.
+        scale: f32,
+        /// The channel of the map that moves pixels horizontally.
+        x_channel: ColorChannel,
+        /// The channel of the map that moves pixels vertically.
+        y_channel: ColorChannel,
+    },
+    /// Remaps each channel of the source image through its own transfer
+    /// function, the operation behind SVG `feComponentTransfer` (posterize,
+    /// threshold, duotone, gamma). Channels are remapped unpremultiplied.
+    ComponentTransfer {
+        /// The transfer functions of the four channels.
+        transfer: ComponentTransfer,
+    },
+}
+
+/// A transfer function of [`ComponentTransfer`], mapping a channel value `C`
+/// in `[0, 1]` to a new value. Results are clamped to `[0, 1]`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum TransferFunction {
+    /// Leaves the channel unchanged.
+    #[default]
+    Identity,
+    /// Interpolates linearly between evenly spaced values. An empty table
+    /// leaves the channel unchanged.
+    Table(Vec<f32>),
+    /// Steps through the values, each covering an even share of the range.
+    /// An empty table leaves the channel unchanged.
+    Discrete(Vec<f32>),
+    /// `slope * C + intercept`.
+    Linear {
+        /// Multiplies the channel.
+        slope: f32,
+        /// Added to the result.
+        intercept: f32,
+    },
+    /// `amplitude * C^exponent + offset`.
+    Gamma {
+        /// Multiplies the power.
+        amplitude: f32,
+        /// The exponent applied to the channel.
+        exponent: f32,
+        /// Added to the result.
+        offset: f32,
+    },
+}
+
+impl TransferFunction {
+    fn apply(&self, c: f32) -> f32 {
+        let value = match self {
+            Self::Identity => c,
+            Self::Table(values) | Self::Discrete(values) if values.is_empty() => c,
+            Self::Table(values) => {
+                let n = (values.len() - 1) as f32;
+                let k = ((c * n).floor() as usize).min(values.len() - 1);
+                match values.get(k + 1) {
+                    Some(next) => values[k] + (c * n - k as f32) * (next - values[k]),
+                    None => values[k],
+                }
+            }
+            Self::Discrete(values) => values[((c * values.len() as f32).floor() as usize).min(values.len() - 1)],
+            Self::Linear { slope, intercept } => slope * c + intercept,
+            Self::Gamma {
+                amplitude,
+                exponent,
+                offset,
+            } => amplitude * c.powf(*exponent) + offset,
+        };
+        value.clamp(0.0, 1.0)
+    }
+}
+
+/// The per-channel transfer functions of [`ImageFilter::ComponentTransfer`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ComponentTransfer {
+    /// Remaps the red channel.
+    pub red: TransferFunction,
+    /// Remaps the green channel.
+    pub green: TransferFunction,
+    /// Remaps the blue channel.
+    pub blue: TransferFunction,
+    /// Remaps the alpha channel.
+    pub alpha: TransferFunction,
+}
+
+impl ComponentTransfer {
+    /// Applies `function` to the red, green and blue channels and leaves
+    /// alpha unchanged.
+    pub fn rgb(function: TransferFunction) -> Self {
+        Self {
+            red: function.clone(),
+            green: function.clone(),
+            blue: function,
+            alpha: TransferFunction::Identity,
+        }
+    }
+
+    // The functions sampled at every 8-bit channel value: texel `x` holds the
+    // four channels remapped from `x / 255`.
+    pub(crate) fn lookup(&self) -> ImgVec<RGBA8> {
+        let functions = [&self.red, &self.green, &self.blue, &self.alpha];
+        let pixels = (0..256)
+            .map(|x| {
+                let [r, g, b, a] = functions.map(|function| (function.apply(x as f32 / 255.0) * 255.0).round() as u8);
+                RGBA8::new(r, g, b, a)
+            })
+            .collect();
+        ImgVec::new(pixels, 256, 1)
+    }
+}
+
+/// A color channel of an image, selected by [`ImageFilter::DisplacementMap`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum ColorChannel {
+    /// The red channel.
+    Red,
+    /// The green channel.
+    Green,
+    /// The blue channel.
+    Blue,
+    /// The alpha channel, the SVG default.
+    #[default]
+    Alpha,
+}
+
+/// How [`ImageFilter::Turbulence`] sums its octaves.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum TurbulenceKind {
+    /// Sums the absolute noise values, giving sharp-creased turbulence.
+    #[default]
+    Turbulence,
+    /// Sums the signed noise values around mid gray, giving smooth clouds.
+    FractalNoise,
+}
+
+/// How [`ImageFilter::Convolve`] extends the image beyond its edges.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum EdgeMode {
+    /// Repeats the nearest edge pixel.
+    #[default]
+    Duplicate,
+    /// Wraps around to the opposite edge.
+    Wrap,
+    /// Treats pixels outside the image as transparent black.
+    None,
+}
+
+/// Whether [`ImageFilter::Morphology`] thins or fattens the image.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum MorphologyOperator {
+    /// Takes the minimum, shrinking opaque shapes.
+    #[default]
+    Erode,
+    /// Takes the maximum, growing opaque shapes.
+    Dilate,
+}
+
+/// How [`ImageFilter::Blend`] mixes the colors of an image with its backdrop,
+/// following the blend modes of the CSS compositing spec (and SVG `feBlend`).
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum BlendMode {
+    /// The source color, i.e. plain source-over compositing.
+    #[default]
+    Normal,
+    /// Multiplies the colors, darkening the result.
+    Multiply,
+    /// Multiplies the complements of the colors, lightening the result.
+    Screen,
+    /// Multiplies or screens depending on the backdrop color.
+    Overlay,
+    /// The darker of the two colors, per channel.
+    Darken,
+    /// The lighter of the two colors, per channel.
+    Lighten,
+    /// Brightens the backdrop to reflect the source.
+    ColorDodge,
+    /// Darkens the backdrop to reflect the source.
+    ColorBurn,
+    /// Multiplies or screens depending on the source color.
+    HardLight,
+    /// Darkens or lightens depending on the source color, like a diffused
+    /// spotlight.
+    SoftLight,
+    /// The absolute difference of the colors.
+    Difference,
+    /// Like `Difference`, with lower contrast.
+    Exclusion,
+    /// The hue of the source with the saturation and luminosity of the backdrop.
+    Hue,
+    /// The saturation of the source with the hue and luminosity of the backdrop.
+    Saturation,
+    /// The hue and saturation of the source with the luminosity of the backdrop.
+    Color,
+    /// The luminosity of the source with the hue and saturation of the backdrop.
+    Luminosity,
 }
 
 impl ImageFilter {
@@ -374,6 +670,25 @@ impl ImageFilter {
     const LG: f32 = 0.7152;
     const LB: f32 = 0.0722;
 
+    /// A convolution with the SVG `feConvolveMatrix` defaults: the kernel's
+    /// sum as divisor, no bias, duplicated edges and convolved alpha.
+    /// `kernel` holds `columns * rows` row-major values; missing values are
+    /// zero and values beyond 25 are dropped.
+    pub fn convolve(kernel: &[f32], columns: usize, rows: usize) -> Self {
+        let mut values = [0.0; 25];
+        for (value, k) in values.iter_mut().zip(kernel) {
+            *value = *k;
+        }
+        Self::Convolve {
+            kernel: values,
+            size: (columns, rows),
+            divisor: 0.0,
+            bias: 0.0,
+            edge_mode: EdgeMode::Duplicate,
+            preserve_alpha: false,
+        }
+    }
+
     /// CSS `grayscale(amount)`; `amount` is clamped to `[0, 1]` (1 = fully gray).
     pub fn grayscale(amount: f32) -> Self {
         let a = amount.clamp(0.0, 1.0);
@@ -549,4 +864,277 @@ impl ImageFilter {
             ],
         }
     }
+
+    /// Parses a CSS `filter` property value such as
+    /// `"blur(4px) grayscale(50%) hue-rotate(90deg)"` into the filters it
+    /// applies, in order, for use with `crate::Canvas::set_filter`.
+    ///
+    /// `none` yields no filters. The color functions map onto the
+    /// constructors above and accept a number or a percentage (defaulting to
+    /// 1, or 0 for `hue-rotate`, which takes a `deg`, `rad`, `grad` or `turn`
+    /// angle). `blur()` takes a length in pixels, used as the standard
+    /// deviation of the Gaussian as in CSS. `drop-shadow()` and `url()`
+    /// references are not supported and are reported as errors.
+    pub fn parse_css(value: &str) -> Result<Vec<Self>, ErrorKind> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("none") {
+            return Ok(Vec::new());
+        }
+
+        let mut filters = Vec::new();
+        let mut rest = value;
+        while !rest.is_empty() {
+            let error = || ErrorKind::GeneralError(format!("Invalid CSS filter: {value:?}"));
+            let (name, tail) = rest.split_once('(').ok_or_else(error)?;
+            let (argument, tail) = tail.split_once(')').ok_or_else(error)?;
+            let name = name.trim().to_ascii_lowercase();
+            let argument = argument.trim();
+
+            let filter = match name.as_str() {
+                "blur" => Self::GaussianBlur {
+                    sigma: css_length(argument).ok_or_else(error)?,
+                },
+                "hue-rotate" => Self::hue_rotate(css_angle(argument).ok_or_else(error)?),
+                _ => {
+                    let constructor: fn(f32) -> Self = match name.as_str() {
+                        "grayscale" => Self::grayscale,
+                        "sepia" => Self::sepia,
+                        "saturate" => Self::saturate,
+                        "brightness" => Self::brightness,
+                        "contrast" => Self::contrast,
+                        "invert" => Self::invert,
+                        "opacity" => Self::opacity,
+                        _ => {
+                            return Err(ErrorKind::GeneralError(format!(
+                                "Unsupported CSS filter function: {name:?}"
+                            )))
+                        }
+                    };
+                    constructor(css_amount(argument).ok_or_else(error)?)
+                }
+            };
+
+            filters.push(filter);
+            rest = tail.trim_start();
+        }
+
+        Ok(filters)
+    }
+
+    // Whether the filter can run on an offscreen copy of the canvas. The second
+    // image of the two-input filters is sized for the user's images, not for
+    // the offscreen, and a blur under 0.01 spreads nothing but would compute
+    // NaN coefficients.
+    pub(crate) fn runs_offscreen(&self) -> bool {
+        match self {
+            Self::Blend { .. } | Self::Arithmetic { .. } | Self::DisplacementMap { .. } => false,
+            Self::GaussianBlur { sigma } => *sigma >= 0.01,
+            _ => true,
+        }
+    }
+
+    // How far, in pixels, the filter can move content: the padding an
+    // offscreen pass needs around a shape so nothing is cut off.
+    pub(crate) fn reach(&self) -> f32 {
+        match self {
+            Self::GaussianBlur { sigma } => (sigma * 3.0).ceil().max(0.0),
+            Self::Morphology { radius_x, radius_y, .. } => radius_x.max(*radius_y).round().clamp(0.0, 32.0),
+            Self::Convolve {
+                size: (columns, rows), ..
+            } => (*columns).max(*rows).min(25) as f32,
+            Self::DisplacementMap { scale, .. } => (scale.abs() / 2.0).ceil(),
+            _ => 0.0,
+        }
+    }
+
+    // Render targets store their rows bottom-up, so every pass over an image
+    // flips it vertically: single-pass filters return the source upside down,
+    // the two passes of the separable filters restore its orientation.
+    // Generators ignore the orientation of their source and always come out
+    // upright for their target.
+    pub(crate) fn flips_vertically(&self) -> bool {
+        !matches!(
+            self,
+            Self::GaussianBlur { .. } | Self::Morphology { .. } | Self::Turbulence { .. }
+        )
+    }
+
+    // The second image sampled by two-input filters.
+    pub(crate) fn backdrop(&self) -> Option<ImageId> {
+        match self {
+            Self::Blend { backdrop, .. }
+            | Self::Arithmetic { backdrop, .. }
+            | Self::DisplacementMap { map: backdrop, .. } => Some(*backdrop),
+            _ => None,
+        }
+    }
+}
+
+// A non-negative CSS `<number>` or `<percentage>`; empty means the default 1.
+fn css_amount(argument: &str) -> Option<f32> {
+    if argument.is_empty() {
+        return Some(1.0);
+    }
+    let amount = match argument.strip_suffix('%') {
+        Some(percentage) => percentage.parse::<f32>().ok()? / 100.0,
+        None => argument.parse().ok()?,
+    };
+    (amount.is_finite() && amount >= 0.0).then_some(amount)
+}
+
+// A non-negative CSS `<length>` in `px` (a bare zero is allowed); empty
+// means 0.
+fn css_length(argument: &str) -> Option<f32> {
+    if argument.is_empty() {
+        return Some(0.0);
+    }
+    let argument = argument.to_ascii_lowercase();
+    let length = match argument.strip_suffix("px") {
+        Some(pixels) => pixels.parse().ok()?,
+        None => argument.parse().ok().filter(|length: &f32| *length == 0.0)?,
+    };
+    (length.is_finite() && length >= 0.0).then_some(length)
+}
+
+// A CSS `<angle>` in radians (a bare zero is allowed); empty means 0.
+fn css_angle(argument: &str) -> Option<f32> {
+    if argument.is_empty() {
+        return Some(0.0);
+    }
+    let argument = argument.to_ascii_lowercase();
+    let units = [
+        ("grad", std::f32::consts::PI / 200.0),
+        ("turn", std::f32::consts::TAU),
+        ("deg", std::f32::consts::PI / 180.0),
+        ("rad", 1.0),
+    ];
+    let angle = match units
+        .iter()
+        .find_map(|(unit, factor)| Some((argument.strip_suffix(unit)?, factor)))
+    {
+        Some((value, factor)) => value.parse::<f32>().ok()? * factor,
+        None => argument.parse().ok().filter(|angle: &f32| *angle == 0.0)?,
+    };
+    angle.is_finite().then_some(angle)
+}
+
+// The random lattice of `feTurbulence` for `seed`, generated exactly like the
+// reference code of the Filter Effects spec. Row 0 holds the lattice selector
+// in the red channel, rows 1 to 4 the gradient vectors of the red, green, blue
+// and alpha noise, as two 16-bit fixed-point values in `[-1, 1]` (high byte
+// first). The shaders sample it in place of a glyph texture.
+pub(crate) fn turbulence_lattice(seed: f32) -> ImgVec<RGBA8> {
+    const BLOCK_SIZE: usize = 256;
+    const RAND_M: i64 = 2147483647;
+    const RAND_A: i64 = 16807;
+    const RAND_Q: i64 = 127773;
+    const RAND_R: i64 = 2836;
+
+    fn random(seed: i64) -> i64 {
+        let result = RAND_A * (seed % RAND_Q) - RAND_R * (seed / RAND_Q);
+        if result <= 0 {
+            result + RAND_M
+        } else {
+            result
+        }
+    }
+
+    let mut seed = seed as i64;
+    if seed <= 0 {
+        seed = -(seed % (RAND_M - 1)) + 1;
+    }
+    seed = seed.min(RAND_M - 1);
+
+    let mut lattice = [0usize; BLOCK_SIZE];
+    let mut gradients = [[[0.0f64; 2]; BLOCK_SIZE]; 4];
+    for channel in &mut gradients {
+        for (i, gradient) in channel.iter_mut().enumerate() {
+            lattice[i] = i;
+            for value in gradient.iter_mut() {
+                seed = random(seed);
+                *value = ((seed % (2 * BLOCK_SIZE as i64)) - BLOCK_SIZE as i64) as f64 / BLOCK_SIZE as f64;
+            }
+            let length = (gradient[0] * gradient[0] + gradient[1] * gradient[1]).sqrt();
+            // A zero vector stays zero rather than becoming NaN.
+            if length > 0.0 {
+                gradient[0] /= length;
+                gradient[1] /= length;
+            }
+        }
+    }
+    for i in (1..BLOCK_SIZE).rev() {
+        seed = random(seed);
+        lattice.swap(i, seed as usize % BLOCK_SIZE);
+    }
+
+    let encode = |value: f64| {
+        let fixed = ((value + 1.0) * 0.5 * 65535.0).round() as u16;
+        [(fixed >> 8) as u8, fixed as u8]
+    };
+    let mut pixels = Vec::with_capacity(BLOCK_SIZE * 5);
+    pixels.extend(lattice.iter().map(|&index| RGBA8::new(index as u8, 0, 0, 255)));
+    for channel in &gradients {
+        pixels.extend(channel.iter().map(|gradient| {
+            let ([x_hi, x_lo], [y_hi, y_lo]) = (encode(gradient[0]), encode(gradient[1]));
+            RGBA8::new(x_hi, x_lo, y_hi, y_lo)
+        }));
+    }
+    ImgVec::new(pixels, BLOCK_SIZE, 5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(filter: &ImageFilter) -> [f32; 20] {
+        match filter {
+            ImageFilter::ColorMatrix { matrix } => *matrix,
+            other => panic!("expected a color matrix, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn css_filters_map_onto_the_constructors() {
+        let filters =
+            ImageFilter::parse_css("blur(4PX) grayscale(50%)Sepia(1) hue-rotate(0.25turn) opacity()").unwrap();
+        assert_eq!(filters.len(), 5);
+        assert!(matches!(filters[0], ImageFilter::GaussianBlur { sigma } if sigma == 4.0));
+        assert_eq!(matrix(&filters[1]), matrix(&ImageFilter::grayscale(0.5)));
+        assert_eq!(matrix(&filters[2]), matrix(&ImageFilter::sepia(1.0)));
+        assert_eq!(
+            matrix(&filters[3]),
+            matrix(&ImageFilter::hue_rotate(std::f32::consts::FRAC_PI_2))
+        );
+        assert_eq!(matrix(&filters[4]), matrix(&ImageFilter::opacity(1.0)));
+
+        for (css, radians) in [
+            ("90deg", 90f32.to_radians()),
+            ("100grad", 1.5707964),
+            ("2rad", 2.0),
+            ("0", 0.0),
+        ] {
+            let filters = ImageFilter::parse_css(&format!("hue-rotate({css})")).unwrap();
+            assert_eq!(matrix(&filters[0]), matrix(&ImageFilter::hue_rotate(radians)), "{css}");
+        }
+
+        assert!(ImageFilter::parse_css(" none ").unwrap().is_empty());
+        assert!(ImageFilter::parse_css("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid_css_filters_are_rejected() {
+        for css in [
+            "blur(4)",
+            "blur(-1px)",
+            "brightness(-50%)",
+            "contrast(1px)",
+            "hue-rotate(90)",
+            "drop-shadow(1px 1px red)",
+            "url(#filter)",
+            "grayscale(1",
+            "grayscale",
+        ] {
+            assert!(ImageFilter::parse_css(css).is_err(), "{css}");
+        }
+    }
 }
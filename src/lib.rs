@@ -20,6 +20,7 @@ TODO:
 #[macro_use]
 extern crate serde;
 
+use std::hash::Hash;
 #[cfg(feature = "textlayout")]
 use std::ops::Range;
 use std::{cell::RefCell, path::Path as FilePath, rc::Rc};
@@ -33,22 +34,30 @@ mod error;
 pub use error::ErrorKind;
 
 pub use text::{
-    Align, Atlas, Baseline, DrawCommand, FontId, FontMetrics, GlyphDrawCommands, Quad, RenderMode, VariationAxisInfo,
+    Align, Atlas, Baseline, DrawCommand, FontId, FontInfo, FontMetrics, FontStyle, GlyphAtlasStats, GlyphDrawCommands,
+    NamedInstance, Quad, RenderMode, TextAntialiasing, TextJustify, TextPathSide, VariationAxisInfo, WritingMode,
 };
 
 pub use text::TextContext;
 #[cfg(feature = "textlayout")]
 pub use text::TextMetrics;
 
-use text::{GlyphAtlas, TextContextImpl};
+use text::{ColorGlyphStyle, GlyphAtlas, TextContextImpl};
 
 mod image;
 use crate::image::ImageStore;
-pub use crate::image::{ImageFilter, ImageFlags, ImageId, ImageInfo, ImageSource, PixelFormat};
+pub use crate::image::{
+    BlendMode, ColorChannel, ComponentTransfer, EdgeMode, ImageFilter, ImageFlags, ImageId, ImageInfo, ImageSource,
+    MorphologyOperator, PixelFormat, TransferFunction, TurbulenceKind,
+};
 
 mod color;
 pub use color::Color;
 
+mod filter;
+use filter::FilterSlot;
+pub use filter::{FilterCompositeOperator, FilterEffect, FilterGraph, FilterInput, FilterPrimitive};
+
 pub mod renderer;
 pub use renderer::{RenderTarget, Renderer};
 
@@ -59,8 +68,11 @@ pub use geometry::Transform2D;
 use geometry::*;
 
 mod paint;
+pub use paint::BoxShadow;
 pub use paint::Paint;
 pub use paint::TextDecoration;
+pub use paint::TextDecorationStyle;
+pub use paint::TextEffects;
 use paint::{GlyphTexture, PaintFlavor, StrokeSettings};
 
 mod path;
@@ -70,10 +82,13 @@ pub use path::{Path, PathIter, Solidity, Verb};
 mod gradient_store;
 use gradient_store::GradientStore;
 
+mod shadow_cache;
+use shadow_cache::{ShadowCache, ShadowKey};
+
 /// Determines the fill rule used when filling paths.
 ///
 /// The fill rule defines how the interior of a shape is determined.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum FillRule {
     /// The interior is determined using the even-odd rule.
@@ -239,7 +254,7 @@ impl Scissor {
 /// Determines the shape used to draw the end points of lines.
 ///
 /// The default value is `Butt`.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Default)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum LineCap {
     /// The ends of lines are squared off at the endpoints.
@@ -255,7 +270,7 @@ pub enum LineCap {
 /// Determines the shape used to join two line segments where they meet.
 ///
 /// The default value is `Miter`.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Default)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum LineJoin {
     /// Connected segments are joined by extending their outside edges to
@@ -273,7 +288,7 @@ pub enum LineJoin {
     Bevel,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 struct State {
     composite_operation: CompositeOperationState,
     transform: Transform2D,
@@ -285,6 +300,9 @@ struct State {
     shadow_color: Color,
     shadow_blur: f32,
     shadow_offset: [f32; 2],
+    // Filters every draw is rendered through, see `Canvas::set_filter`. `None`
+    // (the default) draws directly, without an offscreen pass.
+    filter: Option<Rc<[ImageFilter]>>,
 }
 
 impl Default for State {
@@ -299,6 +317,7 @@ impl Default for State {
             shadow_color: Color::rgbaf(0.0, 0.0, 0.0, 0.0),
             shadow_blur: 0.0,
             shadow_offset: [0.0, 0.0],
+            filter: None,
         }
     }
 }
@@ -313,6 +332,8 @@ pub struct Canvas<T: Renderer> {
     glyph_atlas: Rc<GlyphAtlas>,
     // Glyph atlas used for direct rendering of color glyphs, dropped after flush()
     ephemeral_glyph_atlas: Option<Rc<GlyphAtlas>>,
+    // Glyph atlas holding the distance fields of glyphs drawn in distance-field mode
+    distance_field_atlas: Rc<GlyphAtlas>,
     current_render_target: RenderTarget,
     state_stack: Vec<State>,
     commands: Vec<Command>,
@@ -323,42 +344,209 @@ pub struct Canvas<T: Renderer> {
     tess_tol: f32,
     dist_tol: f32,
     gradients: GradientStore,
-    // Transient offscreen images allocated for drop-shadow passes. They are
-    // referenced by deferred draw commands, so they can only be freed once those
-    // commands have been submitted to the renderer (i.e. after flush).
-    shadow_images: Vec<ImageId>,
+    // Transient offscreen images allocated for drop-shadow passes and filter
+    // graphs. They are referenced by deferred draw commands, so they can only be
+    // freed once those commands have been submitted to the renderer (i.e. after
+    // flush).
+    transient_images: Vec<ImageId>,
+    // Blurred drop-shadow images kept across frames for shapes drawn again
+    // with the same shadow.
+    shadow_cache: ShadowCache,
+}
+
+/// Builds the two triangles of each glyph quad, transformed into device space.
+fn glyph_quad_vertices(transform: &Transform2D, quads: &[text::Quad]) -> Vec<Vertex> {
+    let mut verts = Vec::with_capacity(quads.len() * 6);
+
+    for quad in quads {
+        let left = quad.x0;
+        let right = quad.x1;
+        let top = quad.y0;
+        let bottom = quad.y1;
+
+        let (p0, p1) = transform.transform_point(left, top);
+        let (p2, p3) = transform.transform_point(right, top);
+        let (p4, p5) = transform.transform_point(right, bottom);
+        let (p6, p7) = transform.transform_point(left, bottom);
+
+        verts.push(Vertex::new(p0, p1, quad.s0, quad.t0));
+        verts.push(Vertex::new(p4, p5, quad.s1, quad.t1));
+        verts.push(Vertex::new(p2, p3, quad.s1, quad.t0));
+        verts.push(Vertex::new(p0, p1, quad.s0, quad.t0));
+        verts.push(Vertex::new(p6, p7, quad.s0, quad.t1));
+        verts.push(Vertex::new(p4, p5, quad.s1, quad.t1));
+    }
+    verts
 }
 
-/// Returns the enabled text-decoration lines as `(offset, thickness)` pairs,
-/// where `offset` is the line center relative to the run baseline in +y-down
-/// user space. This is the single source of decoration geometry: the painter
-/// builds its rects from it and the shadow pass sizes its coverage box with it,
-/// so the two can never disagree.
+// Twice the signed area of a triangle of vertices.
+fn triangle_area(triangle: &[Vertex]) -> f32 {
+    let [a, b, c] = triangle else {
+        return 0.0;
+    };
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+/// One enabled text-decoration line of a run.
+#[cfg(feature = "textlayout")]
+#[derive(Copy, Clone, Debug)]
+struct DecorationLine {
+    /// Center of the line relative to the run baseline, +y down.
+    offset: f32,
+    thickness: f32,
+    /// Half the height the line's style covers around `offset`.
+    half_extent: f32,
+    /// Whether the line leaves gaps where glyphs cross it.
+    skip_ink: bool,
+}
+
+/// Returns the enabled text-decoration lines of a run. This is the single
+/// source of decoration geometry: the painter builds its shapes from it and the
+/// shadow pass sizes its coverage box with it, so the two can never disagree.
 ///
 /// OpenType position values measure from the baseline with +y pointing up,
 /// while canvas y grows downward, so a line's center is the negated position.
 /// The overline has no dedicated metric; it sits at the ascent with the
-/// underline's thickness, nudged up by half that thickness so it clears the
-/// glyphs. Thicknesses are clamped to one user-space unit so lines stay
-/// visible for tiny fonts.
+/// underline's thickness, nudged up so it clears the glyphs. Thicknesses from
+/// the font are clamped to one user-space unit so lines stay visible for tiny
+/// fonts; an explicit thickness is used as is.
 #[cfg(feature = "textlayout")]
-fn decoration_lines(decoration: TextDecoration, metrics: &FontMetrics) -> impl Iterator<Item = (f32, f32)> {
-    let underline_thickness = metrics.underline_thickness().max(1.0);
+fn decoration_lines(settings: &paint::TextSettings, metrics: &FontMetrics) -> impl Iterator<Item = DecorationLine> {
+    let decoration = settings.text_decoration;
+    let thickness = settings
+        .text_decoration_thickness
+        .filter(|thickness| thickness.is_finite() && *thickness > 0.0);
+    let underline_thickness = thickness.unwrap_or_else(|| metrics.underline_thickness().max(1.0));
+    let strikeout_thickness = thickness.unwrap_or_else(|| metrics.strikeout_thickness().max(1.0));
+
+    // Double lines and waves reach one and a half thicknesses from their center.
+    let half_extent = |thickness: f32| match settings.text_decoration_style {
+        TextDecorationStyle::Double | TextDecorationStyle::Wavy => thickness * 1.5,
+        _ => thickness / 2.0,
+    };
+    let line = |offset: f32, thickness: f32, skips_ink: bool| DecorationLine {
+        offset,
+        thickness,
+        half_extent: half_extent(thickness),
+        skip_ink: skips_ink && settings.text_decoration_skip_ink,
+    };
+
+    // An explicit underline offset places the top of the decoration. Styles
+    // taller than a solid line grow downwards, away from the glyphs.
+    let underline_offset = match settings.text_underline_offset.filter(|offset| offset.is_finite()) {
+        Some(offset) => offset + half_extent(underline_thickness),
+        None => -metrics.underline_position() + half_extent(underline_thickness) - underline_thickness / 2.0,
+    };
+
     [
         decoration
             .underline
-            .then(|| (-metrics.underline_position(), underline_thickness)),
+            .then(|| line(underline_offset, underline_thickness, true)),
         decoration
             .strikethrough
-            .then(|| (-metrics.strikeout_position(), metrics.strikeout_thickness().max(1.0))),
-        decoration
-            .overline
-            .then(|| (-metrics.ascender() - underline_thickness / 2.0, underline_thickness)),
+            .then(|| line(-metrics.strikeout_position(), strikeout_thickness, false)),
+        decoration.overline.then(|| {
+            line(
+                -metrics.ascender() - half_extent(underline_thickness),
+                underline_thickness,
+                true,
+            )
+        }),
     ]
     .into_iter()
     .flatten()
 }
 
+/// Returns the parts of `x0..x1` that none of `gaps` covers, in order.
+#[cfg(feature = "textlayout")]
+fn subtract_ranges(x0: f32, x1: f32, gaps: impl IntoIterator<Item = (f32, f32)>) -> Vec<(f32, f32)> {
+    let mut gaps = gaps.into_iter().collect::<Vec<_>>();
+    gaps.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut spans = Vec::new();
+    let mut start = x0;
+    for (gap_start, gap_end) in gaps {
+        if gap_start > start {
+            spans.push((start, gap_start.min(x1)));
+        }
+        start = start.max(gap_end);
+        if start >= x1 {
+            break;
+        }
+    }
+    if start < x1 {
+        spans.push((start, x1));
+    }
+
+    spans
+}
+
+/// Adds the shape of one decoration line spanning `x0..x1` to `path`. Dots,
+/// dashes and waves are laid out from `origin`, so a line that is interrupted
+/// to skip ink keeps its rhythm across the gaps.
+#[cfg(feature = "textlayout")]
+fn add_decoration_line(
+    path: &mut Path,
+    style: TextDecorationStyle,
+    origin: f32,
+    x0: f32,
+    x1: f32,
+    center: f32,
+    thickness: f32,
+) {
+    if x1 <= x0 {
+        return;
+    }
+
+    match style {
+        TextDecorationStyle::Solid => path.rect(x0, center - thickness / 2.0, x1 - x0, thickness),
+        TextDecorationStyle::Double => {
+            path.rect(x0, center - thickness * 1.5, x1 - x0, thickness);
+            path.rect(x0, center + thickness / 2.0, x1 - x0, thickness);
+        }
+        TextDecorationStyle::Dotted => {
+            // Round dots one thickness across, one thickness apart. Only
+            // whole dots are drawn.
+            let period = thickness * 2.0;
+            let mut left = origin + ((x0 - origin) / period).ceil() * period;
+            while left + thickness <= x1 {
+                path.circle(left + thickness / 2.0, center, thickness / 2.0);
+                left += period;
+            }
+        }
+        TextDecorationStyle::Dashed => {
+            let (dash, period) = (thickness * 3.0, thickness * 5.0);
+            let mut start = origin + ((x0 - origin) / period).floor() * period;
+            while start < x1 {
+                let (a, b) = (start.max(x0), (start + dash).min(x1));
+                if b > a {
+                    path.rect(a, center - thickness / 2.0, b - a, thickness);
+                }
+                start += period;
+            }
+        }
+        TextDecorationStyle::Wavy => {
+            // A sine wave one thickness high on either side of the center, four
+            // thicknesses long, filled as a ribbon of the line's thickness.
+            let wavelength = thickness * 4.0;
+            let wave = |x: f32| center + thickness * ((x - origin) / wavelength * std::f32::consts::TAU).sin();
+            let steps = ((x1 - x0) / wavelength * 16.0).ceil().max(1.0) as usize;
+            let x_at = |step: usize| x0 + (x1 - x0) * step as f32 / steps as f32;
+
+            path.move_to(x0, wave(x0) - thickness / 2.0);
+            for step in 1..=steps {
+                let x = x_at(step);
+                path.line_to(x, wave(x) - thickness / 2.0);
+            }
+            for step in (0..=steps).rev() {
+                let x = x_at(step);
+                path.line_to(x, wave(x) + thickness / 2.0);
+            }
+            path.close();
+        }
+    }
+}
+
 impl<T> Canvas<T>
 where
     T: Renderer,
@@ -367,6 +555,7 @@ where
     pub fn new(renderer: T) -> Result<Self, ErrorKind> {
         let text_context = Rc::new(RefCell::new(TextContextImpl::default()));
         let glyph_atlas = Rc::new(GlyphAtlas::new(&text_context));
+        let distance_field_atlas = Rc::new(GlyphAtlas::new_distance_field(&text_context));
         let mut canvas = Self {
             width: 0,
             height: 0,
@@ -374,6 +563,7 @@ where
             text_context,
             glyph_atlas,
             ephemeral_glyph_atlas: None,
+            distance_field_atlas,
             current_render_target: RenderTarget::Screen,
             state_stack: Vec::new(),
             commands: Vec::new(),
@@ -384,7 +574,8 @@ where
             tess_tol: 0.25,
             dist_tol: 0.01,
             gradients: GradientStore::new(),
-            shadow_images: Vec::new(),
+            transient_images: Vec::new(),
+            shadow_cache: ShadowCache::new(),
         };
 
         canvas.save();
@@ -397,6 +588,7 @@ where
     /// registered with a clone of this context will also be visible to this canvas.
     pub fn new_with_text_context(renderer: T, text_context: TextContext) -> Result<Self, ErrorKind> {
         let glyph_atlas = Rc::new(GlyphAtlas::new(&text_context.0));
+        let distance_field_atlas = Rc::new(GlyphAtlas::new_distance_field(&text_context.0));
         let mut canvas = Self {
             width: 0,
             height: 0,
@@ -404,6 +596,7 @@ where
             text_context: text_context.0,
             glyph_atlas,
             ephemeral_glyph_atlas: None,
+            distance_field_atlas,
             current_render_target: RenderTarget::Screen,
             state_stack: Vec::new(),
             commands: Vec::new(),
@@ -414,7 +607,8 @@ where
             tess_tol: 0.25,
             dist_tol: 0.01,
             gradients: GradientStore::new(),
-            shadow_images: Vec::new(),
+            transient_images: Vec::new(),
+            shadow_cache: ShadowCache::new(),
         };
 
         canvas.save();
@@ -495,10 +689,13 @@ where
         self.verts.clear();
         self.gradients
             .release_old_gradients(&mut self.images, &mut self.renderer);
-        self.release_shadow_images();
+        self.release_transient_images();
+        self.shadow_cache.evict(&mut self.images, &mut self.renderer);
         if let Some(atlas) = self.ephemeral_glyph_atlas.take() {
             atlas.clear(self);
         }
+        self.glyph_atlas.clone().end_frame(self);
+        self.distance_field_atlas.clone().end_frame(self);
         command_buffer
     }
 
@@ -513,7 +710,7 @@ where
     ///
     /// A matching `restore()` must be used to restore the state.
     pub fn save(&mut self) {
-        let state = self.state_stack.last().map_or_else(State::default, |state| *state);
+        let state = self.state_stack.last().map_or_else(State::default, State::clone);
 
         self.state_stack.push(state);
     }
@@ -564,16 +761,17 @@ where
     ///
     /// # Performance
     ///
-    /// Shadows are not cheap: every shadowed fill, stroke or text draw renders the
-    /// shape's coverage into a transient offscreen image sized to its padded
-    /// bounds, runs a two-pass Gaussian blur over it (when `shadowBlur` is
-    /// non-zero), and composites the result — per draw, every frame. Prefer
-    /// shadowing a few composed shapes over many small primitives, and if the
-    /// same shadowed shape is drawn every frame, consider rendering it once into
-    /// an [image](Self::create_image_empty) via
-    /// [`set_render_target`](Self::set_render_target) and re-drawing that cached
-    /// image instead. Setting a fully transparent shadow color restores the
-    /// zero-overhead path.
+    /// Shadows are not cheap: a shadowed fill, stroke or text draw renders the
+    /// shape's coverage into an offscreen image sized to its padded bounds, runs
+    /// a two-pass Gaussian blur over it (when `shadowBlur` is non-zero), and
+    /// composites the result. Rectangles and rounded rectangles filled with a
+    /// solid color skip the offscreen passes and are shadowed in closed form.
+    /// The blurred images of other paths are kept across frames (see
+    /// [`set_shadow_cache_limit`](Self::set_shadow_cache_limit)), so a path drawn
+    /// again with the same transform, paint and shadow only pays for the
+    /// composite; text shadows are rendered on every draw. Prefer shadowing a
+    /// few composed shapes over many small primitives. Setting a fully
+    /// transparent shadow color restores the zero-overhead path.
     pub fn set_shadow_color(&mut self, color: Color) {
         self.state_mut().shadow_color = color;
     }
@@ -611,6 +809,44 @@ where
         }
     }
 
+    /// Sets how many bytes of blurred drop-shadow images are kept across
+    /// frames, 32 MiB by default. Zero turns the cache off.
+    ///
+    /// The shadow of a filled or stroked path is looked up by the path's
+    /// outline, the transform, the paint, and the shadow blur and color. The
+    /// shadow offset, global alpha, scissor and composite operation only apply
+    /// to the composite and can change freely. Paths painted with an image are
+    /// not cached, since the image's pixels can change. Past the limit, the
+    /// least recently used images are released at the end of the frame.
+    pub fn set_shadow_cache_limit(&mut self, bytes: usize) {
+        self.shadow_cache.set_limit(bytes);
+    }
+
+    /// Sets the filters every subsequent fill, stroke and text draw is rendered
+    /// through, like the Canvas 2D `filter` attribute. An empty slice (the
+    /// default) turns filtering off. See [`ImageFilter::parse_css()`] to build
+    /// the list from CSS filter syntax.
+    ///
+    /// Each draw is rendered into a transient offscreen image padded by the
+    /// reach of the filters, the filters are applied in order, and the result
+    /// is composited into the current render target with the global alpha,
+    /// scissor, composite operation and shadow, in that order per the drawing
+    /// model. Lengths such as the blur deviation are in device pixels.
+    ///
+    /// Filters that sample a second image (`Blend`, `Arithmetic` and
+    /// `DisplacementMap`) cannot line up with the per-draw offscreen image and
+    /// are skipped; use [`Self::filter_image()`] for those. Blurs with a
+    /// deviation under 0.01, such as `blur(0px)`, have no effect and are
+    /// skipped too.
+    ///
+    /// # Performance
+    ///
+    /// Like shadows, this costs an offscreen image and one filter pass per
+    /// filter for every draw, every frame.
+    pub fn set_filter(&mut self, filters: &[ImageFilter]) {
+        self.state_mut().filter = (!filters.is_empty()).then(|| filters.into());
+    }
+
     /// Sets the composite operation.
     pub fn global_composite_operation(&mut self, op: CompositeOperation) {
         self.state_mut().composite_operation = CompositeOperationState::new(op);
@@ -798,7 +1034,9 @@ where
     /// as a drawing command and run by the renderer when [`Self::flush()`] is called.
     ///
     /// The filtering does not take any transformation set on the Canvas into account nor does it
-    /// change the current rendering target.
+    /// change the current rendering target. [`ImageFilter::Turbulence`] uploads a small random
+    /// lattice for its seed, and [`ImageFilter::ComponentTransfer`] a lookup table of its
+    /// transfer functions, which are freed after the flush.
     pub fn filter_image(&mut self, target_image: ImageId, filter: ImageFilter, source_image: ImageId) {
         let Ok((image_width, image_height)) = self.image_size(source_image) else {
             return;
@@ -806,9 +1044,35 @@ where
 
         // The renderer will receive a RenderFilteredImage command with two triangles attached that
         // cover the image and the source image.
+        // Two-input filters sample their backdrop, the noise generator its random lattice and
+        // the component transfer its lookup table from the glyph texture slot.
+        let table = match &filter {
+            ImageFilter::Turbulence { seed, .. } => Some(image::turbulence_lattice(*seed)),
+            ImageFilter::ComponentTransfer { transfer } => Some(transfer.lookup()),
+            _ => None,
+        };
+        let second_texture = match table {
+            Some(table) => {
+                let Ok(table) = self.create_image(table.as_ref(), ImageFlags::NEAREST) else {
+                    return;
+                };
+                self.transient_images.push(table);
+                Some(table)
+            }
+            None => filter.backdrop(),
+        };
+
         let mut cmd = Command::new(CommandType::RenderFilteredImage { target_image, filter });
         cmd.image = Some(source_image);
+        cmd.glyph_texture = second_texture.map_or(GlyphTexture::None, GlyphTexture::FilterBackdrop);
+        cmd.triangles_verts = Some(self.push_image_quad(image_width, image_height));
+
+        self.append_cmd(cmd)
+    }
 
+    // Pushes the two triangles that image passes draw: they cover the target
+    // image and map the source image onto it.
+    fn push_image_quad(&mut self, image_width: usize, image_height: usize) -> (usize, usize) {
         let vertex_offset = self.verts.len();
 
         let image_width = image_width as f32;
@@ -831,9 +1095,418 @@ where
         self.verts.push(Vertex::new(quad_x0, quad_y1, texture_x0, texture_y1));
         self.verts.push(Vertex::new(quad_x1, quad_y1, texture_x1, texture_y1));
 
-        cmd.triangles_verts = Some((vertex_offset, 6));
+        (vertex_offset, 6)
+    }
 
-        self.append_cmd(cmd)
+    /// Filters what is already drawn under `path` and paints the result back
+    /// inside it, like the CSS `backdrop-filter` property: a
+    /// [`ImageFilter::GaussianBlur`] gives the frosted glass look. Draw the
+    /// panel itself on top afterwards.
+    ///
+    /// The region of the current render target (the screen or an image) under
+    /// the path, widened by the reach of the filter so a blur takes in its
+    /// surroundings, is copied into a transient image and filtered like with
+    /// [`Self::filter_image()`]. The result is then filled into the path with
+    /// the current transform, scissor, global alpha and composite operation.
+    ///
+    /// Two-input filters ([`ImageFilter::Blend`], [`ImageFilter::Arithmetic`]
+    /// and [`ImageFilter::DisplacementMap`]) and blurs with a sigma under 0.01
+    /// are ignored, as is everything with the wgpu renderer when the output
+    /// texture lacks the `COPY_SRC` usage needed to read the screen.
+    pub fn backdrop_filter(&mut self, path: &Path, filter: ImageFilter) {
+        if !filter.runs_offscreen() {
+            return;
+        }
+
+        let transform = self.state().transform;
+        let bounds = {
+            let cache = path.cache(&transform, self.tess_tol, self.dist_tol);
+            cache.bounds
+        };
+
+        let reach = filter.reach() + self.fringe_width;
+        let minx = (bounds.minx - reach).floor().max(0.0);
+        let miny = (bounds.miny - reach).floor().max(0.0);
+        let maxx = (bounds.maxx + reach).ceil().min(self.width() as f32);
+        let maxy = (bounds.maxy + reach).ceil().min(self.height() as f32);
+
+        // Empty or off-target paths have no backdrop.
+        if maxx <= minx || maxy <= miny {
+            return;
+        }
+
+        let width = (maxx - minx) as usize;
+        let height = (maxy - miny) as usize;
+
+        // The copy arrives bottom-up, like any render target image.
+        let backdrop_flags = ImageFlags::PREMULTIPLIED | ImageFlags::FLIP_Y;
+        let filtered_flags = if filter.flips_vertically() {
+            backdrop_flags.symmetric_difference(ImageFlags::FLIP_Y)
+        } else {
+            backdrop_flags
+        };
+        let Ok(backdrop) = self.create_transient_image(width, height, backdrop_flags) else {
+            return;
+        };
+        let Ok(filtered) = self.create_transient_image(width, height, filtered_flags) else {
+            return;
+        };
+
+        let mut cmd = Command::new(CommandType::CopyRenderTarget {
+            target_image: backdrop,
+            x: minx as u32,
+            y: miny as u32,
+        });
+        cmd.triangles_verts = Some(self.push_image_quad(width, height));
+        self.append_cmd(cmd);
+
+        self.filter_image(filtered, filter, backdrop);
+
+        // The filtered region sits in device space, so fill the path there
+        // too, whatever the transform.
+        let mut device_path = Path::new();
+        device_path.append_path(path, &transform);
+        let paint = Paint::image(filtered, minx, miny, width as f32, height as f32, 0.0, 1.0);
+
+        self.save();
+        self.state_mut().transform = Transform2D::identity();
+        self.state_mut().shadow_color = Color::rgbaf(0.0, 0.0, 0.0, 0.0);
+        self.state_mut().filter = None;
+        self.fill_path_internal(&device_path, &paint.flavor, true, FillRule::NonZero);
+        self.restore();
+    }
+
+    /// Renders `source_image` through a declarative filter graph into `target_image`, the
+    /// equivalent of an SVG `<filter>` element.
+    ///
+    /// Intermediate results live in transient images that are freed after the next flush. The
+    /// target image must have the same size as the source image. It receives the output in render
+    /// target orientation, so create it with [`ImageFlags::FLIP_Y`] (and
+    /// [`ImageFlags::PREMULTIPLIED`]) to draw it upright. Like [`Self::filter_image()`], the graph
+    /// ignores the Canvas transformation, scissor and composite operation and leaves the current
+    /// rendering target unchanged.
+    ///
+    /// Fails without recording any drawing into the target when an image does not exist, the sizes
+    /// differ or an intermediate image cannot be allocated.
+    pub fn filter_image_graph(
+        &mut self,
+        target_image: ImageId,
+        graph: &FilterGraph,
+        source_image: ImageId,
+    ) -> Result<(), ErrorKind> {
+        let (width, height) = self.image_size(source_image)?;
+        if self.image_size(target_image)? != (width, height) {
+            return Err(ErrorKind::GeneralError(
+                "filter graph source and target images differ in size".to_string(),
+            ));
+        }
+
+        let (region, plan) = graph.plan(Rect::new(0.0, 0.0, width as f32, height as f32));
+
+        let previous_target = self.current_render_target;
+        self.save();
+        self.state_mut().transform = Transform2D::identity();
+        self.state_mut().alpha = 1.0;
+        self.state_mut().scissor = Scissor::default();
+        self.state_mut().shadow_color = Color::rgbaf(0.0, 0.0, 0.0, 0.0);
+
+        let result = self.run_filter_plan(&plan, region, source_image, width, height);
+
+        if let Ok(output) = result {
+            self.set_render_target(RenderTarget::Image(target_image));
+            self.clear_rect(0, 0, width as u32, height as u32, Color::rgbaf(0.0, 0.0, 0.0, 0.0));
+            if let Some(output) = output {
+                self.draw_filter_image(output, 0.0, 0.0, region, CompositeOperation::Copy);
+            }
+        }
+
+        self.restore();
+        self.set_render_target(previous_target);
+
+        result.map(|_| ())
+    }
+
+    // Runs the primitives of a filter graph, each into its own transient image,
+    // and returns the image holding the output (None for an empty graph).
+    fn run_filter_plan(
+        &mut self,
+        plan: &[filter::PlannedPrimitive<'_>],
+        region: Rect,
+        source_image: ImageId,
+        width: usize,
+        height: usize,
+    ) -> Result<Option<ImageId>, ErrorKind> {
+        // Intermediates are render targets, so they hold premultiplied colors
+        // stored bottom-up (see render_shadow).
+        let image_flags = ImageFlags::PREMULTIPLIED | ImageFlags::FLIP_Y;
+        let transparent = Color::rgbaf(0.0, 0.0, 0.0, 0.0);
+
+        let mut source_graphic = None;
+        let mut source_alpha = None;
+        let mut results: Vec<(ImageId, Rect)> = Vec::with_capacity(plan.len());
+
+        for primitive in plan {
+            let mut inputs = Vec::with_capacity(primitive.inputs.len());
+            for slot in &primitive.inputs {
+                let image = match *slot {
+                    FilterSlot::Primitive(index) => results[index].0,
+                    FilterSlot::SourceGraphic => match source_graphic {
+                        Some(image) => image,
+                        None => {
+                            let image = self.create_transient_image(width, height, image_flags)?;
+                            self.set_render_target(RenderTarget::Image(image));
+                            self.clear_rect(0, 0, width as u32, height as u32, transparent);
+                            self.draw_filter_image(source_image, 0.0, 0.0, region, CompositeOperation::Copy);
+                            *source_graphic.insert(image)
+                        }
+                    },
+                    FilterSlot::SourceAlpha => match source_alpha {
+                        Some(image) => image,
+                        None => {
+                            let image = self.create_transient_image(width, height, image_flags)?;
+                            self.set_render_target(RenderTarget::Image(image));
+                            self.clear_rect(0, 0, width as u32, height as u32, transparent);
+                            self.fill_filter_rect(region, Color::black());
+                            self.draw_filter_image(source_image, 0.0, 0.0, region, CompositeOperation::DestinationIn);
+                            *source_alpha.insert(image)
+                        }
+                    },
+                };
+                inputs.push(image);
+            }
+
+            let subregion = primitive.subregion;
+
+            // Effects implemented by an image filter run it into a scratch
+            // image first, the result is then clipped to the subregion.
+            let filtered = match *primitive.effect {
+                FilterEffect::GaussianBlur { std_deviation, .. } if std_deviation >= 0.01 => {
+                    Some(ImageFilter::GaussianBlur { sigma: std_deviation })
+                }
+                FilterEffect::ColorMatrix { matrix, .. } => Some(ImageFilter::ColorMatrix { matrix }),
+                FilterEffect::Convolve {
+                    kernel,
+                    size,
+                    divisor,
+                    bias,
+                    edge_mode,
+                    preserve_alpha,
+                    ..
+                } => Some(ImageFilter::Convolve {
+                    kernel,
+                    size,
+                    divisor,
+                    bias,
+                    edge_mode,
+                    preserve_alpha,
+                }),
+                FilterEffect::Morphology {
+                    operator,
+                    radius_x,
+                    radius_y,
+                    ..
+                } if radius_x.min(radius_y) >= 0.0 && radius_x.max(radius_y) > 0.0 => Some(ImageFilter::Morphology {
+                    operator,
+                    radius_x,
+                    radius_y,
+                }),
+                FilterEffect::Turbulence {
+                    kind,
+                    base_frequency,
+                    octaves,
+                    seed,
+                    stitch_tiles,
+                } => Some(ImageFilter::Turbulence {
+                    kind,
+                    base_frequency,
+                    octaves,
+                    seed,
+                    stitch_tiles,
+                }),
+                FilterEffect::Composite {
+                    operator: FilterCompositeOperator::Arithmetic { k1, k2, k3, k4 },
+                    ..
+                } => Some(ImageFilter::Arithmetic {
+                    k1,
+                    k2,
+                    k3,
+                    k4,
+                    backdrop: inputs[1],
+                }),
+                FilterEffect::Blend { mode, .. } => Some(ImageFilter::Blend {
+                    mode,
+                    backdrop: inputs[1],
+                }),
+                FilterEffect::ComponentTransfer { ref transfer, .. } => Some(ImageFilter::ComponentTransfer {
+                    transfer: transfer.clone(),
+                }),
+                FilterEffect::DisplacementMap {
+                    scale,
+                    x_channel,
+                    y_channel,
+                    ..
+                } => Some(ImageFilter::DisplacementMap {
+                    map: inputs[1],
+                    scale,
+                    x_channel,
+                    y_channel,
+                }),
+                _ => None,
+            };
+            let filtered = match filtered {
+                Some(filter) => {
+                    // A single pass hands back the image upside down, which
+                    // is the upright orientation of a plain image.
+                    let flags = if filter.flips_vertically() {
+                        ImageFlags::PREMULTIPLIED
+                    } else {
+                        image_flags
+                    };
+                    let scratch = self.create_transient_image(width, height, flags)?;
+                    // Generators read no input; the source only sizes the pass.
+                    self.filter_image(scratch, filter, inputs.first().copied().unwrap_or(source_image));
+                    Some(scratch)
+                }
+                None => None,
+            };
+
+            // The tile is snapped to whole pixels so the pattern repeats
+            // without seams.
+            let tile = match primitive.effect {
+                FilterEffect::Tile { .. } => {
+                    let tile_rect = match primitive.inputs[0] {
+                        FilterSlot::Primitive(index) => results[index].1,
+                        _ => region,
+                    };
+                    let x = tile_rect.x.floor();
+                    let y = tile_rect.y.floor();
+                    let w = (tile_rect.x + tile_rect.w).ceil() - x;
+                    let h = (tile_rect.y + tile_rect.h).ceil() - y;
+                    if w >= 1.0 && h >= 1.0 {
+                        let tile_flags = image_flags | ImageFlags::REPEAT_X | ImageFlags::REPEAT_Y;
+                        let tile = self.create_transient_image(w as usize, h as usize, tile_flags)?;
+                        self.set_render_target(RenderTarget::Image(tile));
+                        self.clear_rect(0, 0, w as u32, h as u32, transparent);
+                        self.draw_filter_image(inputs[0], -x, -y, Rect::new(0.0, 0.0, w, h), CompositeOperation::Copy);
+                        Some((tile, Rect::new(x, y, w, h)))
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            };
+
+            let output = self.create_transient_image(width, height, image_flags)?;
+            self.set_render_target(RenderTarget::Image(output));
+            self.clear_rect(0, 0, width as u32, height as u32, transparent);
+
+            if !subregion.is_empty() {
+                match (primitive.effect, filtered) {
+                    (_, Some(filtered)) => {
+                        self.draw_filter_image(filtered, 0.0, 0.0, subregion, CompositeOperation::Copy);
+                    }
+                    (
+                        FilterEffect::GaussianBlur { .. }
+                        | FilterEffect::ColorMatrix { .. }
+                        | FilterEffect::Morphology { .. },
+                        None,
+                    ) => {
+                        self.draw_filter_image(inputs[0], 0.0, 0.0, subregion, CompositeOperation::Copy);
+                    }
+                    (FilterEffect::Offset { dx, dy, .. }, None) => {
+                        // Only the shifted image itself is drawn, the sampler
+                        // would otherwise smear its edges over the gap.
+                        let shifted = Rect::new(*dx, *dy, width as f32, height as f32);
+                        self.draw_filter_image(
+                            inputs[0],
+                            *dx,
+                            *dy,
+                            subregion.intersect(shifted),
+                            CompositeOperation::Copy,
+                        );
+                    }
+                    (FilterEffect::Flood { color }, None) => self.fill_filter_rect(subregion, *color),
+                    (FilterEffect::Merge { .. }, None) => {
+                        for image in &inputs {
+                            self.draw_filter_image(*image, 0.0, 0.0, subregion, CompositeOperation::SourceOver);
+                        }
+                    }
+                    (FilterEffect::Composite { operator, .. }, None) => {
+                        let operation = match operator {
+                            FilterCompositeOperator::Over | FilterCompositeOperator::Arithmetic { .. } => {
+                                CompositeOperation::SourceOver
+                            }
+                            FilterCompositeOperator::In => CompositeOperation::SourceIn,
+                            FilterCompositeOperator::Out => CompositeOperation::SourceOut,
+                            FilterCompositeOperator::Atop => CompositeOperation::Atop,
+                            FilterCompositeOperator::Xor => CompositeOperation::Xor,
+                            FilterCompositeOperator::Lighter => CompositeOperation::Lighter,
+                        };
+                        self.draw_filter_image(inputs[1], 0.0, 0.0, subregion, CompositeOperation::Copy);
+                        self.draw_filter_image(inputs[0], 0.0, 0.0, subregion, operation);
+                    }
+                    (FilterEffect::Tile { .. }, None) => {
+                        if let Some((tile, tile_rect)) = tile {
+                            self.state_mut().composite_operation =
+                                CompositeOperationState::new(CompositeOperation::Copy);
+                            let mut paint =
+                                Paint::image(tile, tile_rect.x, tile_rect.y, tile_rect.w, tile_rect.h, 0.0, 1.0);
+                            paint.set_anti_alias(false);
+                            let mut path = Path::new();
+                            path.rect(subregion.x, subregion.y, subregion.w, subregion.h);
+                            self.fill_path_internal(&path, &paint.flavor, false, FillRule::NonZero);
+                        }
+                    }
+                    (
+                        FilterEffect::Blend { .. }
+                        | FilterEffect::Convolve { .. }
+                        | FilterEffect::ComponentTransfer { .. }
+                        | FilterEffect::Turbulence { .. }
+                        | FilterEffect::DisplacementMap { .. },
+                        None,
+                    ) => {
+                        unreachable!("these effects always run their filter")
+                    }
+                }
+            }
+
+            results.push((output, subregion));
+        }
+
+        Ok(results.last().map(|(image, _)| *image))
+    }
+
+    // Allocates an offscreen image that is freed after the next flush.
+    fn create_transient_image(&mut self, width: usize, height: usize, flags: ImageFlags) -> Result<ImageId, ErrorKind> {
+        let image = self.create_image_empty(width, height, PixelFormat::Rgba8, flags)?;
+        self.transient_images.push(image);
+        Ok(image)
+    }
+
+    // Draws the part of `image` inside `rect` into the current render target,
+    // with the image's origin at (x, y), in device pixels.
+    fn draw_filter_image(&mut self, image: ImageId, x: f32, y: f32, rect: Rect, operation: CompositeOperation) {
+        let Ok((width, height)) = self.image_size(image) else {
+            return;
+        };
+        if rect.is_empty() {
+            return;
+        }
+
+        self.state_mut().composite_operation = CompositeOperationState::new(operation);
+        let mut paint = Paint::image(image, x, y, width as f32, height as f32, 0.0, 1.0);
+        paint.set_anti_alias(false);
+        let mut path = Path::new();
+        path.rect(rect.x, rect.y, rect.w, rect.h);
+        self.fill_path_internal(&path, &paint.flavor, false, FillRule::NonZero);
+    }
+
+    // Fills `rect` of the current render target with a color, in device pixels.
+    fn fill_filter_rect(&mut self, rect: Rect, color: Color) {
+        self.state_mut().composite_operation = CompositeOperationState::new(CompositeOperation::Copy);
+        let mut path = Path::new();
+        path.rect(rect.x, rect.y, rect.w, rect.h);
+        self.fill_path_internal(&path, &PaintFlavor::Color(color), false, FillRule::NonZero);
     }
 
     // Transforms
@@ -1050,13 +1723,114 @@ where
         self.fill_path_internal(path, &paint.flavor, paint.shape_anti_alias, paint.fill_rule);
     }
 
+    /// Draws a CSS-style box shadow for the rounded rectangle at (`x`, `y`)
+    /// with size `width` x `height` and corner `radius`, in user space.
+    ///
+    /// The blurred shape is computed in closed form rather than rendered and
+    /// blurred offscreen, so this is cheap enough to call for every card in a
+    /// long list. An outer shadow is only painted outside the box and an inset
+    /// one only inside it, so the box can be drawn before or after. The
+    /// canvas shadow is not applied to it.
+    pub fn draw_box_shadow(&mut self, x: f32, y: f32, width: f32, height: f32, radius: f32, shadow: &BoxShadow) {
+        let (x, y) = (x.min(x + width), y.min(y + height));
+        let (width, height) = (width.abs(), height.abs());
+        let radius = radius.max(0.0).min(width.min(height) * 0.5);
+
+        // An inset shadow surrounds the box shrunk by the spread. A square
+        // corner stays square, as in CSS.
+        let spread = if shadow.inset { -shadow.spread } else { shadow.spread };
+        let shadow_width = (width + spread * 2.0).max(0.0);
+        let shadow_height = (height + spread * 2.0).max(0.0);
+        let shadow_x = x + shadow.offset_x + (width - shadow_width) * 0.5;
+        let shadow_y = y + shadow.offset_y + (height - shadow_height) * 0.5;
+        let shadow_radius = if radius > 0.0 { (radius + spread).max(0.0) } else { 0.0 };
+
+        let sigma = shadow.blur.max(0.0) / 2.0;
+        let flavor = PaintFlavor::BoxShadow {
+            pos: Position {
+                x: shadow_x,
+                y: shadow_y,
+            },
+            width: shadow_width,
+            height: shadow_height,
+            radius: shadow_radius,
+            sigma,
+            color: shadow.color,
+            inset: shadow.inset,
+        };
+
+        let mut path = Path::new();
+        let fill_rule = if shadow.inset {
+            path.rounded_rect(x, y, width, height, radius);
+            FillRule::NonZero
+        } else {
+            // The box is cut out of a rectangle covering both it and the
+            // blurred shadow, 3 sigma and a pixel out.
+            let reach = sigma * 3.0 + 1.0;
+            let minx = (shadow_x - reach).min(x);
+            let miny = (shadow_y - reach).min(y);
+            let maxx = (shadow_x + shadow_width + reach).max(x + width);
+            let maxy = (shadow_y + shadow_height + reach).max(y + height);
+            path.rect(minx, miny, maxx - minx, maxy - miny);
+            path.rounded_rect(x, y, width, height, radius);
+            FillRule::EvenOdd
+        };
+
+        let shadow_color = self.state().shadow_color;
+        self.state_mut().shadow_color = Color::rgbaf(0.0, 0.0, 0.0, 0.0);
+        self.fill_path_internal(&path, &flavor, true, fill_rule);
+        self.state_mut().shadow_color = shadow_color;
+    }
+
     fn fill_path_internal(&mut self, path: &Path, paint_flavor: &PaintFlavor, anti_alias: bool, fill_rule: FillRule) {
+        self.fill_path_grown(path, paint_flavor, anti_alias, fill_rule, None);
+    }
+
+    // Fills `path`, grown by half of `pen`'s width all around when given, as
+    // if the pen had stroked its outline too. The grown shape is covered in a
+    // single pass, so a translucent paint is blended only once.
+    fn fill_path_grown(
+        &mut self,
+        path: &Path,
+        paint_flavor: &PaintFlavor,
+        anti_alias: bool,
+        fill_rule: FillRule,
+        pen: Option<&StrokeSettings>,
+    ) {
         let mut paint_flavor = paint_flavor.clone();
         let transform = self.state().transform;
 
+        // The pen's width in device pixels. Like thin strokes, pens narrower
+        // than the antialiasing fringe are widened to it, so that the edges
+        // they leave still ramp down from full coverage.
+        let fringe_width = if anti_alias { self.fringe_width } else { 0.0 };
+        let pen_width = pen.map_or(0.0, |pen| {
+            (pen.line_width * transform.average_scale()).max(fringe_width)
+        });
+        let grow = pen_width * 0.5;
+
         let canvas_width = self.width();
         let canvas_height = self.height();
 
+        // Filtered fills re-enter with the filter off inside an offscreen pass,
+        // whose composite also casts the shadow (of the filtered result).
+        if self.filter_enabled() {
+            let mut bounds = {
+                let cache = path.cache(&transform, self.tess_tol, self.dist_tol);
+                cache.bounds
+            };
+            bounds.minx -= self.fringe_width + grow;
+            bounds.miny -= self.fringe_width + grow;
+            bounds.maxx += self.fringe_width + grow;
+            bounds.maxy += self.fringe_width + grow;
+            let path = path.clone();
+            let pen = pen.cloned();
+            self.render_filtered(bounds, move |canvas| {
+                canvas.fill_path_grown(&path, &paint_flavor, anti_alias, fill_rule, pen.as_ref());
+            });
+            return;
+        }
+
         // Draw the drop shadow (if any) under the fill. The closure re-enters
         // fill_path_internal with the *real* paint so render_shadow can build the
         // shadow from the source's true per-pixel alpha; render_shadow temporarily
@@ -1064,17 +1838,35 @@ where
         // own scope so the path cache's RefMut borrow is released before the path
         // is cloned (cloning a Path while its cache is borrowed would panic).
         if self.shadow_enabled() {
-            let bounds = {
+            let mut bounds = {
                 let cache = path.cache(&transform, self.tess_tol, self.dist_tol);
                 cache.bounds
             };
+            bounds.minx -= grow;
+            bounds.miny -= grow;
+            bounds.maxx += grow;
+            bounds.maxy += grow;
             // Only skip when even the offset+blurred shadow cannot reach the
             // target; an off-screen shape may still cast an on-screen shadow.
-            if self.shadow_could_be_visible(bounds) {
+            // Solid rounded rectangles are shadowed in closed form instead.
+            if self.shadow_could_be_visible(bounds)
+                && (pen.is_some() || !self.render_analytic_shadow(path, &paint_flavor, anti_alias))
+            {
+                let cache_key = self.shadow_cache_key(&paint_flavor, |hasher| {
+                    path.hash_outline(hasher);
+                    anti_alias.hash(hasher);
+                    fill_rule.hash(hasher);
+                    if let Some(pen) = pen {
+                        pen.line_width.to_bits().hash(hasher);
+                        pen.line_join.hash(hasher);
+                        pen.miter_limit.to_bits().hash(hasher);
+                    }
+                });
                 let path = path.clone();
+                let pen = pen.cloned();
                 let shadow_flavor = paint_flavor.clone();
-                self.render_shadow(bounds, move |canvas| {
-                    canvas.fill_path_internal(&path, &shadow_flavor, anti_alias, fill_rule);
+                self.render_shadow(bounds, cache_key, move |canvas| {
+                    canvas.fill_path_grown(&path, &shadow_flavor, anti_alias, fill_rule, pen.as_ref());
                 });
             }
         }
@@ -1083,10 +1875,10 @@ where
         let mut path_cache = path.cache(&transform, self.tess_tol, self.dist_tol);
 
         // Early out if path is outside the canvas bounds
-        if path_cache.bounds.maxx < 0.0
-            || path_cache.bounds.minx > canvas_width as f32
-            || path_cache.bounds.maxy < 0.0
-            || path_cache.bounds.miny > canvas_height as f32
+        if path_cache.bounds.maxx + grow < 0.0
+            || path_cache.bounds.minx - grow > canvas_width as f32
+            || path_cache.bounds.maxy + grow < 0.0
+            || path_cache.bounds.miny - grow > canvas_height as f32
         {
             return;
         }
@@ -1099,12 +1891,71 @@ where
         // Calculate fill vertices.
         // expand_fill will fill path_cache.contours[].{stroke, fill} with vertex data for the GPU
         // fringe_with is the size of the strip of triangles generated at the path border used for AA
-        let fringe_width = if anti_alias { self.fringe_width } else { 0.0 };
-        path_cache.expand_fill(fringe_width, LineJoin::Miter, 2.4);
+        // A grown fill leaves its edges to the pen, and so is not inset for
+        // fringes of its own.
+        path_cache.expand_fill(if pen.is_some() { 0.0 } else { fringe_width }, LineJoin::Miter, 2.4);
+
+        // With a pen, the band it fully covers goes into the stencil with the
+        // fill, and its stroke edges fringe the grown shape. Fills are inset
+        // by half a fringe along the outline's extrusions, which point out of
+        // outlines wound the way glyphs are; those are antialiased a fringe
+        // further out, and the pen reaches that much further too.
+        let mut band = Vec::new();
+        let mut reach = 0.0;
+        if let Some(pen) = pen {
+            let fill_area: f32 = path_cache
+                .contours
+                .iter()
+                .flat_map(|contour| contour.fill.chunks_exact(3))
+                .map(triangle_area)
+                .sum();
+            reach = grow + if fill_area > 0.0 { fringe_width } else { 0.0 };
+
+            if reach > fringe_width * 0.5 {
+                path_cache.expand_stroke(
+                    reach - fringe_width * 0.5,
+                    0.0,
+                    LineCap::Butt,
+                    LineCap::Butt,
+                    pen.line_join,
+                    pen.miter_limit,
+                    self.tess_tol,
+                );
+                // Wound like the fill, the band only ever adds to its stencil.
+                band = path_cache
+                    .contours
+                    .iter()
+                    .flat_map(|contour| contour.stroke.windows(3))
+                    .filter_map(|triangle| match triangle_area(triangle) * fill_area {
+                        area if area > 0.0 => Some([triangle[0], triangle[1], triangle[2]]),
+                        area if area < 0.0 => Some([triangle[0], triangle[2], triangle[1]]),
+                        _ => None,
+                    })
+                    .flatten()
+                    .collect();
+            }
+
+            if anti_alias {
+                path_cache.expand_stroke(
+                    reach,
+                    fringe_width,
+                    LineCap::Butt,
+                    LineCap::Butt,
+                    pen.line_join,
+                    pen.miter_limit,
+                    self.tess_tol,
+                );
+            } else {
+                for contour in &mut path_cache.contours {
+                    contour.stroke.clear();
+                }
+            }
+        }
 
         // Detect if this path fill is in fact just an unclipped image copy
 
-        if let (Some(path_rect), Some(scissor_rect), true) = (
+        if let (None, Some(path_rect), Some(scissor_rect), true) = (
+            pen,
             path_cache.path_fill_is_rect(),
             scissor.as_rect(canvas_width as f32, canvas_height as f32),
             paint_flavor.is_straight_tinted_image(anti_alias),
@@ -1119,43 +1970,46 @@ where
         }
 
         // GPU uniforms
-        let flavor = if path_cache.contours.len() == 1 && path_cache.contours[0].convexity == Convexity::Convex {
-            let params = Params::new(
-                &self.images,
-                &transform,
-                &paint_flavor,
-                &GlyphTexture::default(),
-                &scissor,
-                self.fringe_width,
-                self.fringe_width,
-                -1.0,
-            );
+        let flavor =
+            if pen.is_none() && path_cache.contours.len() == 1 && path_cache.contours[0].convexity == Convexity::Convex
+            {
+                let params = Params::new(
+                    &self.images,
+                    &transform,
+                    &paint_flavor,
+                    &GlyphTexture::default(),
+                    &scissor,
+                    self.fringe_width,
+                    self.fringe_width,
+                    -1.0,
+                );
+
+                CommandType::ConvexFill { params }
+            } else {
+                let stencil_params = Params {
+                    stroke_thr: -1.0,
+                    shader_type: ShaderType::Stencil,
+                    ..Params::default()
+                };
 
-            CommandType::ConvexFill { params }
-        } else {
-            let stencil_params = Params {
-                stroke_thr: -1.0,
-                shader_type: ShaderType::Stencil,
-                ..Params::default()
+                // A grown fill's fringes are the pen's stroke edges.
+                let fill_params = Params::new(
+                    &self.images,
+                    &transform,
+                    &paint_flavor,
+                    &GlyphTexture::default(),
+                    &scissor,
+                    (reach * 2.0).max(self.fringe_width),
+                    self.fringe_width,
+                    -1.0,
+                );
+
+                CommandType::ConcaveFill {
+                    stencil_params,
+                    fill_params,
+                }
             };
 
-            let fill_params = Params::new(
-                &self.images,
-                &transform,
-                &paint_flavor,
-                &GlyphTexture::default(),
-                &scissor,
-                self.fringe_width,
-                self.fringe_width,
-                -1.0,
-            );
-
-            CommandType::ConcaveFill {
-                stencil_params,
-                fill_params,
-            }
-        };
-
         // GPU command
         let mut cmd = Command::new(flavor);
         cmd.fill_rule = fill_rule;
@@ -1196,31 +2050,40 @@ where
             cmd.drawables.push(drawable);
         }
 
+        if !band.is_empty() {
+            cmd.drawables.push(Drawable {
+                fill_verts: Some((offset, band.len())),
+                stroke_verts: None,
+            });
+            self.verts.extend_from_slice(&band);
+            offset += band.len();
+        }
+
         if let CommandType::ConcaveFill { .. } = cmd.cmd_type {
             // Concave shapes are first filled by writing to a stencil buffer and then drawing a quad
             // over the shape area with stencil test enabled to produce the final fill. These are
             // the verts needed for the covering quad
             self.verts.push(Vertex::new(
-                path_cache.bounds.maxx + fringe_width,
-                path_cache.bounds.maxy + fringe_width,
+                path_cache.bounds.maxx + fringe_width + reach,
+                path_cache.bounds.maxy + fringe_width + reach,
                 0.5,
                 1.0,
             ));
             self.verts.push(Vertex::new(
-                path_cache.bounds.maxx + fringe_width,
-                path_cache.bounds.miny - fringe_width,
+                path_cache.bounds.maxx + fringe_width + reach,
+                path_cache.bounds.miny - fringe_width - reach,
                 0.5,
                 1.0,
             ));
             self.verts.push(Vertex::new(
-                path_cache.bounds.minx - fringe_width,
-                path_cache.bounds.maxy + fringe_width,
+                path_cache.bounds.minx - fringe_width - reach,
+                path_cache.bounds.maxy + fringe_width + reach,
                 0.5,
                 1.0,
             ));
             self.verts.push(Vertex::new(
-                path_cache.bounds.minx - fringe_width,
-                path_cache.bounds.miny,
+                path_cache.bounds.minx - fringe_width - reach,
+                path_cache.bounds.miny - reach,
                 0.5,
                 1.0,
             ));
@@ -1259,13 +2122,14 @@ where
             return;
         }
 
-        // Draw the drop shadow (if any) under the stroke. The path-cache bounds
-        // only cover the centerline, so expand them by the device-space stroke
-        // half-width before handing them to render_shadow. This runs in its own
+        // Draw the drop shadow (if any) under the stroke, or the whole stroke
+        // through the canvas filters. The path-cache bounds only cover the
+        // centerline, so expand them by the device-space stroke half-width before
+        // handing them to render_shadow or render_filtered. This runs in its own
         // scope so the cache's RefMut borrow is released before the path is cloned
-        // (cloning a Path while its cache is borrowed would panic). render_shadow
-        // disables shadows in the state, so re-entering stroke does not recurse.
-        if self.shadow_enabled() {
+        // (cloning a Path while its cache is borrowed would panic). Both passes
+        // disable themselves in the state, so re-entering stroke does not recurse.
+        if self.shadow_enabled() || self.filter_enabled() {
             let centerline = {
                 let cache = path.cache(&transform, self.tess_tol, self.dist_tol);
                 cache.bounds
@@ -1276,15 +2140,35 @@ where
             bounds.miny -= half;
             bounds.maxx += half;
             bounds.maxy += half;
+            if self.filter_enabled() {
+                let path = path.clone();
+                let stroke = stroke.clone();
+                self.render_filtered(bounds, move |canvas| {
+                    canvas.stroke_path_internal(&path, &paint_flavor, anti_alias, &stroke);
+                });
+                return;
+            }
             // Skip only when even the offset+blurred shadow cannot reach the
             // render target. The offset and blur spread can pull a shadow back
             // on-screen for a shape whose own bounds are off-screen, so we must
             // not cull on the shape's bounds alone.
             if self.shadow_could_be_visible(bounds) {
+                // Dashes were applied above, so the path is all there is to
+                // the outline besides the pen.
+                let cache_key = self.shadow_cache_key(&paint_flavor, |hasher| {
+                    path.hash_outline(hasher);
+                    anti_alias.hash(hasher);
+                    stroke.stencil_strokes.hash(hasher);
+                    stroke.miter_limit.to_bits().hash(hasher);
+                    stroke.line_width.to_bits().hash(hasher);
+                    stroke.line_cap_start.hash(hasher);
+                    stroke.line_cap_end.hash(hasher);
+                    stroke.line_join.hash(hasher);
+                });
                 let path = path.clone();
                 let stroke = stroke.clone();
                 let shadow_flavor = paint_flavor.clone();
-                self.render_shadow(bounds, move |canvas| {
+                self.render_shadow(bounds, cache_key, move |canvas| {
                     canvas.stroke_path_internal(&path, &shadow_flavor, anti_alias, &stroke);
                 });
             }
@@ -1436,6 +2320,108 @@ where
         maxx >= 0.0 && minx <= self.width() as f32 && maxy >= 0.0 && miny <= self.height() as f32
     }
 
+    /// Draws the drop shadow of a solid-color rectangle or rounded rectangle
+    /// in closed form with the box shadow shader, instead of blurring it
+    /// offscreen like `render_shadow`. Returns `false`, drawing nothing, if
+    /// the path or paint is anything else, or if the transform would not keep
+    /// the blur round (skew or non-uniform scale).
+    fn render_analytic_shadow(&mut self, path: &Path, paint_flavor: &PaintFlavor, anti_alias: bool) -> bool {
+        let &PaintFlavor::Color(color) = paint_flavor else {
+            return false;
+        };
+        let Some((x, y, width, height, radius)) = path.as_rounded_rect() else {
+            return false;
+        };
+
+        let state = self.state();
+        let Transform2D([a, b, c, d, ..]) = state.transform;
+        let scale = a.hypot(b);
+        let tolerance = scale * 1e-4;
+        let rotation = (a - d).abs() <= tolerance && (b + c).abs() <= tolerance;
+        let reflection = (a + d).abs() <= tolerance && (b - c).abs() <= tolerance;
+        if scale <= 1e-6 || !(rotation || reflection) {
+            return false;
+        }
+
+        // The blur is in device pixels, so it shrinks by the scale in user
+        // space. `render_shadow` blurs the antialiased coverage, and
+        // antialiasing is close to a one pixel box filter, whose standard
+        // deviation of 1/sqrt(12) is folded in so the edges match.
+        let mut sigma = state.shadow_blur / 2.0;
+        if anti_alias {
+            sigma = sigma.hypot(12f32.sqrt().recip());
+        }
+        sigma /= scale;
+
+        // As in `render_shadow`, the shadow alpha is the source alpha times
+        // the shadow color's, and the offset is applied in device space.
+        let mut shadow_color = state.shadow_color;
+        shadow_color.a *= color.a;
+        let [ox, oy] = state.shadow_offset;
+        let mut transform = Transform2D::translation(ox, oy);
+        transform.premultiply(&state.transform);
+
+        let flavor = PaintFlavor::BoxShadow {
+            pos: Position { x, y },
+            width,
+            height,
+            radius,
+            sigma,
+            color: shadow_color,
+            inset: false,
+        };
+
+        // The Gaussian is negligible past 3 sigma; one more pixel keeps the
+        // quad's hard edges clear of it.
+        let reach = sigma * 3.0 + scale.recip();
+        let mut quad = Path::new();
+        quad.rect(x - reach, y - reach, width + reach * 2.0, height + reach * 2.0);
+
+        self.save();
+        self.state_mut().transform = transform;
+        self.state_mut().shadow_color = Color::rgbaf(0.0, 0.0, 0.0, 0.0);
+        self.fill_path_internal(&quad, &flavor, false, FillRule::NonZero);
+        self.restore();
+
+        true
+    }
+
+    /// Returns the key the shadow of a draw is cached under, from `paint_flavor`,
+    /// the state the shadow image depends on (transform, shadow blur and shadow
+    /// color) and whatever `hash_draw` adds to tell the shape apart. Returns
+    /// `None` when the shadow cache is off or the paint is an image.
+    fn shadow_cache_key(
+        &self,
+        paint_flavor: &PaintFlavor,
+        hash_draw: impl FnOnce(&mut ShadowKey),
+    ) -> Option<ShadowKey> {
+        if !self.shadow_cache.is_enabled() {
+            return None;
+        }
+        let mut key = ShadowKey::default();
+        if !paint_flavor.hash_cache_key(&mut key) {
+            return None;
+        }
+
+        let state = self.state();
+        for value in state.transform.0 {
+            value.to_bits().hash(&mut key);
+        }
+        state.shadow_blur.to_bits().hash(&mut key);
+        for component in state.shadow_color.to_array() {
+            component.to_bits().hash(&mut key);
+        }
+        // The tessellation tolerances and the fringe follow the pixel ratio.
+        self.device_px_ratio.to_bits().hash(&mut key);
+        hash_draw(&mut key);
+        Some(key)
+    }
+
+    /// Returns `true` when draws go through the filters set with `set_filter`.
+    fn filter_enabled(&self) -> bool {
+        self.state().filter.is_some()
+    }
+
     /// Renders a drop shadow for a shape whose device-space bounding box is
     /// `shape_bounds`, using the supplied closure to draw the shape's coverage.
     ///
@@ -1457,13 +2443,22 @@ where
     /// `draw_coverage` is expected to issue the shape's normal draw command(s) with
     /// its real paint; the canvas transform in effect during the call already maps
     /// the shape's device-space coordinates into the offscreen image.
-    fn render_shadow(&mut self, shape_bounds: Bounds, draw_coverage: impl FnOnce(&mut Self)) {
+    ///
+    /// With a `cache_key` (see `shadow_cache_key`), the blurred image is kept in
+    /// the shadow cache and a later draw with the same key composites it again
+    /// without calling `draw_coverage`.
+    fn render_shadow(
+        &mut self,
+        shape_bounds: Bounds,
+        cache_key: Option<ShadowKey>,
+        draw_coverage: impl FnOnce(&mut Self),
+    ) {
         // Degenerate / off-screen bounds: nothing to cast a shadow from.
         if shape_bounds.maxx <= shape_bounds.minx || shape_bounds.maxy <= shape_bounds.miny {
             return;
         }
 
-        let state = *self.state();
+        let state = self.state().clone();
         let shadow_color = state.shadow_color;
 
         // Standard deviation in device pixels (HTML drawing model: sigma = blur/2).
@@ -1497,6 +2492,76 @@ where
             return;
         }
 
+        let cached = cache_key
+            .as_ref()
+            .and_then(|key| self.shadow_cache.lookup(key, (minx, miny), (width, height)));
+        let source_image = match cached {
+            Some(image) => image,
+            None => match self.render_shadow_image(&state, (minx, miny), (width, height), draw_coverage) {
+                Some(image) => image,
+                None => return,
+            },
+        };
+        // Images not kept in the cache are referenced by deferred draw
+        // commands, so they can only be freed after the next flush.
+        if cached.is_none() {
+            match cache_key {
+                Some(key) => {
+                    let replaced = self
+                        .shadow_cache
+                        .insert(key, (minx, miny), (width, height), source_image);
+                    self.transient_images.extend(replaced);
+                }
+                None => self.transient_images.push(source_image),
+            }
+        }
+
+        // Composite the shadow back into the current target, offset by the
+        // device-space shadow offset and drawn under the shape. The shadow color's
+        // alpha is already baked into the image (via the SourceIn mask); only the
+        // global alpha is folded into the image tint here.
+        let dst_x = minx + txx;
+        let dst_y = miny + txy;
+
+        // The shadow image already carries shadowColor.rgb and per-pixel alpha
+        // `source.alpha * shadowColor.a` (baked in by the SourceIn mask above), so
+        // here we only fold in the current global alpha.
+        let tint = Color::rgbaf(1.0, 1.0, 1.0, state.alpha);
+        let mut shadow_paint = Paint::image_tint(source_image, dst_x, dst_y, width as f32, height as f32, 0.0, tint);
+        shadow_paint.set_anti_alias(false);
+
+        // Composite in plain device space (identity transform) at the offset
+        // position, honoring the caller's scissor and composite operation.
+        let saved_transform = self.state().transform;
+        let saved_alpha = self.state().alpha;
+        self.state_mut().transform = Transform2D::identity();
+        self.state_mut().alpha = 1.0;
+        self.state_mut().shadow_color = Color::rgbaf(0.0, 0.0, 0.0, 0.0);
+
+        let mut shadow_rect = Path::new();
+        shadow_rect.rect(dst_x, dst_y, width as f32, height as f32);
+        self.fill_path_internal(&shadow_rect, &shadow_paint.flavor, false, FillRule::NonZero);
+
+        self.state_mut().transform = saved_transform;
+        self.state_mut().alpha = saved_alpha;
+        self.state_mut().shadow_color = shadow_color;
+    }
+
+    /// Renders the image `render_shadow` composites: the shape's coverage,
+    /// drawn by `draw_coverage` into an offscreen image at `origin` and `size`
+    /// in device space, recolored by the shadow color and blurred as set in
+    /// `state`. The render target is restored afterwards. The caller owns the
+    /// returned image; the intermediate image is released after the flush.
+    fn render_shadow_image(
+        &mut self,
+        state: &State,
+        (minx, miny): (f32, f32),
+        (width, height): (usize, usize),
+        draw_coverage: impl FnOnce(&mut Self),
+    ) -> Option<ImageId> {
+        let shadow_color = state.shadow_color;
+        let sigma = state.shadow_blur / 2.0;
+
         // Offscreen render targets store premultiplied-alpha results, so flag the
         // images as PREMULTIPLIED. Otherwise the image-sampling shader would
         // re-premultiply on composite (multiplying rgb by alpha a second time),
@@ -1515,7 +2580,7 @@ where
         // the blurred image keeps the coverage image's orientation.
         let image_flags = ImageFlags::PREMULTIPLIED | ImageFlags::FLIP_Y;
         let Ok(coverage_image) = self.create_image_empty(width, height, PixelFormat::Rgba8, image_flags) else {
-            return;
+            return None;
         };
         // The blur kernel divides by sigma, so a zero (or sub-pixel) blur skips
         // the filter pass entirely — and with it the second offscreen image.
@@ -1524,7 +2589,7 @@ where
                 Ok(image) => Some(image),
                 Err(_) => {
                     self.delete_image(coverage_image);
-                    return;
+                    return None;
                 }
             }
         } else {
@@ -1584,50 +2649,124 @@ where
             coverage_image
         };
 
-        // Composite the shadow back into the original target, offset by the
-        // device-space shadow offset and drawn under the shape. The shadow color's
-        // alpha is already baked into the image (via the SourceIn mask); only the
-        // global alpha is folded into the image tint here.
         self.set_render_target(previous_target);
 
-        let dst_x = minx + txx;
-        let dst_y = miny + txy;
+        // The coverage image is referenced by the deferred blur, so it can only
+        // be freed after the next flush.
+        if blurred_image.is_some() {
+            self.transient_images.push(coverage_image);
+        }
 
-        // The shadow image already carries shadowColor.rgb and per-pixel alpha
-        // `source.alpha * shadowColor.a` (baked in by the SourceIn mask above), so
-        // here we only fold in the current global alpha.
-        let tint = Color::rgbaf(1.0, 1.0, 1.0, state.alpha);
-        let mut shadow_paint = Paint::image_tint(source_image, dst_x, dst_y, width as f32, height as f32, 0.0, tint);
-        shadow_paint.set_anti_alias(false);
+        Some(source_image)
+    }
 
-        // Composite in plain device space (identity transform) at the offset
-        // position, honoring the caller's scissor and composite operation.
-        let saved_transform = self.state().transform;
-        let saved_alpha = self.state().alpha;
-        self.state_mut().transform = Transform2D::identity();
-        self.state_mut().alpha = 1.0;
-        self.state_mut().shadow_color = Color::rgbaf(0.0, 0.0, 0.0, 0.0);
+    /// Renders a draw through the filters set with `set_filter`, using the
+    /// supplied closure to issue the draw. `shape_bounds` is the draw's
+    /// device-space bounding box.
+    ///
+    /// As with `render_shadow`, the draw lands in a transient offscreen image
+    /// padded by the combined reach of the filters (plus a fringe pixel) and
+    /// clipped to the reach around the render target, since content further
+    /// out cannot be pulled back on screen. The filters then run in order over
+    /// a chain of transient images, and the result is composited back with the
+    /// identity transform through `fill_path_internal`, which applies the
+    /// state's global alpha, scissor, composite operation and shadow.
+    fn render_filtered(&mut self, shape_bounds: Bounds, draw: impl FnOnce(&mut Self)) {
+        // Degenerate bounds: nothing was drawn.
+        if shape_bounds.maxx <= shape_bounds.minx || shape_bounds.maxy <= shape_bounds.miny {
+            return;
+        }
 
-        let mut shadow_rect = Path::new();
-        shadow_rect.rect(dst_x, dst_y, width as f32, height as f32);
-        self.fill_path_internal(&shadow_rect, &shadow_paint.flavor, false, FillRule::NonZero);
+        let state = self.state().clone();
+        let Some(filters) = state.filter else {
+            return;
+        };
 
-        self.state_mut().transform = saved_transform;
-        self.state_mut().alpha = saved_alpha;
-        self.state_mut().shadow_color = shadow_color;
+        let filters = filters
+            .iter()
+            .filter(|filter| filter.runs_offscreen())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let pad = filters.iter().map(ImageFilter::reach).sum::<f32>() + 2.0;
+        let minx = (shape_bounds.minx - pad).max(-pad).floor();
+        let miny = (shape_bounds.miny - pad).max(-pad).floor();
+        let maxx = (shape_bounds.maxx + pad).min(self.width() as f32 + pad).ceil();
+        let maxy = (shape_bounds.maxy + pad).min(self.height() as f32 + pad).ceil();
 
-        // The transient images are referenced by deferred draw commands, so they
-        // can only be freed after the next flush. Queue them for later cleanup.
-        self.shadow_images.push(coverage_image);
-        if let Some(blurred_image) = blurred_image {
-            self.shadow_images.push(blurred_image);
+        // Degenerate or off-screen bounds: nothing to filter.
+        if maxx <= minx || maxy <= miny {
+            return;
         }
+
+        let width = (maxx - minx) as usize;
+        let height = (maxy - miny) as usize;
+
+        if width > 8192 || height > 8192 {
+            return;
+        }
+
+        // Premultiplied and flipped, like the shadow coverage image; every
+        // single-pass filter toggles the orientation of its output.
+        let mut image_flags = ImageFlags::PREMULTIPLIED | ImageFlags::FLIP_Y;
+        let Ok(drawing) = self.create_transient_image(width, height, image_flags) else {
+            return;
+        };
+
+        let previous_target = self.current_render_target;
+
+        // Draw at full strength without scissor, shadow or filter; those apply
+        // to the filtered result.
+        self.save();
+        self.set_render_target(RenderTarget::Image(drawing));
+        self.clear_rect(0, 0, width as u32, height as u32, Color::rgbaf(0.0, 0.0, 0.0, 0.0));
+
+        let mut drawing_transform = Transform2D::translation(-minx, -miny);
+        drawing_transform.premultiply(&state.transform);
+        let offscreen = self.state_mut();
+        offscreen.transform = drawing_transform;
+        offscreen.alpha = 1.0;
+        offscreen.scissor = Scissor::default();
+        offscreen.composite_operation = CompositeOperationState::default();
+        offscreen.shadow_color = Color::rgbaf(0.0, 0.0, 0.0, 0.0);
+        offscreen.filter = None;
+
+        draw(self);
+
+        self.restore();
+        self.set_render_target(previous_target);
+
+        let mut filtered = drawing;
+        for filter in filters {
+            if filter.flips_vertically() {
+                image_flags.toggle(ImageFlags::FLIP_Y);
+            }
+            let Ok(target) = self.create_transient_image(width, height, image_flags) else {
+                return;
+            };
+            self.filter_image(target, filter, filtered);
+            filtered = target;
+        }
+
+        let mut paint = Paint::image(filtered, minx, miny, width as f32, height as f32, 0.0, 1.0);
+        paint.set_anti_alias(false);
+
+        self.save();
+        self.state_mut().transform = Transform2D::identity();
+        self.state_mut().filter = None;
+
+        let mut rect = Path::new();
+        rect.rect(minx, miny, width as f32, height as f32);
+        self.fill_path_internal(&rect, &paint.flavor, false, FillRule::NonZero);
+
+        self.restore();
     }
 
-    /// Frees offscreen images allocated by drop-shadow passes during the frame.
+    /// Frees offscreen images allocated by drop-shadow passes, canvas filters
+    /// and filter graphs during the frame.
     /// Called after the renderer has consumed the frame's commands.
-    fn release_shadow_images(&mut self) {
-        for id in std::mem::take(&mut self.shadow_images) {
+    fn release_transient_images(&mut self) {
+        for id in std::mem::take(&mut self.transient_images) {
             self.images.remove(&mut self.renderer, id);
         }
     }
@@ -1730,6 +2869,44 @@ where
         Ok(font.variation_axes())
     }
 
+    /// Returns the family, subfamily and PostScript names of the specified font,
+    /// along with the named instances of variable fonts.
+    pub fn font_info(&self, font_id: FontId) -> Result<FontInfo, ErrorKind> {
+        let ctx = self.text_context.borrow();
+        let font = ctx.font(font_id).ok_or(ErrorKind::NoFontFound)?;
+        Ok(font.info())
+    }
+
+    /// Selects the registered font that best matches a family name, weight,
+    /// style and stretch. See [`TextContext::query_font`] for the matching rules.
+    pub fn query_font(&self, family: &str, weight: f32, style: FontStyle, stretch: f32) -> Result<FontId, ErrorKind> {
+        self.text_context.borrow().query_font(family, weight, style, stretch)
+    }
+
+    /// Limits the GPU memory used by the glyph atlas textures, or removes the
+    /// limit with `None` (the default).
+    ///
+    /// Rendered glyphs are cached in atlas textures of about 1 MiB each. Without
+    /// a budget, new textures are allocated for as long as new glyphs, sizes or
+    /// fonts are drawn. With a budget, once it is reached the texture whose
+    /// glyphs were drawn least recently is cleared and reused; its glyphs are
+    /// rasterized again the next time they are drawn. The budget is rounded
+    /// down to whole textures, but allows at least one. It applies separately
+    /// to the atlas for regular text and the one for distance-field text.
+    ///
+    /// The budget may be exceeded temporarily when a single frame draws more
+    /// glyphs than fit; the excess textures are released at the next flush.
+    pub fn set_glyph_atlas_budget(&mut self, bytes: Option<usize>) {
+        let max_pages = bytes.map(|bytes| (bytes / text::PAGE_BYTES).max(1));
+        self.glyph_atlas.set_max_pages(max_pages);
+        self.distance_field_atlas.set_max_pages(max_pages);
+    }
+
+    /// Returns the memory usage and eviction counters of the glyph atlases.
+    pub fn glyph_atlas_stats(&self) -> GlyphAtlasStats {
+        self.glyph_atlas.stats().merge(self.distance_field_atlas.stats())
+    }
+
     /// Returns information on how the provided text will be drawn with the specified paint.
     #[cfg(feature = "textlayout")]
     pub fn measure_text<S: AsRef<str>>(
@@ -1739,6 +2916,7 @@ where
         text: S,
         paint: &Paint,
     ) -> Result<TextMetrics, ErrorKind> {
+        let paint = &paint.optically_sized(self.font_scale());
         let scale = self.font_scale() * self.device_px_ratio;
 
         let mut text_settings = paint.text.clone();
@@ -1757,6 +2935,41 @@ where
             })
     }
 
+    /// Returns the outlines of the provided text, shaped and positioned as
+    /// [`fill_text`](Self::fill_text) would draw it, as a single [`Path`] in
+    /// user space.
+    ///
+    /// The path can be filled, stroked or used as a clip like any other path.
+    /// Glyphs without outlines, such as bitmap emoji, are left out, and so are
+    /// text decorations. A synthesized oblique for fonts without an italic face
+    /// is applied to the outlines; a synthesized bold is not, so such glyphs
+    /// keep the regular weight.
+    #[cfg(feature = "textlayout")]
+    pub fn text_to_path<S: AsRef<str>>(&self, x: f32, y: f32, text: S, paint: &Paint) -> Result<Path, ErrorKind> {
+        let paint = &paint.optically_sized(self.font_scale());
+        let scale = self.font_scale() * self.device_px_ratio;
+        let invscale = 1.0 / scale;
+
+        let mut text_settings = paint.text.clone();
+        text_settings.font_size *= scale;
+        text_settings.letter_spacing *= scale;
+
+        // Shape exactly like `draw_text`, so the outlines land where the glyphs
+        // would be drawn.
+        let mut text_context = self.text_context.borrow_mut();
+        let mut layout = text::shape(
+            x * scale,
+            y * scale,
+            &mut text_context,
+            &text_settings,
+            text.as_ref(),
+            None,
+        )?;
+        layout.scale(invscale);
+
+        Ok(text_context.glyphs_to_path(&layout, &paint.text))
+    }
+
     /// Returns font metrics for a particular Paint, in user-space units.
     ///
     /// The values scale with the paint's font size only — the canvas
@@ -1773,6 +2986,7 @@ where
         // zoomed canvas came back inflated — sub/superscript runs sized from
         // `subscript_size()` grew with the zoom instead of staying anchored
         // to the run's font size.
+        let paint = paint.optically_sized(1.0);
         self.text_context.borrow_mut().measure_font(
             paint.text.font_size,
             &paint.text.font_ids,
@@ -1785,6 +2999,7 @@ where
     /// The retuned index will always lie at the start and/or end of a UTF-8 code point sequence or at the start or end of the text
     #[cfg(feature = "textlayout")]
     pub fn break_text<S: AsRef<str>>(&self, max_width: f32, text: S, paint: &Paint) -> Result<usize, ErrorKind> {
+        let paint = &paint.optically_sized(self.font_scale());
         let scale = self.font_scale() * self.device_px_ratio;
 
         let mut text_settings = paint.text.clone();
@@ -1799,6 +3014,9 @@ where
     }
 
     /// Returnes a list of ranges representing each line of text that will fit inside `max_width`
+    ///
+    /// With a vertical [`WritingMode`], `max_width` limits the length of each
+    /// column instead.
     #[cfg(feature = "textlayout")]
     pub fn break_text_vec<S: AsRef<str>>(
         &self,
@@ -1806,6 +3024,7 @@ where
         text: S,
         paint: &Paint,
     ) -> Result<Vec<Range<usize>>, ErrorKind> {
+        let paint = &paint.optically_sized(self.font_scale());
         let scale = self.font_scale() * self.device_px_ratio;
 
         let mut text_settings = paint.text.clone();
@@ -1819,6 +3038,116 @@ where
             .break_text_vec(max_width, text, &text_settings)
     }
 
+    /// Fits the provided text into at most `max_lines` lines no wider than
+    /// `max_width`, ending it with an ellipsis where it is cut.
+    ///
+    /// With `max_lines` of one (or zero) the text stays on a single line;
+    /// otherwise it is wrapped like [`break_text_vec`](Self::break_text_vec)
+    /// and the last allowed line takes the rest of the text. A line that is
+    /// too wide is cut at a grapheme cluster boundary, trailing whitespace is
+    /// dropped, and "…" (U+2026, or "..." if no registered font has it) is
+    /// appended at the logical end, so right-to-left text shows it on the left.
+    /// Lines that fit are returned unchanged.
+    #[cfg(feature = "textlayout")]
+    pub fn truncate_text<S: AsRef<str>>(
+        &self,
+        max_width: f32,
+        max_lines: usize,
+        text: S,
+        paint: &Paint,
+    ) -> Result<Vec<String>, ErrorKind> {
+        let paint = &paint.optically_sized(self.font_scale());
+        let scale = self.font_scale() * self.device_px_ratio;
+
+        let mut text_settings = paint.text.clone();
+        text_settings.font_size *= scale;
+        text_settings.letter_spacing *= scale;
+
+        let max_width = max_width * scale;
+
+        self.text_context
+            .borrow_mut()
+            .truncate_text(max_width, max_lines, text, &text_settings)
+    }
+
+    /// Fills the provided string with the specified Paint, truncated with an
+    /// ellipsis to at most `max_lines` lines no wider than `max_width`.
+    ///
+    /// The lines are the ones returned by [`truncate_text`](Self::truncate_text).
+    /// The first is drawn at `(x, y)`, and each following line one font
+    /// [height](FontMetrics::height) further in the [`WritingMode`]'s line
+    /// direction: down, or left or right for vertical text. Returns the
+    /// metrics of each drawn line.
+    #[cfg(feature = "textlayout")]
+    pub fn fill_text_truncated<S: AsRef<str>>(
+        &mut self,
+        x: f32,
+        y: f32,
+        max_width: f32,
+        max_lines: usize,
+        text: S,
+        paint: &Paint,
+    ) -> Result<Vec<TextMetrics>, ErrorKind> {
+        let lines = self.truncate_text(max_width, max_lines, text, paint)?;
+        let (step_x, step_y) = self.line_step(paint)?;
+
+        lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let index = index as f32;
+                self.fill_text(x + index * step_x, y + index * step_y, line, paint)
+            })
+            .collect()
+    }
+
+    /// Fills the provided string with the specified Paint, wrapped into lines
+    /// no wider than `max_width`.
+    ///
+    /// The lines are the ones returned by [`break_text_vec`](Self::break_text_vec),
+    /// without their trailing whitespace, stacked like the lines of
+    /// [`fill_text_truncated`](Self::fill_text_truncated). With
+    /// [`Align::Justify`] every line but the last is stretched to `max_width`
+    /// as selected by the paint's [`TextJustify`]. Returns the metrics of each
+    /// drawn line.
+    #[cfg(feature = "textlayout")]
+    pub fn fill_text_wrapped<S: AsRef<str>>(
+        &mut self,
+        x: f32,
+        y: f32,
+        max_width: f32,
+        text: S,
+        paint: &Paint,
+    ) -> Result<Vec<TextMetrics>, ErrorKind> {
+        let text = text.as_ref();
+        let lines = self.break_text_vec(max_width, text, paint)?;
+        let (step_x, step_y) = self.line_step(paint)?;
+
+        let mut line_paint = paint.clone();
+        let line_count = lines.len();
+        lines
+            .into_iter()
+            .enumerate()
+            .map(|(index, range)| {
+                line_paint.text.justify_width = (index + 1 < line_count).then_some(max_width);
+                let index = index as f32;
+                let line = text[range].trim_end();
+                self.fill_text(x + index * step_x, y + index * step_y, line, &line_paint)
+            })
+            .collect()
+    }
+
+    // The offset from one line of text to the next in the paint's writing mode.
+    #[cfg(feature = "textlayout")]
+    fn line_step(&self, paint: &Paint) -> Result<(f32, f32), ErrorKind> {
+        let line_height = self.measure_font(paint)?.height();
+        Ok(match paint.text.writing_mode {
+            WritingMode::HorizontalTb => (0.0, line_height),
+            WritingMode::VerticalRl => (-line_height, 0.0),
+            WritingMode::VerticalLr => (line_height, 0.0),
+        })
+    }
+
     /// Fills the provided string with the specified Paint.
     #[cfg(feature = "textlayout")]
     pub fn fill_text<S: AsRef<str>>(
@@ -1843,6 +3172,30 @@ where
         self.draw_text(x, y, text.as_ref(), paint, RenderMode::Stroke)
     }
 
+    /// Fills the provided string along `path`, like SVG's `<textPath>`.
+    ///
+    /// The text is shaped as by [`fill_text`](Self::fill_text), then every
+    /// character is moved to its distance along the path, counted from `offset`,
+    /// and rotated to follow the path's direction at its midpoint. The paint's
+    /// [`Align`] anchors the text at `offset`, its [`Baseline`] positions the
+    /// text relative to the path, and its [`TextPathSide`] selects which side of
+    /// the path the text runs on.
+    ///
+    /// Characters whose midpoint falls off the end of an open path are not
+    /// drawn; on a path made of a single closed sub-path the text wraps around.
+    /// Text decorations are not drawn, and the [`WritingMode`] is ignored. The returned metrics describe the run
+    /// before it is bent, with x positions measured along the path.
+    #[cfg(feature = "textlayout")]
+    pub fn fill_text_on_path<S: AsRef<str>>(
+        &mut self,
+        path: &Path,
+        offset: f32,
+        text: S,
+        paint: &Paint,
+    ) -> Result<TextMetrics, ErrorKind> {
+        self.draw_text_on_path(path, offset, text.as_ref(), paint, RenderMode::Fill)
+    }
+
     /// Fills the provided glyphs with the specified Paint.
     ///
     /// `normalized_coords` specifies variation axis positions for variable
@@ -1859,7 +3212,7 @@ where
         glyphs: impl IntoIterator<Item = PositionedGlyph>,
         paint: &Paint,
     ) -> Result<(), ErrorKind> {
-        self.draw_glyph_run(glyphs, paint, font_id, normalized_coords, RenderMode::Fill)
+        self.draw_glyph_run(glyphs, paint, font_id, normalized_coords, RenderMode::Fill, false)
     }
 
     /// Strokes the provided glyphs with the specified Paint.
@@ -1878,45 +3231,31 @@ where
         glyphs: impl IntoIterator<Item = PositionedGlyph>,
         paint: &Paint,
     ) -> Result<(), ErrorKind> {
-        self.draw_glyph_run(glyphs, paint, font_id, normalized_coords, RenderMode::Stroke)
+        self.draw_glyph_run(glyphs, paint, font_id, normalized_coords, RenderMode::Stroke, false)
     }
 
     /// Dispatch an explicit set of `GlyphDrawCommands` to the renderer. Use this only if you are
     /// using a custom font rasterizer/layout.
     pub fn draw_glyph_commands(&mut self, draw_commands: GlyphDrawCommands, paint: &Paint) {
         let transform = self.state().transform;
-        let create_vertices = |quads: &Vec<text::Quad>| {
-            let mut verts = Vec::with_capacity(quads.len() * 6);
-
-            for quad in quads {
-                let left = quad.x0;
-                let right = quad.x1;
-                let top = quad.y0;
-                let bottom = quad.y1;
-
-                let (p0, p1) = transform.transform_point(left, top);
-                let (p2, p3) = transform.transform_point(right, top);
-                let (p4, p5) = transform.transform_point(right, bottom);
-                let (p6, p7) = transform.transform_point(left, bottom);
-
-                verts.push(Vertex::new(p0, p1, quad.s0, quad.t0));
-                verts.push(Vertex::new(p4, p5, quad.s1, quad.t1));
-                verts.push(Vertex::new(p2, p3, quad.s1, quad.t0));
-                verts.push(Vertex::new(p0, p1, quad.s0, quad.t0));
-                verts.push(Vertex::new(p6, p7, quad.s0, quad.t1));
-                verts.push(Vertex::new(p4, p5, quad.s1, quad.t1));
-            }
-            verts
-        };
+        let create_vertices = |quads: &Vec<text::Quad>| glyph_quad_vertices(&transform, quads);
 
         // Apply global alpha
         let mut paint_flavor = paint.flavor.clone();
         paint_flavor.mul_alpha(self.state().alpha);
 
+        let composite_operation = self.state().composite_operation;
+
         for cmd in draw_commands.alpha_glyphs {
             let verts = create_vertices(&cmd.quads);
 
-            self.render_triangles(&verts, &transform, &paint_flavor, GlyphTexture::AlphaMask(cmd.image_id));
+            self.render_triangles(
+                &verts,
+                &transform,
+                &paint_flavor,
+                GlyphTexture::AlphaMask(cmd.image_id),
+                composite_operation,
+            );
         }
 
         for cmd in draw_commands.color_glyphs {
@@ -1927,6 +3266,34 @@ where
                 &transform,
                 &paint_flavor,
                 GlyphTexture::ColorTexture(cmd.image_id),
+                composite_operation,
+            );
+        }
+
+        // Component alpha: every channel is composited source-over with its own
+        // coverage. Without dual-source blending this takes two passes, first
+        // dst * (1 - alpha * coverage), then + color * alpha * coverage.
+        for cmd in draw_commands.subpixel_glyphs {
+            let verts = create_vertices(&cmd.quads);
+
+            self.render_triangles(
+                &verts,
+                &transform,
+                &paint_flavor,
+                GlyphTexture::SubpixelCoverage(cmd.image_id),
+                CompositeOperationState {
+                    src_rgb: BlendFactor::Zero,
+                    src_alpha: BlendFactor::Zero,
+                    dst_rgb: BlendFactor::OneMinusSrcColor,
+                    dst_alpha: BlendFactor::OneMinusSrcAlpha,
+                },
+            );
+            self.render_triangles(
+                &verts,
+                &transform,
+                &paint_flavor,
+                GlyphTexture::SubpixelColor(cmd.image_id),
+                CompositeOperationState::new(CompositeOperation::Lighter),
             );
         }
     }
@@ -1944,12 +3311,14 @@ where
     ) -> Result<TextMetrics, ErrorKind> {
         use itertools::Itertools;
 
+        let paint = &paint.optically_sized(self.font_scale());
         let scale = self.font_scale() * self.device_px_ratio;
         let invscale = 1.0 / scale;
 
         let mut text_settings = paint.text.clone();
         text_settings.font_size *= scale;
         text_settings.letter_spacing *= scale;
+        text_settings.justify_width = text_settings.justify_width.map(|width| width * scale);
 
         let mut layout = text::shape(
             x * scale,
@@ -1987,8 +3356,9 @@ where
         // shadows suppressed), so a single shadow covers the whole painting
         // operation, matching the drawing model — decorations are not shadowed
         // separately. (render_shadow disables shadows in the state so this does
-        // not recurse.)
-        if self.shadow_enabled() {
+        // not recurse.) A filtered run goes through render_filtered the same way,
+        // and its composite casts the shadow.
+        if self.shadow_enabled() || self.filter_enabled() {
             // Layout metrics are in the scaled shaping space; bring them back to
             // user space. The horizontal extent unions the glyph ink boxes with
             // the run's advance box (layout.x .. layout.x + width): negative letter
@@ -2000,7 +3370,17 @@ where
                 gx0 = gx0.min(glyph.x);
                 gx1 = gx1.max(glyph.x + glyph.width);
             }
-            let (ux0, uy0, ux1, uy1) = if gx0 <= gx1 {
+            let (ux0, uy0, ux1, uy1) = if paint.text.writing_mode.is_vertical() {
+                // Vertical runs carry no decorations, so their ink box is all
+                // the shadow has to cover.
+                let margin = paint.text.font_size * 0.2;
+                (
+                    x - layout.actual_bounding_box_left() * invscale - margin,
+                    y - layout.actual_bounding_box_ascent() * invscale - margin,
+                    x + layout.actual_bounding_box_right() * invscale + margin,
+                    y + layout.actual_bounding_box_descent() * invscale + margin,
+                )
+            } else if gx0 <= gx1 {
                 let rx0 = layout.x.min(layout.x + layout.width());
                 let rx1 = layout.x.max(layout.x + layout.width());
                 let x0 = (gx0 * invscale).min(rx0 * invscale);
@@ -2019,9 +3399,9 @@ where
                     .map_or((0.0, 0.0), |m| (m.ascender(), m.descender()));
                 let (mut top, mut bottom) = (baseline - ascent, baseline - descent);
                 if let Some(metrics) = &run_font_metrics {
-                    for (offset, thickness) in decoration_lines(paint.text.text_decoration, metrics) {
-                        top = top.min(baseline + offset - thickness / 2.0);
-                        bottom = bottom.max(baseline + offset + thickness / 2.0);
+                    for line in decoration_lines(&paint.text, metrics) {
+                        top = top.min(baseline + line.offset - line.half_extent);
+                        bottom = bottom.max(baseline + line.offset + line.half_extent);
                     }
                 }
                 // A little slack for antialiased edges and blur reach.
@@ -2042,6 +3422,16 @@ where
                 device.maxy = device.maxy.max(dy);
             }
 
+            if self.filter_enabled() {
+                let text = text.to_owned();
+                let filter_paint = paint.clone();
+                self.render_filtered(device, move |canvas| {
+                    let _ = canvas.draw_text(x, y, &text, &filter_paint, render_mode);
+                });
+                layout.scale(invscale);
+                return Ok(layout);
+            }
+
             // Skip only when the offset+blurred shadow cannot reach the target;
             // text just off-screen may still cast an on-screen shadow.
             if self.shadow_could_be_visible(device) {
@@ -2050,7 +3440,7 @@ where
                 // the glyphs' true coverage/alpha; render_shadow recolors it by the
                 // shadow color while preserving that alpha.
                 let shadow_paint = paint.clone();
-                self.render_shadow(device, move |canvas| {
+                self.render_shadow(device, None, move |canvas| {
                     let _ = canvas.draw_text(x, y, &text, &shadow_paint, render_mode);
                 });
             }
@@ -2065,22 +3455,29 @@ where
         self.state_mut().shadow_color = Color::rgbaf(0.0, 0.0, 0.0, 0.0);
 
         let mut glyph_run_result = Ok(());
-        for (font_id, glyph_run) in &layout
+        for ((font_id, rotated), glyph_run) in &layout
             .glyphs
             .iter()
             .filter(|shaped_glyph| !shaped_glyph.c.is_control())
-            .chunk_by(|g| g.font_id)
+            .chunk_by(|g| (g.font_id, g.rotated))
         {
             glyph_run_result = self.draw_glyph_run(
-                glyph_run.map(|shaped_glyph| PositionedGlyph {
-                    x: shaped_glyph.x * invscale,
-                    y: shaped_glyph.y * invscale,
-                    glyph_id: shaped_glyph.glyph_id,
+                glyph_run.map(|shaped_glyph| {
+                    let (x, y) = (shaped_glyph.x * invscale, shaped_glyph.y * invscale);
+                    // Sideways glyphs are positioned in the frame that the
+                    // quarter turn maps back onto the layout.
+                    let (x, y) = if rotated { (y, -x) } else { (x, y) };
+                    PositionedGlyph {
+                        x,
+                        y,
+                        glyph_id: shaped_glyph.glyph_id,
+                    }
                 }),
                 paint,
                 font_id,
                 &normalized_coords,
                 render_mode,
+                rotated,
             );
             if glyph_run_result.is_err() {
                 break;
@@ -2100,9 +3497,13 @@ where
         // pass above already rendered the decorations into its coverage (it
         // re-enters draw_text, which draws them), so glyphs and decorations share
         // a single shadow and the lines must not cast a second one.
-        if glyph_run_result.is_ok() && !paint.text.text_decoration.is_none() && has_drawable_glyphs {
+        if glyph_run_result.is_ok()
+            && !paint.text.text_decoration.is_none()
+            && !paint.text.writing_mode.is_vertical()
+            && has_drawable_glyphs
+        {
             if let Some(metrics) = &run_font_metrics {
-                self.draw_text_decorations(paint, metrics, layout.baseline(), layout.x, layout.width());
+                self.draw_text_decorations(paint, metrics, &layout);
             }
         }
 
@@ -2113,16 +3514,152 @@ where
         Ok(layout)
     }
 
-    /// Emits the enabled text-decoration lines for a run as filled rectangles in
-    /// user space. `baseline` is the run baseline (user space, +y down), `x` the
-    /// run's left edge, and `width` its advance width. `metrics` is the run's
-    /// font metrics in the same user-space units as `baseline`.
     #[cfg(feature = "textlayout")]
-    fn draw_text_decorations(&mut self, paint: &Paint, metrics: &FontMetrics, baseline: f32, x: f32, width: f32) {
+    fn draw_text_on_path(
+        &mut self,
+        path: &Path,
+        offset: f32,
+        text: &str,
+        paint: &Paint,
+        render_mode: RenderMode,
+    ) -> Result<TextMetrics, ErrorKind> {
+        use itertools::Itertools;
+
+        let paint = &paint.optically_sized(self.font_scale());
+        let scale = self.font_scale() * self.device_px_ratio;
+        let invscale = 1.0 / scale;
+
+        let mut text_settings = paint.text.clone();
+        text_settings.font_size *= scale;
+        text_settings.letter_spacing *= scale;
+        // Text on a path is always set horizontally along it.
+        text_settings.writing_mode = WritingMode::HorizontalTb;
+
+        // Shaped along a straight line where x is the distance along the path
+        // and y the offset across it.
+        let mut layout = text::shape(
+            offset * scale,
+            0.0,
+            &mut self.text_context.borrow_mut(),
+            &text_settings,
+            text,
+            None,
+        )?;
+
+        let normalized_coords = {
+            let text_context = self.text_context.borrow();
+            text::normalize_variations(&text_context, &paint.text.font_ids, &paint.text.font_variations)
+        };
+
+        let measure = path.measure(self.tess_tol, paint.text.text_path_side == TextPathSide::Right);
+
+        // Every glyph of a cluster shares the position and rotation taken at the
+        // midpoint of the cluster's advance, so marks stay on their base.
+        let mut placed_glyphs = Vec::new();
+        for (_, cluster) in &layout
+            .glyphs
+            .iter()
+            .filter(|shaped_glyph| !shaped_glyph.c.is_control())
+            .chunk_by(|shaped_glyph| shaped_glyph.byte_index)
+        {
+            let cluster = cluster.collect::<Vec<_>>();
+            let start = cluster[0].x - cluster[0].offset_x;
+            let advance = cluster.iter().map(|shaped_glyph| shaped_glyph.advance_x).sum::<f32>();
+            let midpoint = (start + advance / 2.0) * invscale;
+
+            let Some((position, tangent)) = measure.sample(midpoint) else {
+                continue;
+            };
+            let transform = Transform2D::rotation(tangent.angle()) * Transform2D::translation(position.x, position.y);
+
+            for shaped_glyph in cluster {
+                let glyph = PositionedGlyph {
+                    x: shaped_glyph.x * invscale - midpoint,
+                    y: shaped_glyph.y * invscale,
+                    glyph_id: shaped_glyph.glyph_id,
+                };
+                placed_glyphs.push((shaped_glyph.font_id, glyph, shaped_glyph.width * invscale, transform));
+            }
+        }
+
+        // One shadow (or filter pass) for the whole run, as in `draw_text`,
+        // covering the line box of every placed glyph.
+        if (self.shadow_enabled() || self.filter_enabled()) && !placed_glyphs.is_empty() {
+            let (ascent, descent) = self
+                .text_context
+                .borrow()
+                .measure_font(paint.text.font_size, &paint.text.font_ids, &paint.text.font_variations)
+                .map_or((0.0, 0.0), |metrics| (metrics.ascender(), metrics.descender()));
+            let margin = paint.text.font_size * 0.2;
+
+            let canvas_transform = self.state().transform;
+            let mut device = Bounds::default();
+            for (_, glyph, width, transform) in &placed_glyphs {
+                let (x0, x1) = (glyph.x - margin, glyph.x + width + margin);
+                let (y0, y1) = (glyph.y - ascent - margin, glyph.y - descent + margin);
+                for (cx, cy) in [(x0, y0), (x1, y0), (x1, y1), (x0, y1)] {
+                    let (px, py) = transform.transform_point(cx, cy);
+                    let (dx, dy) = canvas_transform.transform_point(px, py);
+                    device.minx = device.minx.min(dx);
+                    device.miny = device.miny.min(dy);
+                    device.maxx = device.maxx.max(dx);
+                    device.maxy = device.maxy.max(dy);
+                }
+            }
+
+            if self.filter_enabled() {
+                let path = path.clone();
+                let text = text.to_owned();
+                let filter_paint = paint.clone();
+                self.render_filtered(device, move |canvas| {
+                    let _ = canvas.draw_text_on_path(&path, offset, &text, &filter_paint, render_mode);
+                });
+                layout.scale(invscale);
+                return Ok(layout);
+            }
+
+            if self.shadow_could_be_visible(device) {
+                let path = path.clone();
+                let text = text.to_owned();
+                let shadow_paint = paint.clone();
+                self.render_shadow(device, None, move |canvas| {
+                    let _ = canvas.draw_text_on_path(&path, offset, &text, &shadow_paint, render_mode);
+                });
+            }
+        }
+
+        let saved_shadow_color = self.state().shadow_color;
+        self.state_mut().shadow_color = Color::rgbaf(0.0, 0.0, 0.0, 0.0);
+
+        let mut glyph_run_result = Ok(());
+        for (font_id, glyph, _, transform) in placed_glyphs {
+            self.save();
+            self.set_transform(&transform);
+            glyph_run_result = self.draw_glyph_run([glyph], paint, font_id, &normalized_coords, render_mode, false);
+            self.restore();
+            if glyph_run_result.is_err() {
+                break;
+            }
+        }
+
+        self.state_mut().shadow_color = saved_shadow_color;
+        glyph_run_result?;
+
+        layout.scale(invscale);
+
+        Ok(layout)
+    }
+
+    /// Emits the enabled text-decoration lines for a run as filled shapes in
+    /// user space. `layout` is the run in user space (+y down), whose baseline
+    /// and advance box the lines follow. `metrics` is the run's font metrics in
+    /// the same user-space units.
+    #[cfg(feature = "textlayout")]
+    fn draw_text_decorations(&mut self, paint: &Paint, metrics: &FontMetrics, layout: &TextMetrics) {
         // NOTE: this assumes a horizontal writing mode. The lines run along the
         // advance direction (x) and are offset perpendicular to it (y), spanning
-        // `[x, x + width]` at a baseline-relative y. That is correct for both LTR
-        // and RTL runs, since a horizontal decoration is direction-independent.
+        // the run's advance box at a baseline-relative y. That is correct for both
+        // LTR and RTL runs, since a horizontal decoration is direction-independent.
         // A vertical writing mode (top-to-bottom) would need the lines to run
         // along y and offset along x, driven by vertical metrics; the geometry
         // below would have to be generalized to the advance axis rather than
@@ -2130,22 +3667,57 @@ where
         //
         // All enabled lines share one path (a single verbs/coords allocation) and
         // one fill_path call, so a run costs one draw no matter how many lines
-        // are on. The rects are disjoint horizontal bands, and the paint below
-        // forces NonZero filling, so any degenerate overlap (e.g. thickness
-        // clamping on tiny fonts) still paints solid.
+        // are on. The shapes are disjoint, and the paint below forces NonZero
+        // filling, so any degenerate overlap (e.g. thickness clamping on tiny
+        // fonts) still paints solid.
+        //
+        // Negative letter spacing can make the advance width negative; the
+        // lines still cover the box between the run's two ends.
+        let (x0, x1) = (
+            layout.x.min(layout.x + layout.width()),
+            layout.x.max(layout.x + layout.width()),
+        );
+        let baseline = layout.baseline();
+        let style = paint.text.text_decoration_style;
+
         let mut path = Path::new();
-        for (offset, thickness) in decoration_lines(paint.text.text_decoration, metrics) {
-            path.rect(x, baseline + offset - thickness / 2.0, width, thickness);
+        for line in decoration_lines(&paint.text, metrics) {
+            let center = baseline + line.offset;
+
+            let spans = if line.skip_ink {
+                // Leave a gap of one line thickness on either side of the ink.
+                let ink = self.text_context.borrow().glyph_ink_ranges(
+                    layout,
+                    &paint.text,
+                    center - line.half_extent,
+                    center + line.half_extent,
+                    self.tess_tol,
+                );
+                let gaps = ink
+                    .into_iter()
+                    .map(|(start, end)| (start - line.thickness, end + line.thickness));
+                subtract_ranges(x0, x1, gaps)
+            } else {
+                vec![(x0, x1)]
+            };
+
+            for (start, end) in spans {
+                add_decoration_line(&mut path, style, x0, start, end, center, line.thickness);
+            }
         }
 
         if path.is_empty() {
             return;
         }
 
-        // The decoration takes the text paint's color, matching SVG where the
-        // decoration uses the text fill. The full paint flavor (gradient/image)
-        // is reused as-is so a gradient-filled run gets a gradient-filled line.
-        let line_paint = paint.clone().with_fill_rule(FillRule::NonZero);
+        // The decoration takes the text paint, matching SVG where the decoration
+        // uses the text fill. The full paint flavor (gradient/image) is reused
+        // as-is so a gradient-filled run gets a gradient-filled line, unless a
+        // decoration color replaces it.
+        let mut line_paint = paint.clone().with_fill_rule(FillRule::NonZero);
+        if let Some(color) = paint.text.text_decoration_color {
+            line_paint.set_color(color);
+        }
         self.fill_path(&path, &line_paint);
     }
 
@@ -2156,6 +3728,7 @@ where
         font_id: FontId,
         normalized_coords: &[i16],
         render_mode: RenderMode,
+        sideways: bool,
     ) -> Result<(), ErrorKind> {
         // TODO: Early out if text is outside the canvas bounds, or maybe even check for each character in layout.
 
@@ -2204,6 +3777,15 @@ where
             }
         };
 
+        // Sideways glyphs are classified and rasterized upright like any
+        // other run, and only turned a quarter clockwise about their origin
+        // where they are drawn, so they still go through the atlas.
+        let turn = if sideways {
+            Transform2D::rotation(std::f32::consts::FRAC_PI_2)
+        } else {
+            Transform2D::identity()
+        };
+
         let need_direct_rendering = matches!(rasterization, Rasterization::Path);
         let effective_scale = match rasterization {
             Rasterization::ScaledAtlas { scale, .. } => scale,
@@ -2236,9 +3818,10 @@ where
         let glyphs_it = glyphs.into_iter();
         let non_color_glyphs = glyphs_it
             .filter(|glyph| {
-                if font
-                    .glyph(&font_face, glyph.glyph_id, normalized_coords)
-                    .is_some_and(|glyph| glyph.path.is_none())
+                if font.is_color_layer_glyph(&font_face, glyph.glyph_id)
+                    || font
+                        .glyph(&font_face, glyph.glyph_id, normalized_coords)
+                        .is_some_and(|glyph| glyph.path.is_none())
                 {
                     color_glyphs.push(glyph.clone());
 
@@ -2258,8 +3841,56 @@ where
             glyph_id: g.glyph_id,
         };
 
-        let mut draw_commands = if need_direct_rendering {
-            text::render_direct(
+        // Component alpha blending is only defined for solid colors composited
+        // source-over; everything else gets grayscale coverage. So do sideways
+        // glyphs, whose subpixel order would turn with them.
+        let antialiasing = if matches!(paint.flavor, PaintFlavor::Color(_))
+            && self.state().composite_operation == CompositeOperationState::default()
+            && !sideways
+        {
+            paint.text.antialiasing
+        } else {
+            TextAntialiasing::Grayscale
+        };
+
+        // COLR glyphs take the palette of the paint, and its color for layers
+        // painted in the foreground color.
+        let color_style = ColorGlyphStyle {
+            palette: paint.text.font_palette,
+            foreground: match paint.flavor {
+                PaintFlavor::Color(color) => color.to_array().map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8),
+                _ => [0, 0, 0, 255],
+            },
+        };
+
+        // Faux bold and oblique for a weight or slant the font lacks.
+        let synthesis = font.synthesis(&paint.text.font_variations);
+
+        // Distance fields only carry coverage, so they are limited to solid
+        // colors; other paints, and synthesized styles, keep the regular
+        // rasterizer.
+        let distance_field =
+            paint.text.distance_field && matches!(paint.flavor, PaintFlavor::Color(_)) && synthesis.is_none();
+
+        let mut draw_commands = if distance_field {
+            let commands = self.distance_field_atlas.clone().render_distance_field_atlas(
+                self,
+                font_id,
+                font,
+                &font_face,
+                non_color_glyphs.into_iter(),
+                paint.text.font_size,
+                normalized_coords,
+            )?;
+            let saved = self.state().transform;
+            self.state_mut().transform = turn * saved;
+            self.draw_distance_field_commands(commands, paint, render_mode);
+            self.state_mut().transform = saved;
+            GlyphDrawCommands::default()
+        } else if need_direct_rendering {
+            let saved = self.state().transform;
+            self.state_mut().transform = turn * saved;
+            let result = text::render_direct(
                 self,
                 font,
                 non_color_glyphs.into_iter(),
@@ -2268,8 +3899,11 @@ where
                 &stroke,
                 paint.text.font_size,
                 render_mode,
+                synthesis,
                 normalized_coords,
-            )?;
+            );
+            self.state_mut().transform = saved;
+            result?;
             GlyphDrawCommands::default()
         } else {
             self.glyph_atlas.clone().render_atlas(
@@ -2281,6 +3915,9 @@ where
                 effective_font_size,
                 stroke.line_width,
                 render_mode,
+                antialiasing,
+                color_style,
+                synthesis,
                 normalized_coords,
             )?
         };
@@ -2308,6 +3945,9 @@ where
                         paint.text.font_size,
                         stroke.line_width,
                         render_mode,
+                        antialiasing,
+                        color_style,
+                        synthesis,
                         normalized_coords,
                     )?
                 } else {
@@ -2320,6 +3960,9 @@ where
                         effective_font_size,
                         stroke.line_width,
                         render_mode,
+                        antialiasing,
+                        color_style,
+                        synthesis,
                         normalized_coords,
                     )?
                 }
@@ -2327,6 +3970,7 @@ where
 
             draw_commands.alpha_glyphs.extend(color_commands.alpha_glyphs);
             draw_commands.color_glyphs.extend(color_commands.color_glyphs);
+            draw_commands.subpixel_glyphs.extend(color_commands.subpixel_glyphs);
         }
 
         // For the scaled-atlas path, present the pre-scaled glyph quads with a
@@ -2349,14 +3993,83 @@ where
                 // quantization error times the glyph's distance from the origin.
                 let residual = true_scale / scale;
                 let saved = self.state().transform;
-                self.state_mut().transform = Transform2D::new(residual, 0.0, 0.0, residual, tx, ty);
+                self.state_mut().transform = turn * Transform2D::new(residual, 0.0, 0.0, residual, tx, ty);
+                self.draw_glyph_commands(draw_commands, paint);
+                self.state_mut().transform = saved;
+            }
+            _ => {
+                let saved = self.state().transform;
+                self.state_mut().transform = turn * saved;
                 self.draw_glyph_commands(draw_commands, paint);
                 self.state_mut().transform = saved;
             }
-            _ => self.draw_glyph_commands(draw_commands, paint),
         }
 
-        Ok(())
+        Ok(())
+    }
+
+    // Draws distance-field glyph quads with the FillDistanceField shader. The
+    // field's range and the effect sizes are handed to the shader in device
+    // pixels, so the edges stay one pixel wide at any zoom.
+    fn draw_distance_field_commands(
+        &mut self,
+        commands: Vec<text::DrawCommand>,
+        paint: &Paint,
+        render_mode: RenderMode,
+    ) {
+        let transform = self.state().transform;
+        let alpha = self.state().alpha;
+        let scissor = self.state().scissor;
+        let scale = transform.average_scale();
+
+        let px_range =
+            2.0 * text::distance_field::SPREAD * paint.text.font_size / text::distance_field::REFERENCE_SIZE * scale;
+        // Keep a pixel of the encoded range free: beyond it the field saturates
+        // and effects would fill the whole quad.
+        let reach = (px_range * 0.5 - 1.0).max(0.0);
+        let stroke_half_width = match render_mode {
+            RenderMode::Fill => 0.0,
+            RenderMode::Stroke => (paint.stroke.line_width * 0.5 * scale).clamp(0.0, reach),
+        };
+        let effects = paint.text.text_effects;
+        let outline_width = (effects.outline_width * scale).clamp(0.0, reach - stroke_half_width);
+        let glow_radius = (effects.glow_radius * scale).clamp(0.0, reach - stroke_half_width - outline_width);
+
+        let premultiplied = |mut color: Color| {
+            color.a *= alpha;
+            color.premultiplied().to_array()
+        };
+
+        let mut paint_flavor = paint.flavor.clone();
+        paint_flavor.mul_alpha(alpha);
+
+        for cmd in commands {
+            let verts = glyph_quad_vertices(&transform, &cmd.quads);
+            let glyph_texture = GlyphTexture::DistanceField(cmd.image_id);
+
+            let mut params = Params::new(
+                &self.images,
+                &transform,
+                &paint_flavor,
+                &glyph_texture,
+                &scissor,
+                1.0,
+                self.fringe_width,
+                -1.0,
+            );
+            params.shader_type = ShaderType::FillDistanceField;
+            params.outer_col = premultiplied(effects.outline_color);
+            params.sdf_glow_col = premultiplied(effects.glow_color);
+            params.sdf_params = [px_range, stroke_half_width, outline_width, glow_radius];
+
+            let mut cmd = Command::new(CommandType::Triangles { params });
+            cmd.composite_operation = self.state().composite_operation;
+            cmd.glyph_texture = glyph_texture;
+            cmd.triangles_verts = Some((self.verts.len(), verts.len()));
+            self.append_cmd(cmd);
+
+            self.verts.extend_from_slice(&verts);
+        }
     }
 
     fn render_triangles(
@@ -2365,6 +4078,7 @@ where
         transform: &Transform2D,
         paint_flavor: &PaintFlavor,
         glyph_texture: GlyphTexture,
+        composite_operation: CompositeOperationState,
     ) {
         let scissor = self.state().scissor;
 
@@ -2380,7 +4094,7 @@ where
         );
 
         let mut cmd = Command::new(CommandType::Triangles { params });
-        cmd.composite_operation = self.state().composite_operation;
+        cmd.composite_operation = composite_operation;
         cmd.glyph_texture = glyph_texture;
 
         if let &PaintFlavor::Image { id, .. } = paint_flavor {
@@ -2451,10 +4165,13 @@ where
         self.verts.clear();
         self.gradients
             .release_old_gradients(&mut self.images, &mut self.renderer);
-        self.release_shadow_images();
+        self.release_transient_images();
+        self.shadow_cache.evict(&mut self.images, &mut self.renderer);
         if let Some(atlas) = self.ephemeral_glyph_atlas.take() {
             atlas.clear(self);
         }
+        self.glyph_atlas.clone().end_frame(self);
+        self.distance_field_atlas.clone().end_frame(self);
     }
 }
 
@@ -3010,6 +4727,218 @@ fn fill_text_selects_atlas_or_path_rendering() {
     }
 }
 
+/// Distance-field text is drawn from glyphs generated once at the reference
+/// size: every size and transform (including ones that push regular text onto
+/// outline rendering) samples the same cached fields through the dedicated
+/// shader, with the effect sizes clamped to the field's range.
+#[cfg(feature = "textlayout")]
+#[test]
+fn distance_field_text_renders_any_transform_from_one_field() {
+    use crate::paint::GlyphTexture;
+    use renderer::{CommandType, ShaderType};
+
+    let renderer = RecordingRenderer::default();
+    let recorded = renderer.last_commands.clone();
+    let mut canvas = Canvas::new(renderer).unwrap();
+    canvas.set_size(4000, 4000, 1.0);
+    let font = canvas
+        .add_font_mem(include_bytes!("../examples/assets/amiri-regular.ttf"))
+        .expect("failed to load test font");
+
+    let effects = TextEffects {
+        outline_width: 100.0,
+        outline_color: Color::rgb(255, 0, 0),
+        glow_radius: 2.0,
+        glow_color: Color::rgb(0, 0, 255),
+    };
+    let paint = Paint::color(Color::black())
+        .with_font(&[font])
+        .with_text_distance_field(true)
+        .with_text_effects(effects);
+
+    let mut cached_glyphs = None;
+    for (transform, font_size) in [
+        (Transform2D::identity(), 16.0),
+        (Transform2D::rotation(std::f32::consts::FRAC_PI_4), 16.0),
+        (Transform2D::scaling(20.0, 20.0), 16.0),
+        (Transform2D::identity(), 100.0),
+    ] {
+        canvas.set_transform(&transform);
+        canvas
+            .fill_text(10.0, 40.0, "Hello", &paint.clone().with_font_size(font_size))
+            .unwrap();
+        canvas.flush_to_output(());
+        canvas.reset_transform();
+
+        let commands = recorded.borrow();
+        let mut glyph_draws = 0;
+        for command in commands.iter() {
+            match (&command.cmd_type, command.glyph_texture) {
+                (CommandType::Triangles { params }, GlyphTexture::DistanceField(_)) => {
+                    glyph_draws += 1;
+                    assert_eq!(params.shader_type, ShaderType::FillDistanceField);
+                    let [px_range, stroke_half_width, outline_width, glow_radius] = params.sdf_params;
+                    assert_eq!(stroke_half_width, 0.0);
+                    // The oversized outline is clamped to what the field encodes.
+                    assert!(outline_width > 0.0 && outline_width <= px_range * 0.5 - 1.0);
+                    assert!(glow_radius >= 0.0 && outline_width + glow_radius <= px_range * 0.5 - 1.0);
+                }
+                (_, glyph_texture) => assert_eq!(glyph_texture, GlyphTexture::None, "unexpected glyph draw"),
+            }
+        }
+        assert!(glyph_draws > 0, "no distance-field glyphs drawn");
+
+        let count = canvas.distance_field_atlas.rendered_glyphs.borrow().len();
+        assert_eq!(
+            *cached_glyphs.get_or_insert(count),
+            count,
+            "fields regenerated for a new size"
+        );
+    }
+    assert!(canvas.glyph_atlas.rendered_glyphs.borrow().is_empty());
+}
+
+/// Subpixel glyphs are composited with component alpha in two passes, and
+/// only for solid colors drawn source-over; other paints and composite
+/// operations fall back to grayscale masks.
+#[cfg(feature = "textlayout")]
+#[test]
+fn subpixel_text_uses_component_alpha_passes() {
+    use crate::paint::GlyphTexture;
+
+    let renderer = RecordingRenderer::default();
+    let recorded = renderer.last_commands.clone();
+    let mut canvas = Canvas::new(renderer).unwrap();
+    canvas.set_size(400, 400, 1.0);
+    let font = canvas
+        .add_font_mem(include_bytes!("../examples/assets/amiri-regular.ttf"))
+        .expect("failed to load test font");
+
+    let mut glyph_draws = |paint: &Paint, composite_operation| {
+        canvas.global_composite_operation(composite_operation);
+        canvas.fill_text(10.0, 40.0, "Hello", paint).unwrap();
+        canvas.flush_to_output(());
+        recorded
+            .borrow()
+            .iter()
+            .filter(|command| command.glyph_texture != GlyphTexture::None)
+            .map(|command| (command.glyph_texture, command.composite_operation))
+            .collect::<Vec<_>>()
+    };
+
+    let paint = Paint::color(Color::black())
+        .with_font(&[font])
+        .with_text_antialiasing(TextAntialiasing::SubpixelBgr);
+    let draws = glyph_draws(&paint, CompositeOperation::SourceOver);
+    assert!(!draws.is_empty());
+    for pair in draws.chunks(2) {
+        let [(coverage, coverage_blend), (color, color_blend)] = pair else {
+            panic!("unpaired subpixel pass: {pair:?}");
+        };
+        assert!(matches!(coverage, GlyphTexture::SubpixelCoverage(_)));
+        assert_eq!(coverage_blend.dst_rgb, BlendFactor::OneMinusSrcColor);
+        assert!(matches!(color, GlyphTexture::SubpixelColor(_)));
+        assert_eq!(*color_blend, CompositeOperationState::new(CompositeOperation::Lighter));
+    }
+
+    let gradient = Paint::linear_gradient(0.0, 0.0, 100.0, 0.0, Color::black(), Color::white())
+        .with_font(&[font])
+        .with_text_antialiasing(TextAntialiasing::SubpixelRgb);
+    let fallbacks = [
+        glyph_draws(&gradient, CompositeOperation::SourceOver),
+        glyph_draws(&paint, CompositeOperation::Xor),
+    ];
+    for draws in fallbacks {
+        assert!(!draws.is_empty());
+        assert!(draws
+            .iter()
+            .all(|(glyph_texture, _)| matches!(glyph_texture, GlyphTexture::AlphaMask(_))));
+    }
+}
+
+/// With a glyph atlas budget the atlas stops growing: the least recently used
+/// page is recycled, except while the current frame still samples it, in which
+/// case the atlas overflows until the next flush trims it back.
+#[cfg(feature = "textlayout")]
+#[test]
+fn glyph_atlas_budget_recycles_least_recently_used_pages() {
+    let mut canvas = Canvas::new(RecordingRenderer::default()).unwrap();
+    canvas.set_size(2000, 2000, 1.0);
+    let font = canvas
+        .add_font_mem(include_bytes!("../examples/assets/RobotoFlex-VariableFont.ttf"))
+        .expect("failed to load test font");
+    canvas.set_glyph_atlas_budget(Some(2 * text::PAGE_BYTES));
+
+    let alphabet = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+    let draw = |canvas: &mut Canvas<RecordingRenderer>, font_size: f32| {
+        let paint = Paint::color(Color::black())
+            .with_font(&[font])
+            .with_font_size(font_size);
+        canvas.fill_text(10.0, 100.0, alphabet, &paint).unwrap();
+    };
+
+    // Each size fills most of a page.
+    for font_size in [80.0, 84.0] {
+        draw(&mut canvas, font_size);
+        canvas.flush_to_output(());
+    }
+    let stats = canvas.glyph_atlas_stats();
+    assert_eq!(stats.pages, 2);
+    assert_eq!(stats.evicted_glyphs, 0);
+    assert!(stats.fill_ratio > 0.5 && stats.fill_ratio <= 1.0);
+
+    // A third size in a later frame recycles pages instead of growing.
+    draw(&mut canvas, 88.0);
+    let stats = canvas.glyph_atlas_stats();
+    assert_eq!(stats.pages, 2);
+    assert!(stats.recycled_pages > 0);
+    assert!(stats.evicted_glyphs > 0);
+    canvas.flush_to_output(());
+
+    // Drawing all three sizes in one frame can't recycle pages that are in
+    // use, so the atlas grows until the flush.
+    for font_size in [80.0, 84.0, 88.0] {
+        draw(&mut canvas, font_size);
+    }
+    assert!(canvas.glyph_atlas_stats().pages > 2);
+    canvas.flush_to_output(());
+    let stats = canvas.glyph_atlas_stats();
+    assert_eq!(stats.pages, 2);
+    assert_eq!(stats.texture_bytes, 2 * text::PAGE_BYTES);
+
+    // Entries of the deleted pages were evicted; the rest still draw.
+    assert!(stats.glyphs < 3 * alphabet.len());
+    draw(&mut canvas, 88.0);
+    canvas.flush_to_output(());
+}
+
+/// A glyph too large for an empty page fails without recycling a page: evicting
+/// the cached glyphs wouldn't make room for it.
+#[cfg(feature = "textlayout")]
+#[test]
+fn glyph_atlas_budget_keeps_pages_for_glyphs_too_large_to_fit() {
+    let mut canvas = Canvas::new(RecordingRenderer::default()).unwrap();
+    canvas.set_size(2000, 2000, 1.0);
+    let font = canvas
+        .add_font_mem(include_bytes!("../examples/assets/RobotoFlex-VariableFont.ttf"))
+        .expect("failed to load test font");
+    canvas.set_glyph_atlas_budget(Some(text::PAGE_BYTES));
+
+    let paint = Paint::color(Color::black()).with_font(&[font]).with_font_size(40.0);
+    canvas.fill_text(10.0, 100.0, "Hello", &paint).unwrap();
+    canvas.flush_to_output(());
+    let before = canvas.glyph_atlas_stats();
+    assert_eq!(before.pages, 1);
+
+    // A stroke this wide rasterizes glyphs larger than a page.
+    let paint = paint.with_line_width(600.0);
+    assert!(canvas.stroke_text(10.0, 100.0, "W", &paint).is_err());
+    let after = canvas.glyph_atlas_stats();
+    assert_eq!(after.recycled_pages, 0);
+    assert_eq!(after.evicted_glyphs, 0);
+    assert_eq!(after.glyphs, before.glyphs);
+}
+
 /// The Canvas 2D shadow attributes must start at their spec-mandated defaults:
 /// a fully transparent shadow color, zero blur and zero offset.
 #[test]
@@ -3110,8 +5039,9 @@ fn shadow_enable_rule_requires_blur_or_offset() {
         canvas.set_size(100, 100, 1.0);
         configure(&mut canvas);
 
+        // A circle, since rounded rectangles are shadowed in closed form.
         let mut path = Path::new();
-        path.rect(10.0, 10.0, 30.0, 30.0);
+        path.circle(25.0, 25.0, 15.0);
         canvas.fill_path(&path, &Paint::color(Color::rgb(255, 0, 0)));
         canvas.flush_to_output(());
 
@@ -3243,14 +5173,15 @@ fn opaque_shadow_emits_offscreen_blur_pass() {
     canvas.set_shadow_blur(6.0);
     canvas.set_shadow_offset(4.0, 4.0);
 
+    // Rounded rectangles are shadowed in closed form, so use a circle.
     let mut path = Path::new();
-    path.rect(10.0, 10.0, 30.0, 30.0);
+    path.circle(25.0, 25.0, 15.0);
     canvas.fill_path(&path, &Paint::color(Color::rgb(255, 0, 0)));
     canvas.flush_to_output(());
 
     let commands = recorded.borrow();
     let filtered = commands.iter().find_map(|c| match c.cmd_type {
-        CommandType::RenderFilteredImage { filter, .. } => Some(filter),
+        CommandType::RenderFilteredImage { ref filter, .. } => Some(filter.clone()),
         _ => None,
     });
 
@@ -3266,12 +5197,312 @@ fn opaque_shadow_emits_offscreen_blur_pass() {
         None => panic!("opaque shadow must run the Gaussian blur filter"),
     }
 
-    assert!(
-        commands
-            .iter()
-            .any(|c| matches!(c.cmd_type, CommandType::SetRenderTarget(RenderTarget::Image(_)))),
-        "opaque shadow must render coverage into an offscreen image target"
-    );
+    assert!(
+        commands
+            .iter()
+            .any(|c| matches!(c.cmd_type, CommandType::SetRenderTarget(RenderTarget::Image(_)))),
+        "opaque shadow must render coverage into an offscreen image target"
+    );
+}
+
+/// A solid rounded rectangle casts its shadow in closed form, with no
+/// offscreen pass, as does an explicit box shadow; a skewed one falls back to
+/// blurring offscreen.
+#[test]
+fn rounded_rect_shadow_is_drawn_in_closed_form() {
+    use renderer::CommandType;
+
+    let renderer = RecordingRenderer::default();
+    let recorded = renderer.last_commands.clone();
+    let mut canvas = Canvas::new(renderer).unwrap();
+    canvas.set_size(100, 100, 1.0);
+
+    let box_shadows = |commands: &[Command]| {
+        commands
+            .iter()
+            .filter(|c| match c.cmd_type {
+                CommandType::ConvexFill { params } => params.shader_type == ShaderType::BoxShadow,
+                CommandType::ConcaveFill { fill_params, .. } => fill_params.shader_type == ShaderType::BoxShadow,
+                _ => false,
+            })
+            .count()
+    };
+    let offscreen = |commands: &[Command]| {
+        commands
+            .iter()
+            .any(|c| matches!(c.cmd_type, CommandType::SetRenderTarget(RenderTarget::Image(_))))
+    };
+
+    canvas.set_shadow_color(Color::rgba(0, 0, 0, 128));
+    canvas.set_shadow_blur(6.0);
+    canvas.set_shadow_offset(4.0, 4.0);
+
+    let mut path = Path::new();
+    path.rounded_rect(10.0, 10.0, 30.0, 20.0, 5.0);
+    canvas.rotate(0.3);
+    canvas.fill_path(&path, &Paint::color(Color::rgb(255, 0, 0)));
+    canvas.draw_box_shadow(
+        10.0,
+        50.0,
+        30.0,
+        20.0,
+        5.0,
+        &BoxShadow::new(0.0, 2.0, 8.0, Color::black()).with_spread(2.0),
+    );
+    canvas.flush_to_output(());
+    assert_eq!(box_shadows(&recorded.borrow()), 2);
+    assert!(!offscreen(&recorded.borrow()));
+
+    canvas.skew_x(0.3);
+    canvas.fill_path(&path, &Paint::color(Color::rgb(255, 0, 0)));
+    canvas.flush_to_output(());
+    assert_eq!(box_shadows(&recorded.borrow()), 0);
+    assert!(offscreen(&recorded.borrow()));
+}
+
+/// The blurred shadow of a path drawn again with the same transform, paint and
+/// shadow is taken from the shadow cache in later frames, whatever the offset;
+/// anything that changes the image renders it again.
+#[test]
+fn shadow_cache_keeps_blurred_images_across_frames() {
+    use renderer::CommandType;
+
+    let renderer = RecordingRenderer::default();
+    let recorded = renderer.last_commands.clone();
+    let mut canvas = Canvas::new(renderer).unwrap();
+    canvas.set_size(100, 100, 1.0);
+    canvas.set_shadow_color(Color::rgba(0, 0, 0, 128));
+    canvas.set_shadow_blur(6.0);
+
+    let mut path = Path::new();
+    path.circle(40.0, 40.0, 15.0);
+    let paint = Paint::color(Color::rgb(255, 0, 0));
+    let blurs = |canvas: &mut Canvas<RecordingRenderer>, paint: &Paint| {
+        canvas.fill_path(&path, paint);
+        canvas.stroke_path(&path, paint);
+        canvas.flush_to_output(());
+        recorded
+            .borrow()
+            .iter()
+            .filter(|c| matches!(c.cmd_type, CommandType::RenderFilteredImage { .. }))
+            .count()
+    };
+
+    assert_eq!(blurs(&mut canvas, &paint), 2);
+    canvas.set_shadow_offset(3.0, -2.0);
+    canvas.set_global_alpha(0.5);
+    assert_eq!(blurs(&mut canvas, &paint), 0);
+
+    canvas.set_shadow_blur(4.0);
+    assert_eq!(blurs(&mut canvas, &paint), 2);
+    assert_eq!(blurs(&mut canvas, &paint.clone().with_line_width(3.0)), 1);
+    canvas.translate(1.0, 0.0);
+    assert_eq!(blurs(&mut canvas, &paint), 2);
+
+    // Without the cache, every frame blurs and no image outlives its frame.
+    canvas.set_shadow_cache_limit(0);
+    assert_eq!(blurs(&mut canvas, &paint), 2);
+    assert_eq!(blurs(&mut canvas, &paint), 2);
+    let offscreen: Vec<_> = recorded
+        .borrow()
+        .iter()
+        .filter_map(|c| match c.cmd_type {
+            CommandType::SetRenderTarget(RenderTarget::Image(image)) => Some(image),
+            CommandType::RenderFilteredImage { target_image, .. } => Some(target_image),
+            _ => None,
+        })
+        .collect();
+    // The coverage and blurred images of the fill and the stroke.
+    assert_eq!(offscreen.len(), 4);
+    assert!(offscreen.iter().all(|image| canvas.image_info(*image).is_err()));
+}
+
+/// A canvas filter runs each draw through an offscreen image and one filter
+/// pass per filter, skipping filters that need a second image; clearing the
+/// filter returns to plain draws.
+#[test]
+fn canvas_filter_renders_draws_offscreen() {
+    use renderer::CommandType;
+
+    let renderer = RecordingRenderer::default();
+    let recorded = renderer.last_commands.clone();
+    let mut canvas = Canvas::new(renderer).unwrap();
+    canvas.set_size(100, 100, 1.0);
+
+    let backdrop = canvas
+        .create_image_empty(10, 10, PixelFormat::Rgba8, ImageFlags::empty())
+        .unwrap();
+    let mut filters = ImageFilter::parse_css("blur(2px) sepia(1)").unwrap();
+    filters.insert(
+        1,
+        ImageFilter::Blend {
+            mode: BlendMode::Multiply,
+            backdrop,
+        },
+    );
+    canvas.set_filter(&filters);
+
+    let mut path = Path::new();
+    path.rect(10.0, 10.0, 30.0, 30.0);
+    canvas.fill_path(&path, &Paint::color(Color::rgb(255, 0, 0)));
+    canvas.flush_to_output(());
+
+    let filtered: Vec<_> = recorded
+        .borrow()
+        .iter()
+        .filter_map(|c| match c.cmd_type {
+            CommandType::RenderFilteredImage { ref filter, .. } => Some(filter.clone()),
+            _ => None,
+        })
+        .collect();
+    assert!(
+        matches!(
+            filtered[..],
+            [ImageFilter::GaussianBlur { .. }, ImageFilter::ColorMatrix { .. }]
+        ),
+        "expected a blur then a color matrix pass, got {filtered:?}"
+    );
+    assert!(recorded
+        .borrow()
+        .iter()
+        .any(|c| matches!(c.cmd_type, CommandType::SetRenderTarget(RenderTarget::Image(_)))));
+
+    canvas.set_filter(&[]);
+    canvas.fill_path(&path, &Paint::color(Color::rgb(255, 0, 0)));
+    canvas.flush_to_output(());
+    assert!(!recorded
+        .borrow()
+        .iter()
+        .any(|c| matches!(c.cmd_type, CommandType::RenderFilteredImage { .. })));
+}
+
+/// A backdrop filter copies the region under the path, widened by the blur
+/// reach and clamped to the render target, before filtering it.
+#[test]
+fn backdrop_filter_copies_the_clamped_region() {
+    use renderer::CommandType;
+
+    let renderer = RecordingRenderer::default();
+    let recorded = renderer.last_commands.clone();
+    let mut canvas = Canvas::new(renderer).unwrap();
+    canvas.set_size(100, 100, 1.0);
+
+    let mut path = Path::new();
+    path.rect(-20.0, 50.0, 40.0, 20.0);
+    canvas.backdrop_filter(&path, ImageFilter::GaussianBlur { sigma: 2.0 });
+
+    let (backdrop, x, y) = canvas
+        .commands
+        .iter()
+        .find_map(|c| match c.cmd_type {
+            CommandType::CopyRenderTarget { target_image, x, y } => Some((target_image, x, y)),
+            _ => None,
+        })
+        .expect("the backdrop must be copied");
+    assert_eq!((x, y), (0, 43));
+    assert_eq!(canvas.image_size(backdrop).unwrap(), (27, 34));
+
+    canvas.flush_to_output(());
+    assert!(recorded.borrow().iter().any(|c| matches!(
+        c.cmd_type,
+        CommandType::RenderFilteredImage {
+            filter: ImageFilter::GaussianBlur { .. },
+            ..
+        }
+    )));
+}
+
+#[test]
+fn backdrop_filter_ignores_filters_it_cannot_run() {
+    let mut canvas = Canvas::new(RecordingRenderer::default()).unwrap();
+    canvas.set_size(100, 100, 1.0);
+    let flags = ImageFlags::PREMULTIPLIED | ImageFlags::FLIP_Y;
+    let image = canvas.create_image_empty(20, 20, PixelFormat::Rgba8, flags).unwrap();
+
+    let mut path = Path::new();
+    path.rect(10.0, 10.0, 40.0, 20.0);
+    let recorded_before = canvas.commands.len();
+    for filter in [
+        ImageFilter::GaussianBlur { sigma: 0.0 },
+        ImageFilter::Blend {
+            backdrop: image,
+            mode: BlendMode::Multiply,
+        },
+    ] {
+        canvas.backdrop_filter(&path, filter.clone());
+        assert_eq!(canvas.commands.len(), recorded_before, "{filter:?} must draw nothing");
+    }
+}
+
+#[test]
+fn filter_graph_runs_on_transient_images() {
+    use renderer::CommandType;
+
+    let renderer = RecordingRenderer::default();
+    let recorded = renderer.last_commands.clone();
+    let mut canvas = Canvas::new(renderer).unwrap();
+    canvas.set_size(100, 100, 1.0);
+
+    let flags = ImageFlags::PREMULTIPLIED | ImageFlags::FLIP_Y;
+    let source = canvas.create_image_empty(20, 20, PixelFormat::Rgba8, flags).unwrap();
+    let target = canvas.create_image_empty(20, 20, PixelFormat::Rgba8, flags).unwrap();
+    let small = canvas.create_image_empty(10, 20, PixelFormat::Rgba8, flags).unwrap();
+
+    let graph = FilterGraph::new()
+        .with_primitive(FilterPrimitive::new(FilterEffect::Flood { color: Color::white() }).with_result("flood"))
+        .with_primitive(FilterEffect::Blend {
+            input: FilterInput::SourceGraphic,
+            input2: "flood".into(),
+            mode: BlendMode::Multiply,
+        });
+
+    // Invalid images fail before anything is recorded.
+    let recorded_before = canvas.commands.len();
+    let missing = canvas.create_image_empty(20, 20, PixelFormat::Rgba8, flags).unwrap();
+    canvas.delete_image(missing);
+    assert!(matches!(
+        canvas.filter_image_graph(target, &graph, missing),
+        Err(ErrorKind::ImageIdNotFound)
+    ));
+    assert!(matches!(
+        canvas.filter_image_graph(small, &graph, source),
+        Err(ErrorKind::GeneralError(_))
+    ));
+    assert_eq!(canvas.commands.len(), recorded_before);
+
+    canvas.filter_image_graph(target, &graph, source).unwrap();
+    assert_eq!(canvas.current_render_target, RenderTarget::Screen);
+    let transient = canvas.transient_images.clone();
+    // The flood, the source copy, the blend scratch and the blend result.
+    assert_eq!(transient.len(), 4);
+    canvas.flush_to_output(());
+
+    let commands = recorded.borrow();
+    let filters: Vec<_> = commands
+        .iter()
+        .filter_map(|c| match c.cmd_type {
+            CommandType::RenderFilteredImage { ref filter, .. } => Some((filter.clone(), c.image)),
+            _ => None,
+        })
+        .collect();
+    assert!(matches!(
+        filters[..],
+        [(
+            ImageFilter::Blend {
+                mode: BlendMode::Multiply,
+                backdrop
+            },
+            Some(image)
+        )] if backdrop == transient[0] && image == transient[1]
+    ));
+    assert!(matches!(
+        commands.last().map(|c| &c.cmd_type),
+        Some(CommandType::SetRenderTarget(RenderTarget::Screen))
+    ));
+
+    // The intermediates are released once the frame is flushed.
+    assert!(transient.iter().all(|image| canvas.image_info(*image).is_err()));
+    assert!(canvas.image_info(target).is_ok());
 }
 
 /// Known limitation: the blur shader uses the true (spec) Gaussian weights but
@@ -3297,7 +5528,7 @@ fn large_shadow_blur_records_unclamped_spec_sigma() {
     canvas.set_shadow_blur(40.0);
 
     let mut path = Path::new();
-    path.rect(40.0, 40.0, 20.0, 20.0);
+    path.circle(50.0, 50.0, 10.0);
     canvas.fill_path(&path, &Paint::color(Color::rgb(255, 0, 0)));
     canvas.flush_to_output(());
 
@@ -3728,11 +5959,11 @@ fn fill_text_emits_decoration_rects() {
     }
 }
 
-/// A decoration line spans exactly the run's advance box `[layout.x, layout.x
-/// + width]`. Alignment moves `layout.x` (Center/Right shift the run left of
-/// the requested x), and an RTL run lays its glyphs out right-to-left — in
-/// every case the line must track the box the glyphs actually occupy, not the
-/// requested draw position.
+/// A decoration line spans exactly the run's advance box
+/// `[layout.x, layout.x + width]`. Alignment moves `layout.x` (Center/Right
+/// shift the run left of the requested x), and an RTL run lays its glyphs out
+/// right-to-left — in every case the line must track the box the glyphs
+/// actually occupy, not the requested draw position.
 #[cfg(feature = "textlayout")]
 #[test]
 fn decoration_rect_spans_the_run_advance_box() {
@@ -3776,6 +6007,7 @@ fn decoration_rect_spans_the_run_advance_box() {
             Align::Center | Align::Right => {
                 assert!(layout.x < anchor_x, "{case}: aligned run starts left of the anchor")
             }
+            _ => unreachable!("{case}: only physical alignments are exercised"),
         }
 
         let fills = recorded_decoration_fills(&commands.borrow(), &verts.borrow());
@@ -3814,6 +6046,203 @@ fn decoration_rect_spans_the_run_advance_box() {
     }
 }
 
+/// Static regular faces asked for a bold weight or an italic slant are
+/// emboldened and sheared, cached apart from the regular glyphs; faces that
+/// cover the request with their own axes are left alone.
+#[cfg(feature = "textlayout")]
+#[test]
+fn synthetic_bold_and_oblique_for_static_fonts() {
+    let renderer = RecordingRenderer::default();
+    let verts = renderer.last_verts.clone();
+    let mut canvas = Canvas::new(renderer).unwrap();
+    canvas.set_size(1000, 1000, 1.0);
+    let amiri = canvas
+        .add_font_mem(include_bytes!("../examples/assets/amiri-regular.ttf"))
+        .expect("failed to load test font");
+    let roboto = canvas
+        .add_font_mem(include_bytes!("../examples/assets/RobotoFlex-VariableFont.ttf"))
+        .expect("failed to load test font");
+
+    let regular = Paint::color(Color::black()).with_font(&[amiri]);
+    let bold = regular.clone().with_font_weight(700.0);
+    let italic = regular.clone().with_font_italic(true);
+
+    {
+        let context = canvas.text_context.borrow();
+        let amiri = context.font(amiri).unwrap();
+        let roboto = context.font(roboto).unwrap();
+        assert!(amiri.synthesis(&regular.text.font_variations).is_none());
+        assert!(amiri.synthesis(&bold.text.font_variations).bold);
+        assert!(amiri.synthesis(&italic.text.font_variations).oblique);
+        assert!(roboto.synthesis(&bold.text.font_variations).is_none());
+        assert!(roboto.synthesis(&italic.text.font_variations).is_none());
+    }
+
+    // Outlines drawn as paths: the bounds of the emitted vertices.
+    let mut bounds = |paint: &Paint| {
+        canvas
+            .fill_text(100.0, 500.0, "l", &paint.clone().with_font_size(300.0))
+            .unwrap();
+        canvas.flush_to_output(());
+        verts.borrow().iter().fold(
+            (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+            |(min_x, min_y, max_x, max_y), v| (min_x.min(v.x), min_y.min(v.y), max_x.max(v.x), max_y.max(v.y)),
+        )
+    };
+    let (left, top, right, bottom) = bounds(&regular);
+
+    // Emboldened by 1/24 of the em: 12.5 pixels, half of it on each side.
+    let (bold_left, bold_top, bold_right, bold_bottom) = bounds(&bold);
+    for grown in [
+        left - bold_left,
+        top - bold_top,
+        bold_right - right,
+        bold_bottom - bottom,
+    ] {
+        assert!((5.0..8.0).contains(&grown), "bold grew the outline by {grown}");
+    }
+
+    // The stem leans right by at most tan(14°) of its height, its foot stays
+    // put.
+    let (italic_left, italic_top, italic_right, italic_bottom) = bounds(&italic);
+    assert!((italic_left - left).abs() < 1.0);
+    assert!((italic_top - top).abs() < 1.0 && (italic_bottom - bottom).abs() < 1.0);
+    let lean = italic_right - right;
+    assert!(
+        lean > 10.0 && lean < (bottom - top) * 0.25 + 1.0,
+        "oblique leaned by {lean}"
+    );
+
+    // text_to_path outlines lean the same way.
+    let path_right = |paint: &Paint| {
+        let path = canvas
+            .text_to_path(100.0, 500.0, "l", &paint.clone().with_font_size(300.0))
+            .unwrap();
+        path.verbs().fold(f32::MIN, |max_x, verb| match verb {
+            Verb::MoveTo(x, _) | Verb::LineTo(x, _) | Verb::BezierTo(.., x, _) => max_x.max(x),
+            _ => max_x,
+        })
+    };
+    let path_lean = path_right(&italic) - path_right(&regular);
+    assert!((path_lean - lean).abs() < 2.0, "text_to_path leaned by {path_lean}");
+
+    // Atlas glyphs are keyed by their synthesized styles.
+    let atlas_glyphs = |canvas: &mut Canvas<RecordingRenderer>, paint: &Paint| {
+        canvas.fill_text(100.0, 100.0, "l", paint).unwrap();
+        canvas.flush_to_output(());
+        canvas.glyph_atlas.rendered_glyphs.borrow().len()
+    };
+    assert_eq!(atlas_glyphs(&mut canvas, &regular), 1);
+    assert_eq!(atlas_glyphs(&mut canvas, &bold), 2);
+    assert_eq!(atlas_glyphs(&mut canvas, &italic), 3);
+    assert_eq!(atlas_glyphs(&mut canvas, &bold), 3);
+}
+
+/// A faux bold glyph is drawn as one coverage of the paint: the fill and the
+/// emboldening pen share a stencil, so a translucent paint is not blended
+/// twice where the pen overlaps the fill.
+#[cfg(feature = "textlayout")]
+#[test]
+fn synthetic_bold_covers_translucent_paint_once() {
+    use renderer::CommandType;
+
+    let renderer = RecordingRenderer::default();
+    let recorded_commands = renderer.last_commands.clone();
+    let recorded_verts = renderer.last_verts.clone();
+    let mut canvas = Canvas::new(renderer).unwrap();
+    canvas.set_size(1000, 1000, 1.0);
+    let amiri = canvas
+        .add_font_mem(include_bytes!("../examples/assets/amiri-regular.ttf"))
+        .expect("failed to load test font");
+
+    // Stencil winding along the row at `y`, at each whole x.
+    let coverage = |canvas: &mut Canvas<RecordingRenderer>, paint: &Paint, y: f32| {
+        canvas.fill_text(100.0, 500.0, "l", paint).unwrap();
+        canvas.flush_to_output(());
+        let commands = recorded_commands.borrow();
+        let verts = recorded_verts.borrow();
+        let draws: Vec<_> = commands
+            .iter()
+            .filter(|command| !matches!(command.cmd_type, CommandType::SetRenderTarget(_)))
+            .collect();
+        let [command] = draws[..] else {
+            panic!("expected a single draw command, got {}", draws.len());
+        };
+        assert!(matches!(command.cmd_type, CommandType::ConcaveFill { .. }));
+        let triangles: Vec<_> = command
+            .drawables
+            .iter()
+            .filter_map(|drawable| drawable.fill_verts)
+            .flat_map(|(offset, count)| verts[offset..offset + count].chunks_exact(3))
+            .map(|triangle| triangle.to_vec())
+            .collect();
+        (0..1000)
+            .map(|x| {
+                let point = [Vertex::new(x as f32, y, 0.0, 0.0)];
+                triangles
+                    .iter()
+                    .filter(|triangle| {
+                        let area = triangle_area(triangle);
+                        (0..3).all(|i| {
+                            let edge = [triangle[i], triangle[(i + 1) % 3], point[0]];
+                            triangle_area(&edge) * area >= 0.0
+                        })
+                    })
+                    .map(|triangle| triangle_area(triangle).signum() as i32)
+                    .sum::<i32>()
+            })
+            .collect::<Vec<_>>()
+    };
+    let covered = |winding: &[i32]| -> Vec<usize> { (0..winding.len()).filter(|&x| winding[x] != 0).collect() };
+
+    let regular = Paint::color(Color::rgbaf(0.0, 0.0, 1.0, 0.5))
+        .with_font(&[amiri])
+        .with_font_size(300.0);
+    let bold = regular.clone().with_font_weight(700.0);
+
+    // Across the middle of the stem, the grown outline is covered as one
+    // span, about 1/24 of the em wider than the regular stem.
+    let y = 400.0;
+    let stem = covered(&coverage(&mut canvas, &regular, y));
+    let bold_stem = covered(&coverage(&mut canvas, &bold, y));
+    for span in [&stem, &bold_stem] {
+        assert!(!span.is_empty() && span.windows(2).all(|pair| pair[1] == pair[0] + 1));
+    }
+    let grown = bold_stem.len() as f32 - stem.len() as f32;
+    assert!((10.0..15.0).contains(&grown), "bold grew the stem by {grown}");
+}
+
+/// Optical sizing adds `opsz` to the paint's variations; a paint that already
+/// sets as many axes as it can hold is drawn and measured without it.
+#[cfg(feature = "textlayout")]
+#[test]
+fn optical_sizing_with_all_variation_axes_set() {
+    let renderer = RecordingRenderer::default();
+    let verts = renderer.last_verts.clone();
+    let mut canvas = Canvas::new(renderer).unwrap();
+    canvas.set_size(400, 200, 1.0);
+    let font = canvas
+        .add_font_mem(include_bytes!("../examples/assets/RobotoFlex-VariableFont.ttf"))
+        .expect("failed to load test font");
+
+    let paint = Paint::color(Color::black())
+        .with_font(&[font])
+        .with_font_size(24.0)
+        .with_font_weight(700.0)
+        .with_font_italic(true)
+        .with_font_variation(b"wdth", 80.0);
+    let auto = paint.clone().with_font_optical_sizing(true);
+    assert_eq!(auto.optically_sized(2.0).font_variation(b"opsz"), None);
+
+    canvas.fill_text(10.0, 100.0, "Hello", &auto).unwrap();
+    canvas.flush_to_output(());
+    assert!(!verts.borrow().is_empty());
+
+    let measured = canvas.measure_text(10.0, 100.0, "Hello", &auto).unwrap();
+    let expected = canvas.measure_text(10.0, 100.0, "Hello", &paint).unwrap();
+    assert_eq!(measured.width(), expected.width());
+}
+
 /// Under a uniform-scale (scale-baked atlas) transform, the decoration rect must
 /// still line up with the glyphs: it is emitted in user space and run through the
 /// same canvas transform, so its screen-space center scales with the baseline.
@@ -3865,6 +6294,109 @@ fn decoration_rect_tracks_scaled_atlas_transform() {
     );
 }
 
+/// Decoration styles shape the line, an explicit thickness and underline
+/// offset override the font metrics, a decoration color replaces the paint,
+/// and skip-ink breaks the underline around descenders.
+#[cfg(feature = "textlayout")]
+#[test]
+fn styled_decorations_follow_style_thickness_and_offset() {
+    let baseline_y = 200.0_f32;
+    let underline = TextDecoration {
+        underline: true,
+        strikethrough: false,
+        overline: false,
+    };
+
+    // Returns the contour boxes of the decoration fill and its color.
+    let decoration = |text: &str, configure: &dyn Fn(Paint) -> Paint| {
+        let renderer = RecordingRenderer::default();
+        let commands = renderer.last_commands.clone();
+        let verts = renderer.last_verts.clone();
+        let mut canvas = Canvas::new(renderer).unwrap();
+        canvas.set_size(1000, 1000, 1.0);
+        let font = canvas
+            .add_font_mem(include_bytes!("../examples/assets/RobotoFlex-VariableFont.ttf"))
+            .expect("failed to load test font");
+        let paint = Paint::color(Color::black())
+            .with_font(&[font])
+            .with_font_size(40.0)
+            .with_text_decoration(underline);
+        canvas.fill_text(50.0, baseline_y, text, &configure(paint)).unwrap();
+        canvas.flush_to_output(());
+
+        let mut fills = recorded_decoration_fills(&commands.borrow(), &verts.borrow());
+        assert_eq!(fills.len(), 1, "expected one decoration fill, got {fills:?}");
+        // The decoration is drawn after the glyphs, so it is the last solid fill.
+        let color = commands
+            .borrow()
+            .iter()
+            .rev()
+            .find_map(|cmd| match &cmd.cmd_type {
+                CommandType::ConvexFill { params } if matches!(cmd.glyph_texture, GlyphTexture::None) => {
+                    Some(params.inner_col)
+                }
+                CommandType::ConcaveFill { fill_params, .. } => Some(fill_params.inner_col),
+                _ => None,
+            })
+            .expect("decoration fill command");
+        (fills.remove(0), color)
+    };
+    let explicit = |style: TextDecorationStyle| {
+        move |paint: Paint| {
+            paint
+                .with_text_decoration_style(style)
+                .with_text_decoration_thickness(Some(4.0))
+                .with_text_underline_offset(Some(6.0))
+        }
+    };
+    let near = |a: f32, b: f32| (a - b).abs() <= 0.5;
+
+    // Solid: the underline's top sits at the offset, four units thick.
+    let (solid, color) = decoration("Hello", &explicit(TextDecorationStyle::Solid));
+    assert_eq!(solid.len(), 1, "{solid:?}");
+    let (_, min_y, _, max_y) = solid[0];
+    assert!(
+        near(min_y, baseline_y + 6.0) && near(max_y, baseline_y + 10.0),
+        "{solid:?}"
+    );
+    assert_eq!(color, [0.0, 0.0, 0.0, 1.0]);
+
+    // Double: two lines one thickness apart, growing away from the text.
+    let (double, _) = decoration("Hello", &explicit(TextDecorationStyle::Double));
+    assert_eq!(double.len(), 2, "{double:?}");
+    assert!(near(double[0].1, baseline_y + 6.0) && near(double[0].3, baseline_y + 10.0));
+    assert!(near(double[1].1, baseline_y + 14.0) && near(double[1].3, baseline_y + 18.0));
+
+    // Dotted and dashed: a row of separate pieces along the run.
+    let (dotted, _) = decoration("Hello", &explicit(TextDecorationStyle::Dotted));
+    assert!(dotted.len() > 10, "{dotted:?}");
+    assert!(dotted.iter().all(|&(x0, _, x1, _)| x1 - x0 <= 4.5));
+    let (dashed, _) = decoration("Hello", &explicit(TextDecorationStyle::Dashed));
+    assert!(dashed.len() > 3 && dashed.len() < dotted.len(), "{dashed:?}");
+
+    // Wavy: one ribbon swinging a thickness either way around its center.
+    let (wavy, _) = decoration("Hello", &explicit(TextDecorationStyle::Wavy));
+    assert_eq!(wavy.len(), 1, "{wavy:?}");
+    // (Antialiasing fringes reach a little further out on the slanted edges.)
+    let (min_y, max_y) = (wavy[0].1, wavy[0].3);
+    assert!((min_y - (baseline_y + 6.0)).abs() <= 1.0, "{wavy:?}");
+    assert!((max_y - (baseline_y + 18.0)).abs() <= 1.0, "{wavy:?}");
+
+    // A decoration color replaces the paint for the lines only.
+    let (_, color) = decoration("Hello", &|paint: Paint| {
+        paint.with_text_decoration_color(Some(Color::rgbf(1.0, 0.0, 0.0)))
+    });
+    assert_eq!(color, [1.0, 0.0, 0.0, 1.0]);
+
+    // Skip-ink leaves gaps around the descenders crossing the underline.
+    let (plain, _) = decoration("Hugo", &|paint: Paint| paint);
+    assert_eq!(plain.len(), 1);
+    let (skipped, _) = decoration("Hugo", &|paint: Paint| paint.with_text_decoration_skip_ink(true));
+    assert!(skipped.len() > 1, "{skipped:?}");
+    let covered = |boxes: &[(f32, f32, f32, f32)]| boxes.iter().map(|&(x0, _, x1, _)| x1 - x0).sum::<f32>();
+    assert!(covered(&skipped) < covered(&plain));
+}
+
 /// The decoration baseline must be the shared run baseline, independent of the
 /// first drawable glyph's GPOS y-offset. `layout` bakes that offset into
 /// `glyph.y`, so a run beginning with a combining mark (non-zero `offset_y`)
@@ -4310,6 +6842,8 @@ fn layout_stores_the_run_baseline() {
         (Baseline::Top, y + metrics.ascender()),
         (Baseline::Middle, y + (metrics.ascender() + metrics.descender()) / 2.0),
         (Baseline::Bottom, y + metrics.descender()),
+        (Baseline::Hanging, y + metrics.hanging_baseline()),
+        (Baseline::Ideographic, y + metrics.ideographic_baseline()),
     ] {
         let paint = base_paint.clone().with_text_baseline(setting);
         let layout = canvas.measure_text(15.0, y, "Ay", &paint).expect("shaping succeeds");
@@ -4321,6 +6855,474 @@ fn layout_stores_the_run_baseline() {
     }
 }
 
+#[cfg(feature = "textlayout")]
+#[test]
+fn text_to_path_outlines_the_measured_run() {
+    let path_bounds = |path: &Path| {
+        let mut bounds = Bounds::default();
+        for verb in path.verbs() {
+            let points = match verb {
+                Verb::MoveTo(x, y) | Verb::LineTo(x, y) => vec![(x, y)],
+                Verb::BezierTo(c1x, c1y, c2x, c2y, x, y) => vec![(c1x, c1y), (c2x, c2y), (x, y)],
+                _ => vec![],
+            };
+            for (x, y) in points {
+                bounds.minx = bounds.minx.min(x);
+                bounds.miny = bounds.miny.min(y);
+                bounds.maxx = bounds.maxx.max(x);
+                bounds.maxy = bounds.maxy.max(y);
+            }
+        }
+        bounds
+    };
+
+    let outline = |dpr: f32| {
+        let mut canvas = Canvas::new(RecordingRenderer::default()).unwrap();
+        canvas.set_size(400, 200, dpr);
+        let font_id = canvas
+            .add_font_mem(include_bytes!("../examples/assets/RobotoFlex-VariableFont.ttf"))
+            .expect("failed to load test font");
+        let paint = Paint::color(Color::black()).with_font(&[font_id]).with_font_size(24.0);
+        let path = canvas
+            .text_to_path(15.0, 80.0, "Hello", &paint)
+            .expect("shaping succeeds");
+        let metrics = canvas
+            .measure_text(15.0, 80.0, "Hello", &paint)
+            .expect("shaping succeeds");
+        let font_metrics = canvas.measure_font(&paint).expect("font metrics");
+        (path, metrics, font_metrics)
+    };
+
+    let (path, metrics, font_metrics) = outline(1.0);
+    assert!(!path.is_empty());
+
+    // The outlines sit on the baseline inside the run's advance box.
+    let bounds = path_bounds(&path);
+    assert!(bounds.minx >= metrics.x && bounds.maxx <= metrics.x + metrics.width() + 1.0);
+    assert!(bounds.miny >= metrics.baseline() - font_metrics.ascender());
+    assert!(bounds.maxy <= metrics.baseline() - font_metrics.descender());
+    assert!(
+        bounds.maxy > metrics.baseline() - 1.0,
+        "glyphs should reach the baseline"
+    );
+
+    // Like fill_text, the path is in user space regardless of the DPI factor.
+    let hidpi = path_bounds(&outline(2.0).0);
+    for (a, b) in [
+        (bounds.minx, hidpi.minx),
+        (bounds.miny, hidpi.miny),
+        (bounds.maxx, hidpi.maxx),
+        (bounds.maxy, hidpi.maxy),
+    ] {
+        assert!((a - b).abs() < 0.5, "{a} vs {b}");
+    }
+}
+
+#[cfg(feature = "textlayout")]
+#[test]
+fn text_metrics_report_ink_font_and_em_boxes() {
+    let mut canvas = Canvas::new(RecordingRenderer::default()).unwrap();
+    canvas.set_size(400, 200, 1.0);
+    let font_id = canvas
+        .add_font_mem(include_bytes!("../examples/assets/RobotoFlex-VariableFont.ttf"))
+        .expect("failed to load test font");
+    let paint = Paint::color(Color::black())
+        .with_font(&[font_id])
+        .with_font_size(24.0)
+        .with_text_align(Align::Center);
+    let (x, y) = (100.0, 80.0);
+
+    // The ink box matches the outlines drawn for the same run.
+    let path = canvas.text_to_path(x, y, "Hg", &paint).expect("shaping succeeds");
+    let mut ink = Bounds::default();
+    for verb in path.verbs() {
+        if let Verb::MoveTo(px, py) | Verb::LineTo(px, py) | Verb::BezierTo(_, _, _, _, px, py) = verb {
+            ink.minx = ink.minx.min(px);
+            ink.miny = ink.miny.min(py);
+            ink.maxx = ink.maxx.max(px);
+            ink.maxy = ink.maxy.max(py);
+        }
+    }
+    let metrics = canvas.measure_text(x, y, "Hg", &paint).expect("shaping succeeds");
+    for (actual, expected) in [
+        (metrics.actual_bounding_box_left(), x - ink.minx),
+        (metrics.actual_bounding_box_right(), ink.maxx - x),
+        (metrics.actual_bounding_box_ascent(), y - ink.miny),
+        (metrics.actual_bounding_box_descent(), ink.maxy - y),
+    ] {
+        assert!((actual - expected).abs() < 0.5, "{actual} vs {expected}");
+    }
+    assert!(
+        metrics.actual_bounding_box_left() > 0.0,
+        "centered text extends left of x"
+    );
+    assert!(
+        metrics.actual_bounding_box_descent() > 0.0,
+        "the g descends below the baseline"
+    );
+
+    // Font and em boxes hang off the alphabetic baseline, which is y here.
+    let font_metrics = canvas.measure_font(&paint).expect("font metrics");
+    assert!((metrics.font_bounding_box_ascent() - font_metrics.ascender()).abs() < 1e-3);
+    assert!((metrics.font_bounding_box_descent() + font_metrics.descender()).abs() < 1e-3);
+    assert!((metrics.em_height_ascent() + metrics.em_height_descent() - 24.0).abs() < 1e-3);
+    assert_eq!(metrics.alphabetic_baseline(), 0.0);
+    assert!((metrics.hanging_baseline() - font_metrics.hanging_baseline()).abs() < 1e-3);
+    assert!((metrics.ideographic_baseline() - font_metrics.ideographic_baseline()).abs() < 1e-3);
+
+    // With a top baseline everything shifts down by the ascender, so y sits on
+    // the top of the font box.
+    let top = canvas
+        .measure_text(x, y, "Hg", &paint.clone().with_text_baseline(Baseline::Top))
+        .expect("shaping succeeds");
+    assert!(top.font_bounding_box_ascent().abs() < 1e-3);
+    assert!((top.alphabetic_baseline() + font_metrics.ascender()).abs() < 1e-3);
+    assert!(
+        (top.actual_bounding_box_descent() - metrics.actual_bounding_box_descent() - font_metrics.ascender()).abs()
+            < 1e-3
+    );
+
+    // Whitespace has no ink.
+    let blank = canvas.measure_text(x, y, "   ", &paint).expect("shaping succeeds");
+    assert_eq!(blank.actual_bounding_box_right(), 0.0);
+    assert_eq!(blank.actual_bounding_box_ascent(), 0.0);
+}
+
+#[cfg(feature = "textlayout")]
+#[test]
+fn vertical_text_sets_latin_sideways_and_symbols_upright() {
+    let mut canvas = Canvas::new(RecordingRenderer::default()).unwrap();
+    canvas.set_size(400, 400, 1.0);
+    let font_id = canvas
+        .add_font_mem(include_bytes!("../examples/assets/RobotoFlex-VariableFont.ttf"))
+        .expect("failed to load test font");
+    let horizontal = Paint::color(Color::black()).with_font(&[font_id]).with_font_size(24.0);
+    let vertical = horizontal.clone().with_writing_mode(WritingMode::VerticalRl);
+    let font_metrics = canvas.measure_font(&vertical).expect("font metrics");
+    let (x, y) = (100.0, 20.0);
+
+    // "AB" is set sideways, advancing down the column by its horizontal
+    // advances; U+00D7 stands upright (UAX #50 class U).
+    let layout = canvas
+        .measure_text(x, y, "AB\u{d7}", &vertical)
+        .expect("shaping succeeds");
+    let [a, b, times] = layout.glyphs[..] else {
+        panic!("expected three glyphs, got {}", layout.glyphs.len());
+    };
+    assert!(a.rotated && b.rotated && !times.rotated);
+    let latin = canvas
+        .measure_text(0.0, 0.0, "AB", &horizontal)
+        .expect("shaping succeeds");
+    assert!((b.y - a.y - latin.glyphs[0].advance_x).abs() < 1e-3);
+    assert_eq!(a.x, b.x);
+
+    // Without vertical metrics the upright glyph advances by the line box and
+    // is centered on the column.
+    let line_box = font_metrics.ascender() - font_metrics.descender();
+    assert!(
+        (times.advance_y - line_box).abs() < 0.5,
+        "{} vs {line_box}",
+        times.advance_y
+    );
+    let ink_center = times.x + times.bearing_x + times.width / 2.0;
+    assert!((ink_center - x).abs() < 1.0, "upright ink centered at {ink_center}");
+    assert!((layout.width() - latin.width() - times.advance_y).abs() < 1e-3);
+    assert_eq!(layout.baseline(), x);
+
+    // Sideways glyphs are centered by their line box, tops to the right.
+    assert!((a.x - (x - font_metrics.ascender().midpoint(font_metrics.descender()))).abs() < 1e-3);
+    assert!(layout.actual_bounding_box_left() > 0.0 && layout.actual_bounding_box_right() > 0.0);
+    assert!(layout.actual_bounding_box_descent() > latin.width());
+
+    // The outlines agree with the reported ink box.
+    let path = canvas
+        .text_to_path(x, y, "AB\u{d7}", &vertical)
+        .expect("shaping succeeds");
+    let mut ink = Bounds::default();
+    for verb in path.verbs() {
+        if let Verb::MoveTo(px, py) | Verb::LineTo(px, py) | Verb::BezierTo(_, _, _, _, px, py) = verb {
+            ink.minx = ink.minx.min(px);
+            ink.miny = ink.miny.min(py);
+            ink.maxx = ink.maxx.max(px);
+            ink.maxy = ink.maxy.max(py);
+        }
+    }
+    for (actual, expected) in [
+        (layout.actual_bounding_box_left(), x - ink.minx),
+        (layout.actual_bounding_box_right(), ink.maxx - x),
+        (layout.actual_bounding_box_ascent(), y - ink.miny),
+        (layout.actual_bounding_box_descent(), ink.maxy - y),
+    ] {
+        assert!((actual - expected).abs() < 0.5, "{actual} vs {expected}");
+    }
+
+    // Lines break by their length down the column, where the upright glyph
+    // takes more room than across a horizontal line.
+    let text = "\u{d7}\u{d7} \u{d7}\u{d7}";
+    let max_width = 3.0 * line_box;
+    assert_eq!(canvas.break_text_vec(max_width, text, &horizontal).unwrap().len(), 1);
+    assert_eq!(canvas.break_text_vec(max_width, text, &vertical).unwrap().len(), 2);
+
+    // Further lines of vertical-rl text stack to the left.
+    let lines = canvas
+        .fill_text_truncated(x, y, max_width, 2, text, &vertical)
+        .expect("drawing succeeds");
+    assert_eq!(lines.len(), 2);
+    assert!((lines[0].baseline() - lines[1].baseline() - font_metrics.height()).abs() < 1e-3);
+}
+
+#[cfg(feature = "textlayout")]
+#[test]
+fn sideways_glyphs_are_drawn_from_the_glyph_atlas() {
+    use renderer::CommandType;
+
+    let renderer = RecordingRenderer::default();
+    let recorded_commands = renderer.last_commands.clone();
+    let recorded_verts = renderer.last_verts.clone();
+    let mut canvas = Canvas::new(renderer).unwrap();
+    canvas.set_size(400, 400, 1.0);
+    let font_id = canvas
+        .add_font_mem(include_bytes!("../examples/assets/RobotoFlex-VariableFont.ttf"))
+        .expect("failed to load test font");
+    let paint = Paint::color(Color::black())
+        .with_font(&[font_id])
+        .with_font_size(24.0)
+        .with_writing_mode(WritingMode::VerticalRl);
+    let (x, y) = (100.0, 20.0);
+
+    let layout = canvas.fill_text(x, y, "Sideways", &paint).expect("drawing succeeds");
+    assert!(layout.glyphs.iter().all(|glyph| glyph.rotated));
+    canvas.flush_to_output(());
+
+    // The whole sideways run is one batch of atlas quads, not one outline
+    // fill per glyph.
+    let commands = recorded_commands.borrow();
+    let glyph_draws: Vec<_> = commands
+        .iter()
+        .filter(
+            |command| matches!(&command.cmd_type, CommandType::Triangles { params } if params.glyph_texture_type != 0),
+        )
+        .collect();
+    let [glyph_draw] = glyph_draws[..] else {
+        panic!("expected a single glyph draw, got {}", glyph_draws.len());
+    };
+    let (offset, count) = glyph_draw.triangles_verts.expect("glyph quads");
+    assert_eq!(count, 6 * layout.glyphs.len());
+
+    // The quads are turned onto the column, covering the run's ink box.
+    let verts = recorded_verts.borrow();
+    let mut quads = Bounds::default();
+    for vert in &verts[offset..offset + count] {
+        quads.minx = quads.minx.min(vert.x);
+        quads.miny = quads.miny.min(vert.y);
+        quads.maxx = quads.maxx.max(vert.x);
+        quads.maxy = quads.maxy.max(vert.y);
+    }
+    for (actual, expected) in [
+        (x - quads.minx, layout.actual_bounding_box_left()),
+        (quads.maxx - x, layout.actual_bounding_box_right()),
+        (y - quads.miny, layout.actual_bounding_box_ascent()),
+        (quads.maxy - y, layout.actual_bounding_box_descent()),
+    ] {
+        assert!((actual - expected).abs() < 3.0, "{actual} vs {expected}");
+    }
+}
+
+#[cfg(feature = "textlayout")]
+#[test]
+fn start_end_and_justify_alignment() {
+    let mut canvas = Canvas::new(RecordingRenderer::default()).unwrap();
+    canvas.set_size(400, 400, 1.0);
+    let roboto = canvas
+        .add_font_mem(include_bytes!("../examples/assets/RobotoFlex-VariableFont.ttf"))
+        .expect("failed to load test font");
+    let amiri = canvas
+        .add_font_mem(include_bytes!("../examples/assets/amiri-regular.ttf"))
+        .expect("failed to load test font");
+    let paint = Paint::color(Color::black()).with_font(&[roboto]).with_font_size(20.0);
+    let x = 200.0;
+
+    // Start and End follow the paragraph direction.
+    for (text, font, rtl) in [
+        ("Hello", roboto, false),
+        ("\u{645}\u{631}\u{62d}\u{628}\u{627}", amiri, true),
+    ] {
+        let paint = paint.clone().with_font(&[font]);
+        let start = canvas
+            .measure_text(x, 50.0, text, &paint.clone().with_text_align(Align::Start))
+            .unwrap();
+        let end = canvas
+            .measure_text(x, 50.0, text, &paint.clone().with_text_align(Align::End))
+            .unwrap();
+        let (left, right) = (x, x - start.width());
+        let (start_x, end_x) = if rtl { (right, left) } else { (left, right) };
+        assert!((start.x - start_x).abs() < 1e-3, "{text}: start at {}", start.x);
+        assert!((end.x - end_x).abs() < 1e-3, "{text}: end at {}", end.x);
+    }
+
+    // Justified lines but the last span the full width, with the extra space
+    // shared evenly between the word gaps.
+    let max_width = 150.0;
+    let text = "The quick brown fox jumps over the lazy dog and keeps on running";
+    let justified = paint.clone().with_text_align(Align::Justify);
+    let lines = canvas.fill_text_wrapped(x, 50.0, max_width, text, &justified).unwrap();
+    let plain = canvas.fill_text_wrapped(x, 50.0, max_width, text, &paint).unwrap();
+    assert!(lines.len() > 2);
+    let (last, full) = lines.split_last().unwrap();
+    for (line, plain) in full.iter().zip(&plain) {
+        assert!((line.width() - max_width).abs() < 1e-3);
+        let end = line.glyphs.last().map(|glyph| glyph.x + glyph.advance_x).unwrap();
+        assert!((end - (x + max_width)).abs() < 1e-3, "line ends at {end}");
+        let gaps = line.glyphs.iter().filter(|glyph| glyph.c == ' ').collect::<Vec<_>>();
+        let share = (max_width - plain.width()) / gaps.len() as f32;
+        for (gap, plain_gap) in gaps.iter().zip(plain.glyphs.iter().filter(|glyph| glyph.c == ' ')) {
+            assert!((gap.advance_x - plain_gap.advance_x - share).abs() < 1e-3);
+        }
+    }
+    assert!((last.width() - plain.last().unwrap().width()).abs() < 1e-3);
+    assert_eq!(last.x, x);
+
+    // A line without word gaps falls back to spacing out its characters,
+    // unless justification is limited to word gaps.
+    let text = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let lines = canvas.fill_text_wrapped(x, 50.0, max_width, text, &justified).unwrap();
+    assert!(lines.len() > 1);
+    assert!((lines[0].width() - max_width).abs() < 1e-3);
+    let inter_word = justified.clone().with_text_justify(TextJustify::InterWord);
+    let lines = canvas.fill_text_wrapped(x, 50.0, max_width, text, &inter_word).unwrap();
+    assert!(lines[0].width() < max_width);
+}
+
+#[cfg(feature = "textlayout")]
+#[test]
+fn fill_text_on_path_places_glyphs_along_the_path() {
+    fn recorded_verts(draw: impl FnOnce(&mut Canvas<RecordingRenderer>, &Paint)) -> Vec<renderer::Vertex> {
+        let renderer = RecordingRenderer::default();
+        let verts = renderer.last_verts.clone();
+        let mut canvas = Canvas::new(renderer).unwrap();
+        canvas.set_size(800, 800, 1.0);
+        let font_id = canvas
+            .add_font_mem(include_bytes!("../examples/assets/RobotoFlex-VariableFont.ttf"))
+            .expect("failed to load test font");
+        // Large enough to draw outlines directly, so the recorded vertices are
+        // the glyph shapes on the canvas rather than atlas quads.
+        let paint = Paint::color(Color::black()).with_font(&[font_id]).with_font_size(100.0);
+        draw(&mut canvas, &paint);
+        canvas.flush_to_output(());
+        let verts = verts.borrow().clone();
+        verts
+    }
+
+    let mut line = Path::new();
+    line.move_to(10.0, 150.0);
+    line.line_to(790.0, 150.0);
+
+    // Along a straight line the glyphs land exactly where fill_text puts them.
+    let straight = recorded_verts(|canvas, paint| {
+        canvas.fill_text(30.0, 150.0, "Hello", paint).unwrap();
+    });
+    let on_path = recorded_verts(|canvas, paint| {
+        canvas.fill_text_on_path(&line, 20.0, "Hello", paint).unwrap();
+    });
+    assert!(!straight.is_empty());
+    assert_eq!(straight.len(), on_path.len());
+    for (a, b) in straight.iter().zip(&on_path) {
+        assert!((a.x - b.x).abs() < 0.01 && (a.y - b.y).abs() < 0.01);
+    }
+
+    // Along a downward line the glyphs turn a quarter and stack vertically.
+    let mut down = Path::new();
+    down.move_to(400.0, 10.0);
+    down.line_to(400.0, 790.0);
+    let rotated = recorded_verts(|canvas, paint| {
+        canvas.fill_text_on_path(&down, 0.0, "Hello", paint).unwrap();
+    });
+    let (min_x, max_x) = rotated
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(v.x), hi.max(v.x)));
+    let (min_y, max_y) = rotated
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(v.y), hi.max(v.y)));
+    assert!(min_x > 400.0 - 5.0 && max_x < 400.0 + 100.0, "{min_x}..{max_x}");
+    assert!(min_y >= 10.0 - 1.0 && max_y - min_y > 200.0, "{min_y}..{max_y}");
+
+    // Characters past the end of an open path are dropped; the right side
+    // runs the text from the end of the path backwards.
+    let mut short = Path::new();
+    short.move_to(10.0, 150.0);
+    short.line_to(150.0, 150.0);
+    let clipped = recorded_verts(|canvas, paint| {
+        canvas.fill_text_on_path(&short, 0.0, "Hello", paint).unwrap();
+    });
+    assert!(!clipped.is_empty() && clipped.len() < straight.len());
+
+    let right = recorded_verts(|canvas, paint| {
+        let paint = paint.clone().with_text_path_side(TextPathSide::Right);
+        canvas.fill_text_on_path(&line, 20.0, "Hello", &paint).unwrap();
+    });
+    let min_x = right.iter().map(|v| v.x).fold(f32::MAX, f32::min);
+    let min_y = right.iter().map(|v| v.y).fold(f32::MAX, f32::min);
+    assert!(min_x > 400.0 && min_y > 150.0 - 5.0, "{min_x}, {min_y}");
+}
+
+#[cfg(feature = "textlayout")]
+#[test]
+fn truncate_text_appends_an_ellipsis_at_the_logical_end() {
+    let mut canvas = Canvas::new(RecordingRenderer::default()).unwrap();
+    canvas.set_size(400, 200, 1.0);
+    let latin = canvas
+        .add_font_mem(include_bytes!("../examples/assets/RobotoFlex-VariableFont.ttf"))
+        .expect("failed to load test font");
+    let arabic = canvas
+        .add_font_mem(include_bytes!("../examples/assets/amiri-regular.ttf"))
+        .expect("failed to load test font");
+    let paint = Paint::color(Color::black()).with_font(&[latin]).with_font_size(20.0);
+    let width = |canvas: &Canvas<RecordingRenderer>, text: &str, paint: &Paint| {
+        canvas.measure_text(0.0, 0.0, text, paint).unwrap().width()
+    };
+
+    // Text that fits comes back unchanged.
+    let text = "Hello wonderful world";
+    let full = width(&canvas, text, &paint);
+    assert_eq!(canvas.truncate_text(full + 1.0, 1, text, &paint).unwrap(), [text]);
+
+    // Too wide: the longest prefix that leaves room for the ellipsis.
+    let max_width = width(&canvas, "Hello wond", &paint) + width(&canvas, "\u{2026}", &paint);
+    let lines = canvas.truncate_text(max_width, 1, text, &paint).unwrap();
+    assert_eq!(lines, ["Hello wond\u{2026}"]);
+    assert!(width(&canvas, &lines[0], &paint) <= max_width + 0.01);
+
+    // Wrapped: the last allowed line takes the rest of the text and is cut.
+    let max_width = width(&canvas, "Hello wonderful", &paint) + 1.0;
+    let lines = canvas
+        .truncate_text(max_width, 2, "Hello wonderful world of long text strings", &paint)
+        .unwrap();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].trim_end(), "Hello wonderful");
+    assert!(
+        lines[1].starts_with("world") && lines[1].ends_with('\u{2026}'),
+        "{lines:?}"
+    );
+
+    // Cuts never split a grapheme cluster (here, e + combining acute).
+    let text = "e\u{301}".repeat(20);
+    let max_width = width(&canvas, &text, &paint) / 2.0;
+    let lines = canvas.truncate_text(max_width, 1, &text, &paint).unwrap();
+    let kept = lines[0].trim_end_matches('\u{2026}');
+    assert!(!kept.is_empty() && kept.len() < text.len());
+    assert_eq!(kept.matches('e').count(), kept.matches('\u{301}').count());
+
+    // In a right-to-left paragraph the logical end is on the left.
+    let paint = paint.with_font(&[arabic]);
+    let text = "مرحبا بالعالم الجميل";
+    let max_width = width(&canvas, text, &paint) * 0.6;
+    let lines = canvas.truncate_text(max_width, 1, text, &paint).unwrap();
+    assert!(lines[0].ends_with('\u{2026}'), "{lines:?}");
+    let metrics = canvas.measure_text(0.0, 0.0, &lines[0], &paint).unwrap();
+    let ellipsis_x = metrics.glyphs.iter().find(|glyph| glyph.c == '\u{2026}').unwrap().x;
+    assert!(metrics.glyphs.iter().all(|glyph| glyph.x >= ellipsis_x - 0.01));
+}
+
 /// Rebuilds a sfnt/TrueType font byte buffer with the named 4-byte tables
 /// removed, so the fallback metric paths can be exercised on real assets.
 #[cfg(all(test, feature = "textlayout"))]
@@ -1,10 +1,15 @@
 // TODO: prefix paint creation functions with make_ or new_
 // so that they are easier to find when autocompleting
 
+#[cfg(feature = "textlayout")]
+use std::borrow::Cow;
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
-use crate::{geometry::Position, Align, Baseline, Color, FillRule, FontId, ImageId, LineCap, LineJoin};
+use crate::{
+    geometry::Position, Align, Baseline, Color, FillRule, FontId, ImageId, LineCap, LineJoin, TextAntialiasing,
+    TextJustify, TextPathSide, WritingMode,
+};
 
 const MAX_FONT_VARIATIONS: usize = 4;
 
@@ -95,6 +100,11 @@ impl FontVariations {
         self.len == 0
     }
 
+    /// Returns true if no further axis can be added.
+    pub(crate) fn is_full(&self) -> bool {
+        self.len as usize == MAX_FONT_VARIATIONS
+    }
+
     /// Iterates over all set (tag, value) pairs.
     pub(crate) fn iter(&self) -> impl Iterator<Item = (u32, f32)> + '_ {
         self.entries[..self.len as usize].iter().copied()
@@ -196,6 +206,22 @@ pub enum GradientColors {
     MultiStop { stops: MultiStopGradient },
 }
 impl GradientColors {
+    fn hash_colors<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Self::TwoStop { start_color, end_color } => {
+                hash_floats(state, &start_color.to_array());
+                hash_floats(state, &end_color.to_array());
+            }
+            Self::MultiStop { stops } => {
+                for GradientStop(offset, color) in stops.shared_stops.iter() {
+                    hash_floats(state, &[*offset]);
+                    hash_floats(state, &color.to_array());
+                }
+                hash_floats(state, &[stops.tint]);
+            }
+        }
+    }
+
     fn mul_alpha(&mut self, a: f32) {
         match self {
             Self::TwoStop { start_color, end_color } => {
@@ -300,6 +326,18 @@ pub enum PaintFlavor {
         start_angle: f32,
         colors: GradientColors,
     },
+    /// A rounded rectangle blurred with a Gaussian of standard deviation
+    /// `sigma`, as drawn by box shadows. `color` covers the blurred shape and
+    /// fades to transparent outside it, or the other way round when `inset`.
+    BoxShadow {
+        pos: Position,
+        width: f32,
+        height: f32,
+        radius: f32,
+        sigma: f32,
+        color: Color,
+        inset: bool,
+    },
 }
 
 // Convenience method to fetch the GradientColors out of a PaintFlavor
@@ -324,6 +362,9 @@ impl PaintFlavor {
             Self::ConicGradient { colors, .. } => {
                 colors.mul_alpha(a);
             }
+            Self::BoxShadow { color, .. } => {
+                color.a *= a;
+            }
         }
     }
 
@@ -341,6 +382,75 @@ impl PaintFlavor {
     pub(crate) fn is_straight_tinted_image(&self, shape_anti_alias: bool) -> bool {
         matches!(self, &Self::Image { angle, .. } if angle == 0.0 && !shape_anti_alias)
     }
+
+    /// Feeds the paint to `state` for caching what is drawn with it. Returns
+    /// `false`, without feeding anything, for image paints, whose pixels can
+    /// change under the same id.
+    pub(crate) fn hash_cache_key<H: Hasher>(&self, state: &mut H) -> bool {
+        if matches!(self, Self::Image { .. }) {
+            return false;
+        }
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::Color(color) => hash_floats(state, &color.to_array()),
+            Self::Image { .. } => {}
+            Self::LinearGradient { start, end, colors } => {
+                hash_floats(state, &[start.x, start.y, end.x, end.y]);
+                colors.hash_colors(state);
+            }
+            Self::BoxGradient {
+                pos,
+                width,
+                height,
+                radius,
+                feather,
+                colors,
+            } => {
+                hash_floats(state, &[pos.x, pos.y, *width, *height, *radius, *feather]);
+                colors.hash_colors(state);
+            }
+            Self::RadialGradient {
+                center,
+                in_radius,
+                out_radius,
+                colors,
+            } => {
+                hash_floats(
+                    state,
+                    &[center.x, center.y, in_radius.0, in_radius.1, out_radius.0, out_radius.1],
+                );
+                colors.hash_colors(state);
+            }
+            Self::ConicGradient {
+                center,
+                start_angle,
+                colors,
+            } => {
+                hash_floats(state, &[center.x, center.y, *start_angle]);
+                colors.hash_colors(state);
+            }
+            Self::BoxShadow {
+                pos,
+                width,
+                height,
+                radius,
+                sigma,
+                color,
+                inset,
+            } => {
+                hash_floats(state, &[pos.x, pos.y, *width, *height, *radius, *sigma]);
+                hash_floats(state, &color.to_array());
+                inset.hash(state);
+            }
+        }
+        true
+    }
+}
+
+fn hash_floats<H: Hasher>(state: &mut H, values: &[f32]) {
+    for value in values {
+        value.to_bits().hash(state);
+    }
 }
 
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
@@ -349,13 +459,27 @@ pub enum GlyphTexture {
     None,
     AlphaMask(ImageId),
     ColorTexture(ImageId),
+    DistanceField(ImageId),
+    // Subpixel coverage masks are composited in two passes: the first scales
+    // the destination by one minus the per-channel coverage, the second adds
+    // the paint color weighted by it.
+    SubpixelCoverage(ImageId),
+    SubpixelColor(ImageId),
+    // Not a glyph: the second input of a two-input image filter, sampled
+    // through the glyph texture slot.
+    FilterBackdrop(ImageId),
 }
 
 impl GlyphTexture {
     pub(crate) fn image_id(&self) -> Option<ImageId> {
         match self {
             Self::None => None,
-            Self::AlphaMask(image_id) | Self::ColorTexture(image_id) => Some(*image_id),
+            Self::AlphaMask(image_id)
+            | Self::ColorTexture(image_id)
+            | Self::DistanceField(image_id)
+            | Self::SubpixelCoverage(image_id)
+            | Self::SubpixelColor(image_id)
+            | Self::FilterBackdrop(image_id) => Some(*image_id),
         }
     }
 }
@@ -393,7 +517,8 @@ impl Default for StrokeSettings {
 ///
 /// This is an extension toward SVG/CSS `text-decoration` parity; the HTML
 /// Canvas 2D API has no equivalent. Lines combine additively, so any subset can
-/// be enabled at once. The decoration is drawn in the text paint's color.
+/// be enabled at once. The decoration is drawn with the text paint unless
+/// [`Paint::set_text_decoration_color`] picks another color.
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TextDecoration {
@@ -413,6 +538,102 @@ impl TextDecoration {
     }
 }
 
+/// Line style of text decorations, like CSS `text-decoration-style`.
+///
+/// The default value is `Solid`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TextDecorationStyle {
+    /// A single solid line.
+    #[default]
+    Solid,
+    /// Two parallel solid lines, separated by the line thickness.
+    Double,
+    /// A row of round dots.
+    Dotted,
+    /// A row of short dashes.
+    Dashed,
+    /// A wavy line, as used for spell-check squiggles.
+    Wavy,
+}
+
+/// Outline and glow drawn around text that is rendered from distance fields.
+///
+/// Effects are only applied when distance-field rendering is enabled with
+/// [`Paint::set_text_distance_field`]. The outline is drawn behind the glyphs
+/// and the glow behind the outline, fading out over its radius. Both are
+/// limited to about a sixth of the font size; larger values are clamped.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TextEffects {
+    /// Width of the outline around the glyphs in user-space units. Zero disables the outline.
+    pub outline_width: f32,
+    /// Color of the outline.
+    pub outline_color: Color,
+    /// Distance in user-space units over which the glow fades out. Zero disables the glow.
+    pub glow_radius: f32,
+    /// Color of the glow where it meets the glyphs (or their outline).
+    pub glow_color: Color,
+}
+
+impl TextEffects {
+    /// Returns `true` if neither an outline nor a glow is enabled.
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        self.outline_width <= 0.0 && self.glow_radius <= 0.0
+    }
+}
+
+/// A CSS-style box shadow, drawn with [`Canvas::draw_box_shadow`](crate::Canvas::draw_box_shadow).
+///
+/// The shadow is the box offset and grown by the spread (or shrunk, for an
+/// inset shadow), then blurred with a Gaussian whose standard deviation is
+/// half the blur radius, as for CSS `box-shadow` and the canvas shadow.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BoxShadow {
+    /// Horizontal offset of the shadow in user-space units.
+    pub offset_x: f32,
+    /// Vertical offset of the shadow in user-space units.
+    pub offset_y: f32,
+    /// Blur radius in user-space units. Zero gives a sharp shadow.
+    pub blur: f32,
+    /// Distance the shadow is grown by before blurring, or shrunk by if negative.
+    pub spread: f32,
+    /// Color of the shadow.
+    pub color: Color,
+    /// Draw the shadow inside the box, around its edges, instead of outside it.
+    pub inset: bool,
+}
+
+impl BoxShadow {
+    /// Creates an outer shadow with the given offset, blur radius and color, and no spread.
+    pub fn new(offset_x: f32, offset_y: f32, blur: f32, color: Color) -> Self {
+        Self {
+            offset_x,
+            offset_y,
+            blur,
+            spread: 0.0,
+            color,
+            inset: false,
+        }
+    }
+
+    /// Sets the spread distance.
+    #[must_use]
+    pub fn with_spread(mut self, spread: f32) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    /// Makes this an inset shadow.
+    #[must_use]
+    pub fn with_inset(mut self, inset: bool) -> Self {
+        self.inset = inset;
+        self
+    }
+}
+
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TextSettings {
@@ -428,6 +649,37 @@ pub struct TextSettings {
     // deserializing. `TextDecoration::default()` is all-false, i.e. no decoration.
     #[cfg_attr(feature = "serde", serde(default))]
     pub(crate) text_decoration: TextDecoration,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) distance_field: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) text_effects: TextEffects,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) antialiasing: TextAntialiasing,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) font_palette: u16,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) text_path_side: TextPathSide,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) text_decoration_style: TextDecorationStyle,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) text_decoration_color: Option<Color>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) text_decoration_thickness: Option<f32>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) text_underline_offset: Option<f32>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) text_decoration_skip_ink: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) writing_mode: WritingMode,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) text_justify: TextJustify,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) optical_sizing: bool,
+    // The width a justified line is stretched to, set per line by
+    // `Canvas::fill_text_wrapped`. `None` aligns justified text to its start.
+    #[cfg(feature = "textlayout")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) justify_width: Option<f32>,
 }
 
 impl Default for TextSettings {
@@ -440,6 +692,21 @@ impl Default for TextSettings {
             text_align: Align::default(),
             font_variations: FontVariations::default(),
             text_decoration: TextDecoration::default(),
+            distance_field: false,
+            text_effects: TextEffects::default(),
+            antialiasing: TextAntialiasing::default(),
+            font_palette: 0,
+            text_path_side: TextPathSide::default(),
+            text_decoration_style: TextDecorationStyle::default(),
+            text_decoration_color: None,
+            text_decoration_thickness: None,
+            text_underline_offset: None,
+            text_decoration_skip_ink: false,
+            writing_mode: WritingMode::default(),
+            text_justify: TextJustify::default(),
+            optical_sizing: false,
+            #[cfg(feature = "textlayout")]
+            justify_width: None,
         }
     }
 }
@@ -1255,6 +1522,65 @@ impl Paint {
         self
     }
 
+    /// Returns where justified text puts the extra space of its lines.
+    #[inline]
+    pub fn text_justify(&self) -> TextJustify {
+        self.text.text_justify
+    }
+
+    /// Sets where justified text puts the extra space of its lines.
+    #[inline]
+    pub fn set_text_justify(&mut self, justify: TextJustify) {
+        self.text.text_justify = justify;
+    }
+
+    /// Returns the paint with the text justification set to the specified value.
+    #[inline]
+    pub fn with_text_justify(mut self, justify: TextJustify) -> Self {
+        self.set_text_justify(justify);
+        self
+    }
+
+    /// Returns the side of the path that [`Canvas::fill_text_on_path`](crate::Canvas::fill_text_on_path)
+    /// places text on.
+    #[inline]
+    pub fn text_path_side(&self) -> TextPathSide {
+        self.text.text_path_side
+    }
+
+    /// Sets the side of the path that [`Canvas::fill_text_on_path`](crate::Canvas::fill_text_on_path)
+    /// places text on.
+    #[inline]
+    pub fn set_text_path_side(&mut self, side: TextPathSide) {
+        self.text.text_path_side = side;
+    }
+
+    /// Returns the paint with the text path side set to the specified value.
+    #[inline]
+    pub fn with_text_path_side(mut self, side: TextPathSide) -> Self {
+        self.set_text_path_side(side);
+        self
+    }
+
+    /// Returns the writing mode for text operations.
+    #[inline]
+    pub fn writing_mode(&self) -> WritingMode {
+        self.text.writing_mode
+    }
+
+    /// Sets the writing mode for text operations.
+    #[inline]
+    pub fn set_writing_mode(&mut self, writing_mode: WritingMode) {
+        self.text.writing_mode = writing_mode;
+    }
+
+    /// Returns the paint with the writing mode set to the specified value.
+    #[inline]
+    pub fn with_writing_mode(mut self, writing_mode: WritingMode) -> Self {
+        self.set_writing_mode(writing_mode);
+        self
+    }
+
     // --- Text decoration (underline / strikethrough / overline) ---
 
     /// Returns the current set of text decoration lines for text operations.
@@ -1268,7 +1594,9 @@ impl Paint {
     /// Decoration lines are drawn in the paint's color using the font's own
     /// underline/strikeout metrics (overline is derived from the ascent). This
     /// extends femtovg toward SVG/CSS `text-decoration`; Canvas 2D has no
-    /// equivalent.
+    /// equivalent. The style, color, thickness and underline offset can be
+    /// changed with [`set_text_decoration_style`](Self::set_text_decoration_style)
+    /// and the setters that follow it.
     #[inline]
     pub fn set_text_decoration(&mut self, decoration: TextDecoration) {
         self.text.text_decoration = decoration;
@@ -1281,6 +1609,225 @@ impl Paint {
         self
     }
 
+    /// Returns the line style of text decorations.
+    #[inline]
+    pub fn text_decoration_style(&self) -> TextDecorationStyle {
+        self.text.text_decoration_style
+    }
+
+    /// Sets the line style of text decorations, like CSS `text-decoration-style`.
+    #[inline]
+    pub fn set_text_decoration_style(&mut self, style: TextDecorationStyle) {
+        self.text.text_decoration_style = style;
+    }
+
+    /// Returns the paint with the text decoration style set to the specified value.
+    #[inline]
+    pub fn with_text_decoration_style(mut self, style: TextDecorationStyle) -> Self {
+        self.set_text_decoration_style(style);
+        self
+    }
+
+    /// Returns the color of text decorations, or `None` if they are drawn with the paint itself.
+    #[inline]
+    pub fn text_decoration_color(&self) -> Option<Color> {
+        self.text.text_decoration_color
+    }
+
+    /// Sets the color of text decorations, like CSS `text-decoration-color`.
+    /// `None` draws them with the paint, the same way as the glyphs.
+    #[inline]
+    pub fn set_text_decoration_color(&mut self, color: Option<Color>) {
+        self.text.text_decoration_color = color;
+    }
+
+    /// Returns the paint with the text decoration color set to the specified value.
+    #[inline]
+    pub fn with_text_decoration_color(mut self, color: Option<Color>) -> Self {
+        self.set_text_decoration_color(color);
+        self
+    }
+
+    /// Returns the thickness of text decoration lines, or `None` if it comes from the font.
+    #[inline]
+    pub fn text_decoration_thickness(&self) -> Option<f32> {
+        self.text.text_decoration_thickness
+    }
+
+    /// Sets the thickness of text decoration lines in user-space units, like
+    /// CSS `text-decoration-thickness`. `None`, zero, negative and non-finite
+    /// values use the font's underline and strikeout thickness.
+    #[inline]
+    pub fn set_text_decoration_thickness(&mut self, thickness: Option<f32>) {
+        self.text.text_decoration_thickness = thickness;
+    }
+
+    /// Returns the paint with the text decoration thickness set to the specified value.
+    #[inline]
+    pub fn with_text_decoration_thickness(mut self, thickness: Option<f32>) -> Self {
+        self.set_text_decoration_thickness(thickness);
+        self
+    }
+
+    /// Returns the underline offset, or `None` if the underline is placed by the font.
+    #[inline]
+    pub fn text_underline_offset(&self) -> Option<f32> {
+        self.text.text_underline_offset
+    }
+
+    /// Sets the distance from the baseline down to the top of the underline in
+    /// user-space units, like CSS `text-underline-offset`. `None` and
+    /// non-finite values use the font's underline position.
+    #[inline]
+    pub fn set_text_underline_offset(&mut self, offset: Option<f32>) {
+        self.text.text_underline_offset = offset;
+    }
+
+    /// Returns the paint with the underline offset set to the specified value.
+    #[inline]
+    pub fn with_text_underline_offset(mut self, offset: Option<f32>) -> Self {
+        self.set_text_underline_offset(offset);
+        self
+    }
+
+    /// Returns `true` if underlines and overlines are interrupted where glyphs cross them.
+    #[inline]
+    pub fn text_decoration_skip_ink(&self) -> bool {
+        self.text.text_decoration_skip_ink
+    }
+
+    /// Sets whether underlines and overlines leave a gap where glyph outlines,
+    /// such as descenders, cross them, like CSS `text-decoration-skip-ink`.
+    /// Strikethrough lines are never interrupted.
+    #[inline]
+    pub fn set_text_decoration_skip_ink(&mut self, skip_ink: bool) {
+        self.text.text_decoration_skip_ink = skip_ink;
+    }
+
+    /// Returns the paint with ink skipping set to the specified value.
+    #[inline]
+    pub fn with_text_decoration_skip_ink(mut self, skip_ink: bool) -> Self {
+        self.set_text_decoration_skip_ink(skip_ink);
+        self
+    }
+
+    // --- Distance-field text ---
+
+    /// Returns `true` if text is rendered from distance fields.
+    #[inline]
+    pub fn text_distance_field(&self) -> bool {
+        self.text.distance_field
+    }
+
+    /// Sets whether text is rendered from multi-channel signed distance fields.
+    ///
+    /// By default glyphs are rasterized into the glyph atlas for every size they
+    /// are drawn at, so zooming text re-rasterizes it over and over. With
+    /// distance fields, each glyph is rasterized once at a fixed reference size
+    /// and the shader reconstructs its edges at any scale and rotation. Small
+    /// text looks slightly softer than with the regular rasterizer, and very
+    /// thin features can lose detail, so this suits animated or large text best.
+    ///
+    /// Distance-field rendering also enables the outline and glow set with
+    /// [`Self::set_text_effects`]. It applies to solid color paints only;
+    /// gradient and image paints, as well as color (bitmap) glyphs, keep using
+    /// the regular rasterizer.
+    #[inline]
+    pub fn set_text_distance_field(&mut self, enabled: bool) {
+        self.text.distance_field = enabled;
+    }
+
+    /// Returns the paint with distance-field text rendering enabled or disabled.
+    #[inline]
+    pub fn with_text_distance_field(mut self, enabled: bool) -> Self {
+        self.set_text_distance_field(enabled);
+        self
+    }
+
+    /// Returns the outline and glow drawn around distance-field text.
+    #[inline]
+    pub fn text_effects(&self) -> TextEffects {
+        self.text.text_effects
+    }
+
+    /// Sets the outline and glow drawn around distance-field text.
+    ///
+    /// Has no effect unless distance-field rendering is enabled with
+    /// [`Self::set_text_distance_field`].
+    #[inline]
+    pub fn set_text_effects(&mut self, effects: TextEffects) {
+        self.text.text_effects = effects;
+    }
+
+    /// Returns the paint with the text effects set to the specified value.
+    #[inline]
+    pub fn with_text_effects(mut self, effects: TextEffects) -> Self {
+        self.set_text_effects(effects);
+        self
+    }
+
+    // --- Subpixel text ---
+
+    /// Returns how glyph edges are antialiased.
+    #[inline]
+    pub fn text_antialiasing(&self) -> TextAntialiasing {
+        self.text.antialiasing
+    }
+
+    /// Sets how glyph edges are antialiased.
+    ///
+    /// Subpixel antialiasing triples the horizontal resolution of text on LCD
+    /// displays by computing a separate coverage for each color channel; pick
+    /// the variant that matches the display's subpixel order. It is meant for
+    /// text drawn opaquely over a known, opaque background at its native size:
+    /// the colored fringes only cancel out when the pixels end up on screen as
+    /// drawn, so avoid it for text that is later scaled, rotated or composited
+    /// from a transparent layer.
+    ///
+    /// Subpixel antialiasing applies to solid color paints drawn with
+    /// [`CompositeOperation::SourceOver`](crate::CompositeOperation::SourceOver)
+    /// through the glyph atlas. Other paints and composite operations, color
+    /// glyphs, and text drawn as paths under rotation or heavy zoom use
+    /// grayscale antialiasing instead.
+    #[inline]
+    pub fn set_text_antialiasing(&mut self, antialiasing: TextAntialiasing) {
+        self.text.antialiasing = antialiasing;
+    }
+
+    /// Returns the paint with the text antialiasing set to the specified value.
+    #[inline]
+    pub fn with_text_antialiasing(mut self, antialiasing: TextAntialiasing) -> Self {
+        self.set_text_antialiasing(antialiasing);
+        self
+    }
+
+    // --- Color font palette ---
+
+    /// Returns the index of the color palette that color glyphs are drawn with.
+    #[inline]
+    pub fn font_palette(&self) -> u16 {
+        self.text.font_palette
+    }
+
+    /// Sets the index of the `CPAL` color palette that `COLR` color glyphs are
+    /// drawn with.
+    ///
+    /// Palette 0 is the font's default; [`FontInfo::color_palettes`](crate::FontInfo::color_palettes)
+    /// tells how many palettes a font provides, and an index past the last one
+    /// falls back to palette 0. Layers that the font paints in the foreground
+    /// color take the paint's color, or black for gradient and image paints.
+    #[inline]
+    pub fn set_font_palette(&mut self, palette: u16) {
+        self.text.font_palette = palette;
+    }
+
+    /// Returns the paint with the color palette set to the specified index.
+    #[inline]
+    pub fn with_font_palette(mut self, palette: u16) -> Self {
+        self.set_font_palette(palette);
+        self
+    }
+
     // --- Font weight (wght axis) ---
 
     /// Returns the current font weight override for variable fonts, in design space.
@@ -1297,7 +1844,8 @@ impl Paint {
     /// Common values: 100 (Thin), 300 (Light), 400 (Regular), 700 (Bold), 900 (Black).
     /// The actual supported range depends on the font; query it with
     /// [`Canvas::font_variation_axes`](crate::Canvas::font_variation_axes).
-    /// This only affects variable fonts; for static fonts it has no effect.
+    /// Static fonts lighter than semibold are emboldened synthetically for
+    /// weights of 600 and above.
     #[inline]
     pub fn set_font_weight(&mut self, weight: f32) {
         self.text
@@ -1340,6 +1888,7 @@ impl Paint {
     /// Also sets the `slnt` axis as a fallback (`-12` for italic, `0` for upright),
     /// so fonts with only a `slnt` axis (no `ital`) will still render slanted.
     /// An explicit `set_font_slant()` call after this will override the fallback value.
+    /// Fonts with neither axis nor an italic or oblique face are sheared synthetically.
     #[inline]
     pub fn set_font_italic(&mut self, italic: bool) {
         self.text
@@ -1405,6 +1954,54 @@ impl Paint {
         self.text.font_variations.remove(FontVariations::tag_to_u32(b"slnt"));
     }
 
+    // --- Optical size (opsz axis) ---
+
+    /// Returns whether the `opsz` axis follows the font size automatically.
+    #[inline]
+    pub fn font_optical_sizing(&self) -> bool {
+        self.text.optical_sizing
+    }
+
+    /// Sets whether the `opsz` axis of variable fonts follows the font size,
+    /// like CSS `font-optical-sizing: auto`.
+    ///
+    /// When enabled, text is drawn and measured with `opsz` set to the size
+    /// it appears at: the font size times the scale of the canvas transform,
+    /// so zooming the canvas picks the matching optical size. The device pixel
+    /// ratio is not included. An explicit `opsz` value set with
+    /// [`set_font_variation`](Self::set_font_variation) takes precedence, and
+    /// a paint that already sets four variation axes keeps the font's default
+    /// optical size. Disabled by default.
+    #[inline]
+    pub fn set_font_optical_sizing(&mut self, enabled: bool) {
+        self.text.optical_sizing = enabled;
+    }
+
+    /// Returns the paint with automatic optical sizing enabled or disabled.
+    ///
+    /// See [`set_font_optical_sizing`](Self::set_font_optical_sizing).
+    #[inline]
+    pub fn with_font_optical_sizing(mut self, enabled: bool) -> Self {
+        self.set_font_optical_sizing(enabled);
+        self
+    }
+
+    // The paint with `opsz` set to the font size times `zoom`, if it asks for
+    // automatic optical sizing and doesn't set the axis itself. Paints that
+    // already set as many axes as they can hold are left alone.
+    #[cfg(feature = "textlayout")]
+    pub(crate) fn optically_sized(&self, zoom: f32) -> Cow<'_, Self> {
+        let opsz = FontVariations::tag_to_u32(b"opsz");
+        let variations = &self.text.font_variations;
+        if !self.text.optical_sizing || variations.get(opsz).is_some() || variations.is_full() {
+            return Cow::Borrowed(self);
+        }
+
+        let mut paint = self.clone();
+        paint.text.font_variations.set(opsz, self.text.font_size * zoom);
+        Cow::Owned(paint)
+    }
+
     // --- Generic font variation API ---
 
     /// Returns the value of a font variation axis by its 4-byte tag, in design space.
@@ -1514,6 +2111,25 @@ mod tests {
         assert_eq!(paint.line_dash(), &[0.0, 0.0]);
     }
 
+    #[cfg(feature = "textlayout")]
+    #[test]
+    fn optical_sizing_sets_opsz_to_the_zoomed_font_size() {
+        use std::borrow::Cow;
+
+        let paint = Paint::default().with_font_size(12.0);
+        assert!(matches!(paint.optically_sized(2.0), Cow::Borrowed(_)));
+
+        let auto = paint.with_font_optical_sizing(true);
+        assert_eq!(auto.optically_sized(1.0).font_variation(b"opsz"), Some(12.0));
+        assert_eq!(auto.optically_sized(2.5).font_variation(b"opsz"), Some(30.0));
+        assert_eq!(auto.font_variation(b"opsz"), None);
+
+        // An explicit axis value wins.
+        let explicit = auto.with_font_variation(b"opsz", 72.0);
+        assert!(matches!(explicit.optically_sized(2.0), Cow::Borrowed(_)));
+        assert_eq!(explicit.optically_sized(2.0).font_variation(b"opsz"), Some(72.0));
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn conic_gradient_deserializes_without_start_angle() {
@@ -1,6 +1,7 @@
 use std::{
     cell::{RefCell, RefMut},
     f32::consts::PI,
+    hash::{Hash, Hasher},
     slice,
 };
 
@@ -865,6 +866,144 @@ impl Path {
         self.ellipse(cx, cy, r, r);
     }
 
+    /// Flattens the path and measures it by arc length, in the direction it
+    /// was drawn or, with `reversed`, from its end back to its start.
+    #[cfg(feature = "textlayout")]
+    pub(crate) fn measure(&self, tess_tol: f32, reversed: bool) -> PathMeasure {
+        let mut contours = self.flattened_contours(tess_tol, self.dist_tol);
+        let closed = contours.len() == 1 && contours[0].closed;
+
+        if reversed {
+            contours.reverse();
+        }
+
+        let mut segments = Vec::new();
+        let mut length = 0.0;
+
+        for contour in &mut contours {
+            if contour.closed {
+                let first = contour.points[0];
+                contour.points.push(first);
+            }
+            if reversed {
+                contour.points.reverse();
+            }
+
+            for points in contour.points.windows(2) {
+                let segment_length = (points[1] - points[0]).mag2().sqrt();
+                if segment_length > 0.0 {
+                    segments.push(MeasuredSegment {
+                        start: points[0],
+                        end: points[1],
+                        distance: length,
+                    });
+                    length += segment_length;
+                }
+            }
+        }
+
+        PathMeasure {
+            segments,
+            length,
+            closed,
+        }
+    }
+
+    /// Returns the horizontal extent of the parts of the path's outline that
+    /// lie between `top` and `bottom`, or `None` if it doesn't enter that band.
+    #[cfg(feature = "textlayout")]
+    pub(crate) fn horizontal_extent_within(&self, top: f32, bottom: f32, tess_tol: f32) -> Option<(f32, f32)> {
+        let mut extent: Option<(f32, f32)> = None;
+        let mut include = |x: f32| {
+            extent = Some(extent.map_or((x, x), |(min, max)| (min.min(x), max.max(x))));
+        };
+
+        for contour in self.flattened_contours(tess_tol, self.dist_tol) {
+            let closing = contour.points.last().copied().zip(contour.points.first().copied());
+            let segments = contour
+                .points
+                .windows(2)
+                .map(|points| (points[0], points[1]))
+                .chain(closing.filter(|_| contour.closed));
+
+            for (a, b) in segments {
+                // Clip the segment to the band and take the x of the clipped ends.
+                let (low, high) = if a.y <= b.y { (a, b) } else { (b, a) };
+                if high.y < top || low.y > bottom {
+                    continue;
+                }
+                if high.y - low.y <= f32::EPSILON {
+                    include(low.x);
+                    include(high.x);
+                    continue;
+                }
+                let x_at = |y: f32| low.x + (high.x - low.x) * ((y - low.y) / (high.y - low.y));
+                include(x_at(low.y.max(top)));
+                include(x_at(high.y.min(bottom)));
+            }
+        }
+
+        extent
+    }
+
+    /// Returns `(x, y, width, height, radius)` if the path is exactly one
+    /// [`rect`](Self::rect) or uniformly [`rounded_rect`](Self::rounded_rect)
+    /// sub-path, with the width and height made positive and the radius as
+    /// reduced to fit.
+    pub(crate) fn as_rounded_rect(&self) -> Option<(f32, f32, f32, f32, f32)> {
+        let c = &self.coords;
+        let (x, y, w, h, r) = match (self.verbs.len(), c.len()) {
+            (5, 4) => (c[0].x, c[0].y, c[2].x - c[0].x, c[2].y - c[0].y, 0.0),
+            (10, 17) => (
+                c[0].x,
+                c[12].y,
+                c[8].x - c[0].x,
+                c[4].y - c[12].y,
+                (c[4].x - c[0].x).abs(),
+            ),
+            _ => return None,
+        };
+
+        // Rebuild the shape from what was read off and compare, which also
+        // checks the verbs and that every corner has the same radius.
+        let mut rebuilt = Self::new();
+        rebuilt.rounded_rect(x, y, w, h, r);
+        let same = rebuilt.verbs == self.verbs
+            && rebuilt
+                .coords
+                .iter()
+                .zip(c)
+                .all(|(a, b)| (a.x - b.x).abs() <= 1e-3 && (a.y - b.y).abs() <= 1e-3);
+
+        same.then(|| (x.min(x + w), y.min(y + h), w.abs(), h.abs(), r))
+    }
+
+    /// Feeds the path's verbs and points to `state`, so that paths with the
+    /// same outline hash the same however they were built.
+    pub(crate) fn hash_outline<H: Hasher>(&self, state: &mut H) {
+        for verb in &self.verbs {
+            (*verb as u8).hash(state);
+        }
+        for pos in &self.coords {
+            pos.x.to_bits().hash(state);
+            pos.y.to_bits().hash(state);
+        }
+    }
+
+    /// Appends the sub-paths of `other`, with their points mapped through `transform`.
+    pub(crate) fn append_path(&mut self, other: &Self, transform: &Transform2D) {
+        let coords: Vec<Position> = other
+            .coords
+            .iter()
+            .map(|pos| {
+                let (x, y) = transform.transform_point(pos.x, pos.y);
+                Position { x, y }
+            })
+            .collect();
+
+        self.append(&other.verbs, &coords);
+    }
+
     /// Appends a slice of verbs and coordinates to the path.
     fn append(&mut self, verbs: &[PackedVerb], coords: &[Position]) {
         if !coords.is_empty() {
@@ -876,6 +1015,55 @@ impl Path {
     }
 }
 
+/// A flattened path parametrized by arc length. Moving to a new sub-path
+/// does not add to the length.
+#[cfg(feature = "textlayout")]
+pub(crate) struct PathMeasure {
+    segments: Vec<MeasuredSegment>,
+    length: f32,
+    closed: bool,
+}
+
+#[cfg(feature = "textlayout")]
+struct MeasuredSegment {
+    start: Position,
+    end: Position,
+    // Arc length from the start of the path to `start`.
+    distance: f32,
+}
+
+#[cfg(feature = "textlayout")]
+impl PathMeasure {
+    /// Returns the point at `distance` along the path and the unit tangent
+    /// there. Distances wrap around a path made of a single closed sub-path;
+    /// on other paths, distances outside `0..=length` return `None`.
+    pub(crate) fn sample(&self, distance: f32) -> Option<(Position, Vector)> {
+        if self.segments.is_empty() || !distance.is_finite() {
+            return None;
+        }
+
+        let distance = if self.closed {
+            distance.rem_euclid(self.length)
+        } else if (0.0..=self.length).contains(&distance) {
+            distance
+        } else {
+            return None;
+        };
+
+        let index = self
+            .segments
+            .partition_point(|segment| segment.distance <= distance)
+            .saturating_sub(1);
+        let segment = &self.segments[index];
+
+        let mut tangent = segment.end - segment.start;
+        let segment_length = tangent.normalize();
+        let t = ((distance - segment.distance) / segment_length).min(1.0);
+
+        Some((segment.start + (segment.end - segment.start) * t, tangent))
+    }
+}
+
 #[derive(Default)]
 struct FlattenedContour {
     points: Vec<Position>,
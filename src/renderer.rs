@@ -75,6 +75,16 @@ pub enum CommandType {
         /// Image filter to apply.
         filter: ImageFilter,
     },
+    /// Copy a region of the current render target into an image. The image
+    /// receives the rows bottom-up, in the order of a render target image.
+    CopyRenderTarget {
+        /// ID of the target image, whose size is the size of the region.
+        target_image: ImageId,
+        /// Left edge of the region, in render target pixels.
+        x: u32,
+        /// Top edge of the region, in render target pixels.
+        y: u32,
+    },
 }
 
 /// Represents a command that can be executed by the renderer.
@@ -248,6 +258,22 @@ pub enum ShaderType {
     FillImageGradientConic,
     /// Color-matrix image filter shader (`feColorMatrix` / CSS color functions).
     FilterImageColorMatrix,
+    /// Distance-field glyph shader, with optional outline and glow.
+    FillDistanceField,
+    /// Two-input image filter shader (`feBlend` / arithmetic `feComposite`).
+    FilterImageBlend,
+    /// One pass of the separable min/max filter (`feMorphology`).
+    FilterImageMorphology,
+    /// Convolution matrix image filter shader (`feConvolveMatrix`).
+    FilterImageConvolve,
+    /// Perlin noise generator shader (`feTurbulence`).
+    FilterImageTurbulence,
+    /// Displacement map image filter shader (`feDisplacementMap`).
+    FilterImageDisplacementMap,
+    /// Lookup-table image filter shader (`feComponentTransfer`).
+    FilterImageComponentTransfer,
+    /// Gaussian blurred rounded rectangle shader (box shadows).
+    BoxShadow,
 }
 
 impl ShaderType {
@@ -265,6 +291,14 @@ impl ShaderType {
             Self::FillGradientConic => 8,
             Self::FillImageGradientConic => 9,
             Self::FilterImageColorMatrix => 10,
+            Self::FillDistanceField => 11,
+            Self::FilterImageBlend => 12,
+            Self::FilterImageMorphology => 13,
+            Self::FilterImageConvolve => 14,
+            Self::FilterImageTurbulence => 15,
+            Self::FilterImageDisplacementMap => 16,
+            Self::FilterImageComponentTransfer => 17,
+            Self::BoxShadow => 18,
         }
     }
 
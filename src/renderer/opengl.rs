@@ -13,7 +13,7 @@ use rgb::RGBA8;
 use crate::{
     renderer::{GlyphTexture, ImageId, Vertex},
     BlendFactor, Color, CompositeOperationState, ErrorKind, FillRule, ImageFilter, ImageInfo, ImageSource, ImageStore,
-    Scissor, Transform2D,
+    MorphologyOperator, Scissor, Transform2D,
 };
 
 use glow::HasContext;
@@ -42,9 +42,9 @@ pub struct OpenGl {
     view: [f32; 2],
     screen_view: [f32; 2],
     // All types of the vertex/fragment shader, indexed by shader_type when has_glyph_texture is true
-    main_programs_with_glyph_texture: [Option<MainProgram>; 11],
+    main_programs_with_glyph_texture: [Option<MainProgram>; 19],
     // Same shader programs but with has_glyph_texture being false
-    main_programs_without_glyph_texture: [Option<MainProgram>; 11],
+    main_programs_without_glyph_texture: [Option<MainProgram>; 19],
     current_program: u8,
     current_program_needs_glyph_texture: bool,
     vert_arr: Option<<glow::Context as glow::HasContext>::VertexArray>,
@@ -194,6 +194,89 @@ impl OpenGl {
                         false,
                     )?)
                 },
+                if with_glyph_texture {
+                    Some(MainProgram::new(
+                        &context,
+                        antialias,
+                        ShaderType::FillDistanceField,
+                        true,
+                    )?)
+                } else {
+                    // Distance fields are always sampled from a glyph texture
+                    None
+                },
+                if with_glyph_texture {
+                    Some(MainProgram::new(
+                        &context,
+                        antialias,
+                        ShaderType::FilterImageBlend,
+                        true,
+                    )?)
+                } else {
+                    // The backdrop is sampled through the glyph texture slot
+                    None
+                },
+                if with_glyph_texture {
+                    // Image filter is unrelated to glyph rendering
+                    None
+                } else {
+                    Some(MainProgram::new(
+                        &context,
+                        antialias,
+                        ShaderType::FilterImageMorphology,
+                        false,
+                    )?)
+                },
+                if with_glyph_texture {
+                    // Image filter is unrelated to glyph rendering
+                    None
+                } else {
+                    Some(MainProgram::new(
+                        &context,
+                        antialias,
+                        ShaderType::FilterImageConvolve,
+                        false,
+                    )?)
+                },
+                if with_glyph_texture {
+                    Some(MainProgram::new(
+                        &context,
+                        antialias,
+                        ShaderType::FilterImageTurbulence,
+                        true,
+                    )?)
+                } else {
+                    // The noise lattice is sampled through the glyph texture slot
+                    None
+                },
+                if with_glyph_texture {
+                    Some(MainProgram::new(
+                        &context,
+                        antialias,
+                        ShaderType::FilterImageDisplacementMap,
+                        true,
+                    )?)
+                } else {
+                    // The map is sampled through the glyph texture slot
+                    None
+                },
+                if with_glyph_texture {
+                    Some(MainProgram::new(
+                        &context,
+                        antialias,
+                        ShaderType::FilterImageComponentTransfer,
+                        true,
+                    )?)
+                } else {
+                    // The lookup table is sampled through the glyph texture slot
+                    None
+                },
+                if with_glyph_texture {
+                    // Box shadows are computed, never sampled from a glyph texture
+                    None
+                } else {
+                    Some(MainProgram::new(&context, antialias, ShaderType::BoxShadow, false)?)
+                },
             ])
         };
 
@@ -584,6 +667,33 @@ impl OpenGl {
         }
     }
 
+    fn copy_render_target(&self, images: &ImageStore<GlTexture>, target_image: ImageId, x: u32, y: u32) {
+        let Some(texture) = images.get(target_image) else {
+            return;
+        };
+        let width = texture.info().width() as i32;
+        let height = texture.info().height() as i32;
+
+        // Framebuffers keep their rows bottom-up, so the copy lands in render
+        // target order as it is.
+        unsafe {
+            self.context.active_texture(glow::TEXTURE0);
+            self.context.bind_texture(glow::TEXTURE_2D, Some(texture.id()));
+            self.context.copy_tex_sub_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                0,
+                0,
+                x as i32,
+                self.view[1] as i32 - (height + y as i32),
+                width,
+                height,
+            );
+        }
+
+        self.check_error("copy_render_target");
+    }
+
     fn render_filtered_image(
         &mut self,
         images: &mut ImageStore<GlTexture>,
@@ -593,19 +703,25 @@ impl OpenGl {
     ) {
         match filter {
             ImageFilter::GaussianBlur { sigma } => self.render_gaussian_blur(images, cmd, target_image, sigma),
-            ImageFilter::ColorMatrix { matrix } => self.render_color_matrix(images, cmd, target_image, matrix),
+            ImageFilter::Morphology {
+                operator,
+                radius_x,
+                radius_y,
+            } => self.render_morphology(images, cmd, target_image, operator, [radius_x, radius_y]),
+            _ => self.render_single_pass_filter(images, cmd, target_image, filter),
         }
     }
 
-    fn render_color_matrix(
+    fn render_single_pass_filter(
         &mut self,
         images: &mut ImageStore<GlTexture>,
         cmd: Command,
         target_image: ImageId,
-        matrix: [f32; 20],
+        filter: ImageFilter,
     ) {
         let original_render_target = self.current_render_target;
         let source_image_info = images.get(cmd.image.unwrap()).unwrap().info();
+        let target_image_info = images.get(target_image).unwrap().info();
 
         let image_paint = crate::Paint::image(
             cmd.image.unwrap(),
@@ -620,19 +736,13 @@ impl OpenGl {
             images,
             &Transform2D::default(),
             &image_paint.flavor,
-            &GlyphTexture::default(),
+            &cmd.glyph_texture,
             &Scissor::default(),
             0.,
             0.,
             0.,
         );
-        params.shader_type = ShaderType::FilterImageColorMatrix;
-        // The color matrix rides the scissor/paint-matrix uniform slots, which are
-        // dead during a filter pass (no scissor, no paint gradient): frag[0..2]
-        // hold the first 12 values, frag[3..4] the last 8, so no uniform-array
-        // growth is needed. The shader reads them back as a row-major 4x5 matrix.
-        params.scissor_mat.copy_from_slice(&matrix[..12]);
-        params.paint_mat[..8].copy_from_slice(&matrix[12..20]);
+        params.set_single_pass_filter(filter, source_image_info, target_image_info);
 
         self.set_target(images, RenderTarget::Image(target_image));
         self.main_program().set_view(self.view);
@@ -652,15 +762,51 @@ impl OpenGl {
     fn render_gaussian_blur(
         &mut self,
         images: &mut ImageStore<GlTexture>,
-        mut cmd: Command,
+        cmd: Command,
         target_image: ImageId,
         sigma: f32,
     ) {
-        let original_render_target = self.current_render_target;
+        let mut blur_params = self.filter_params(images, &cmd);
+        blur_params.shader_type = ShaderType::FilterImage;
 
-        // The filtering happens in two passes, first a horizontal blur and then the vertial blur. The
-        // first pass therefore renders into an intermediate, temporarily allocated texture.
+        let gauss_coeff_x = 1. / ((2. * std::f32::consts::PI).sqrt() * sigma);
+        let gauss_coeff_y = f32::exp(-0.5 / (sigma * sigma));
+        let gauss_coeff_z = gauss_coeff_y * gauss_coeff_y;
+
+        blur_params.image_blur_filter_coeff[0] = gauss_coeff_x;
+        blur_params.image_blur_filter_coeff[1] = gauss_coeff_y;
+        blur_params.image_blur_filter_coeff[2] = gauss_coeff_z;
+
+        blur_params.image_blur_filter_direction = [1.0, 0.0];
+
+        // GLES 2.0 does not allow non-constant loop indices, so limit the standard devitation to allow for a upper fixed limit
+        // on the number of iterations in the fragment shader.
+        blur_params.image_blur_filter_sigma = sigma.min(8.);
+
+        let mut vertical_params = blur_params;
+        vertical_params.image_blur_filter_direction = [0.0, 1.0];
+
+        self.render_separable_filter(images, cmd, target_image, [blur_params, vertical_params]);
+    }
+
+    fn render_morphology(
+        &mut self,
+        images: &mut ImageStore<GlTexture>,
+        cmd: Command,
+        target_image: ImageId,
+        operator: MorphologyOperator,
+        radius: [f32; 2],
+    ) {
+        let mut horizontal_params = self.filter_params(images, &cmd);
+        let mut vertical_params = horizontal_params;
+        horizontal_params.set_morphology_pass(operator, radius[0], [1.0, 0.0]);
+        vertical_params.set_morphology_pass(operator, radius[1], [0.0, 1.0]);
+
+        self.render_separable_filter(images, cmd, target_image, [horizontal_params, vertical_params]);
+    }
 
+    // Parameters sampling the command's source image over the whole filter quad.
+    fn filter_params(&self, images: &ImageStore<GlTexture>, cmd: &Command) -> Params {
         let source_image_info = images.get(cmd.image.unwrap()).unwrap().info();
 
         let image_paint = crate::Paint::image(
@@ -672,7 +818,7 @@ impl OpenGl {
             0.,
             1.,
         );
-        let mut blur_params = Params::new(
+        Params::new(
             images,
             &Transform2D::default(),
             &image_paint.flavor,
@@ -681,25 +827,25 @@ impl OpenGl {
             0.,
             0.,
             0.,
-        );
-        blur_params.shader_type = ShaderType::FilterImage;
-
-        let gauss_coeff_x = 1. / ((2. * std::f32::consts::PI).sqrt() * sigma);
-        let gauss_coeff_y = f32::exp(-0.5 / (sigma * sigma));
-        let gauss_coeff_z = gauss_coeff_y * gauss_coeff_y;
+        )
+    }
 
-        blur_params.image_blur_filter_coeff[0] = gauss_coeff_x;
-        blur_params.image_blur_filter_coeff[1] = gauss_coeff_y;
-        blur_params.image_blur_filter_coeff[2] = gauss_coeff_z;
+    fn render_separable_filter(
+        &mut self,
+        images: &mut ImageStore<GlTexture>,
+        mut cmd: Command,
+        target_image: ImageId,
+        passes: [Params; 2],
+    ) {
+        let original_render_target = self.current_render_target;
 
-        blur_params.image_blur_filter_direction = [1.0, 0.0];
+        // The filtering happens in two passes, typically first horizontally and then vertically. The
+        // first pass therefore renders into an intermediate, temporarily allocated texture.
 
-        // GLES 2.0 does not allow non-constant loop indices, so limit the standard devitation to allow for a upper fixed limit
-        // on the number of iterations in the fragment shader.
-        blur_params.image_blur_filter_sigma = sigma.min(8.);
+        let source_image_info = images.get(cmd.image.unwrap()).unwrap().info();
 
-        let horizontal_blur_buffer = images.alloc(self, source_image_info).unwrap();
-        self.set_target(images, RenderTarget::Image(horizontal_blur_buffer));
+        let first_pass_buffer = images.alloc(self, source_image_info).unwrap();
+        self.set_target(images, RenderTarget::Image(first_pass_buffer));
         self.main_program().set_view(self.view);
 
         self.clear_rect(
@@ -710,7 +856,7 @@ impl OpenGl {
             Color::rgbaf(0., 0., 0., 0.),
         );
 
-        self.triangles(images, &cmd, &blur_params);
+        self.triangles(images, &cmd, &passes[0]);
 
         self.set_target(images, RenderTarget::Image(target_image));
         self.main_program().set_view(self.view);
@@ -723,13 +869,11 @@ impl OpenGl {
             Color::rgbaf(0., 0., 0., 0.),
         );
 
-        blur_params.image_blur_filter_direction = [0.0, 1.0];
+        cmd.image = Some(first_pass_buffer);
 
-        cmd.image = Some(horizontal_blur_buffer);
+        self.triangles(images, &cmd, &passes[1]);
 
-        self.triangles(images, &cmd, &blur_params);
-
-        images.remove(self, horizontal_blur_buffer);
+        images.remove(self, first_pass_buffer);
 
         // restore previous render target and view
         self.set_target(images, original_render_target);
@@ -875,9 +1019,16 @@ impl Renderer for OpenGl {
                     self.set_target(images, target);
                     self.main_program().set_view(self.view);
                 }
-                CommandType::RenderFilteredImage { target_image, filter } => {
+                CommandType::RenderFilteredImage {
+                    target_image,
+                    ref filter,
+                } => {
+                    let filter = filter.clone();
                     self.render_filtered_image(images, cmd, target_image, filter)
                 }
+                CommandType::CopyRenderTarget { target_image, x, y } => {
+                    self.copy_render_target(images, target_image, x, y);
+                }
             }
         }
 
@@ -1,7 +1,7 @@
 
 precision highp float;
 
-#define UNIFORMARRAY_SIZE 14
+#define UNIFORMARRAY_SIZE 16
 
 #define TAU 6.28318530717958647692528676655900577
 
@@ -26,6 +26,11 @@ uniform vec4 frag[UNIFORMARRAY_SIZE];
 #define imageBlurFilterCoeff frag[12].xyz
 #define scissorRadius frag[12].w
 #define conicStartAngle frag[13].x
+#define sdfGlowCol frag[14]
+#define sdfPxRange frag[15].x
+#define sdfStrokeHalfWidth frag[15].y
+#define sdfOutlineWidth frag[15].z
+#define sdfGlowRadius frag[15].w
 
 uniform sampler2D tex;
 uniform sampler2D glyphtex;
@@ -44,6 +49,14 @@ varying vec2 fpos;
  #define SHADER_TYPE_FillGradientConic 8
  #define SHADER_TYPE_FillImageGradientConic 9
  #define SHADER_TYPE_FilterImageColorMatrix 10
+ #define SHADER_TYPE_FillDistanceField 11
+ #define SHADER_TYPE_FilterImageBlend 12
+ #define SHADER_TYPE_FilterImageMorphology 13
+ #define SHADER_TYPE_FilterImageConvolve 14
+ #define SHADER_TYPE_FilterImageTurbulence 15
+ #define SHADER_TYPE_FilterImageDisplacementMap 16
+ #define SHADER_TYPE_FilterImageComponentTransfer 17
+ #define SHADER_TYPE_BoxShadow 18
 
 float sdroundrect(vec2 pt, vec2 ext, float rad) {
     vec2 ext2 = ext - vec2(rad,rad);
@@ -105,6 +118,49 @@ vec4 renderImageGradient() {
     return ditherGradient(texture2D(tex, vec2(d, 0.0)));
 }
 
+// Abramowitz and Stegun 7.1.27, within 5e-4 of the error function.
+vec2 erfApprox(vec2 x) {
+    vec2 s = sign(x);
+    vec2 a = abs(x);
+    x = 1.0 + (0.278393 + (0.230389 + 0.078108 * (a * a)) * a) * a;
+    x *= x;
+    return s - s / (x * x);
+}
+
+// The row at height y of a rounded rectangle blurred along x only, which the
+// error function gives exactly.
+float boxShadowRow(float x, float y, float sigma, float corner, vec2 halfSize) {
+    float delta = min(halfSize.y - corner - abs(y), 0.0);
+    float curved = halfSize.x - corner + sqrt(max(0.0, corner * corner - delta * delta));
+    vec2 integral = 0.5 + 0.5 * erfApprox((x + vec2(-curved, curved)) * (sqrt(0.5) / sigma));
+    return integral.y - integral.x;
+}
+
+// Box shadow: the rounded rectangle (extent, radius) blurred with a Gaussian
+// of standard deviation feather, in paint space. The rows are blurred
+// exactly and summed over 8 samples of the Gaussian along y (E. Wallace's
+// closed form). Inner color where covered, outer color elsewhere.
+vec4 renderBoxShadow() {
+    vec2 pt = (paintMat * vec3(fpos, 1.0)).xy;
+    float sigma = feather;
+    float corner = min(radius, min(extent.x, extent.y));
+
+    // The Gaussian is only non-zero within 3 sigma, and the rows only
+    // within the rectangle.
+    float start = clamp(-3.0 * sigma, pt.y - extent.y, pt.y + extent.y);
+    float end = clamp(3.0 * sigma, pt.y - extent.y, pt.y + extent.y);
+    float dy = (end - start) / 8.0;
+    float y = start + dy * 0.5;
+    float coverage = 0.0;
+    for (int i = 0; i < 8; i++) {
+        float weight = exp(-(y * y) / (2.0 * sigma * sigma)) / (sqrt(TAU) * sigma);
+        coverage += boxShadowRow(pt.x, pt.y - y, sigma, corner, extent) * weight * dy;
+        y += dy;
+    }
+
+    return mix(outerCol, innerCol, clamp(coverage, 0.0, 1.0));
+}
+
 float conicAngleFraction() {
     vec2 pt = (paintMat * vec3(fpos, 1.0)).xy;
     // Measure the angle clockwise from the positive x axis. In the gradient's
@@ -181,6 +237,219 @@ vec4 renderFilteredImage() {
     return color;
 }
 
+// A premultiplied source texel, transparent black outside the image.
+vec4 morphologySample(vec2 pos) {
+    vec2 uv = pos / extent;
+    vec4 c = texture2D(tex, uv);
+    if (texType == 1) c = vec4(c.xyz * c.w, c.w);
+    if (texType == 2) c = vec4(c.x);
+    if (uv.x < 0.0 || uv.y < 0.0 || uv.x > 1.0 || uv.y > 1.0) c = vec4(0.0);
+    return c;
+}
+
+vec4 renderMorphology() {
+    // One pass along imageBlurFilterDirection: frag[0].x is the radius in
+    // pixels (at most 32, enforced on the Rust side), frag[0].y is 1 to dilate
+    // (maximum) and 0 to erode (minimum).
+    float morphologyRadius = frag[0].x;
+    bool dilate = frag[0].y > 0.5;
+
+    vec4 result = morphologySample(fpos);
+    for (float i = 1.0; i <= 32.0; i += 1.0) {
+        // GLES 2.0 only allows constant loop bounds, see renderFilteredImage.
+        if (i > morphologyRadius) {
+            break;
+        }
+        vec4 before = morphologySample(fpos - i * imageBlurFilterDirection);
+        vec4 after = morphologySample(fpos + i * imageBlurFilterDirection);
+        if (dilate) {
+            result = max(result, max(before, after));
+        } else {
+            result = min(result, min(before, after));
+        }
+    }
+
+    return result;
+}
+
+// A premultiplied source texel, with the image extended beyond its edges by
+// the convolution's edge mode (outerCol.x): 0 duplicates the edge pixels, 1
+// wraps around and 2 reads transparent black.
+vec4 convolveSample(vec2 pos) {
+    int edgeMode = int(outerCol.x + 0.5);
+    vec2 p = pos;
+    if (edgeMode == 0) {
+        p = clamp(p, vec2(0.5), extent - 0.5);
+    } else if (edgeMode == 1) {
+        p = mod(p, extent);
+    }
+    vec2 uv = p / extent;
+    vec4 c = texture2D(tex, uv);
+    if (texType == 1) c = vec4(c.xyz * c.w, c.w);
+    if (texType == 2) c = vec4(c.x);
+    if (uv.x < 0.0 || uv.y < 0.0 || uv.x > 1.0 || uv.y > 1.0) c = vec4(0.0);
+    return c;
+}
+
+vec4 renderConvolve() {
+    // The pre-divided kernel fills frag[0..5] and innerCol.x, innerCol.yzw
+    // hold the bias and the kernel's columns and rows. outerCol.y selects
+    // preserveAlpha and outerCol.z is the texture-space step of one image row
+    // down.
+    float bias = innerCol.y;
+    float columns = innerCol.z;
+    float rows = innerCol.w;
+    bool preserveAlpha = outerCol.y > 0.5;
+    vec2 kernelTarget = floor(vec2(columns, rows) * 0.5);
+
+    vec4 sum = vec4(0.0);
+    for (int i = 0; i < 25; i++) {
+        float index = float(i);
+        if (index >= columns * rows) {
+            break;
+        }
+        float y = floor((index + 0.5) / columns);
+        float x = index - y * columns;
+        // As in SVG, the kernel is applied rotated by 180 degrees.
+        vec2 offset = vec2(columns - 1.0 - x, rows - 1.0 - y) - kernelTarget;
+        vec4 c = convolveSample(fpos + vec2(offset.x, offset.y * outerCol.z));
+        if (preserveAlpha && c.a > 0.0) {
+            c.rgb /= c.a;
+        }
+        float weight = dot(frag[i / 4], vec4(equal(ivec4(0, 1, 2, 3), ivec4(i - (i / 4) * 4))));
+        sum += weight * c;
+    }
+
+    float alpha = convolveSample(fpos).a;
+    if (preserveAlpha) {
+        return vec4(clamp(sum.rgb + bias, 0.0, 1.0) * alpha, alpha);
+    }
+    vec4 result = clamp(sum + bias * alpha, 0.0, 1.0);
+    return vec4(min(result.rgb, vec3(result.a)), result.a);
+}
+
+// The feTurbulence lattice is bound as the glyph texture, 256x5 texels: row 0
+// holds the lattice selector, rows 1 to 4 the gradients of each channel as
+// two 16-bit fixed-point values in [-1, 1].
+float turbulenceLattice(float index) {
+    vec2 uv = vec2((mod(index, 256.0) + 0.5) / 256.0, 0.5 / 5.0);
+    return floor(texture2D(glyphtex, uv).r * 255.0 + 0.5);
+}
+
+vec2 turbulenceGradient(float channel, float index) {
+    vec4 g = floor(texture2D(glyphtex, vec2((index + 0.5) / 256.0, (channel + 1.5) / 5.0)) * 255.0 + 0.5);
+    return (g.xz * 256.0 + g.yw) / 65535.0 * 2.0 - 1.0;
+}
+
+// noise2() of the spec's reference code for one channel, from the lattice
+// corners (b00, b10, b01, b11) and the position inside the lattice cell.
+float turbulenceChannel(float channel, vec4 corners, vec2 r0) {
+    vec2 r1 = r0 - 1.0;
+    vec2 s = r0 * r0 * (3.0 - 2.0 * r0);
+    float u = dot(r0, turbulenceGradient(channel, corners.x));
+    float v = dot(vec2(r1.x, r0.y), turbulenceGradient(channel, corners.y));
+    float a = mix(u, v, s.x);
+    u = dot(vec2(r0.x, r1.y), turbulenceGradient(channel, corners.z));
+    v = dot(r1, turbulenceGradient(channel, corners.w));
+    float b = mix(u, v, s.x);
+    return mix(a, b, s.y);
+}
+
+vec4 turbulenceNoise(vec2 position, bool stitching, vec4 stitch) {
+    vec2 t = position + 4096.0;
+    vec2 b0 = floor(t);
+    vec2 b1 = b0 + 1.0;
+    vec2 r0 = t - b0;
+    // Stitching wraps the lattice around the tile: stitch holds its width
+    // and height and the lattice points where it wraps.
+    if (stitching) {
+        if (b0.x >= stitch.z) b0.x -= stitch.x;
+        if (b1.x >= stitch.z) b1.x -= stitch.x;
+        if (b0.y >= stitch.w) b0.y -= stitch.y;
+        if (b1.y >= stitch.w) b1.y -= stitch.y;
+    }
+    float i = turbulenceLattice(b0.x);
+    float j = turbulenceLattice(b1.x);
+    vec4 corners = vec4(
+        turbulenceLattice(i + mod(b0.y, 256.0)),
+        turbulenceLattice(j + mod(b0.y, 256.0)),
+        turbulenceLattice(i + mod(b1.y, 256.0)),
+        turbulenceLattice(j + mod(b1.y, 256.0))
+    );
+    return vec4(
+        turbulenceChannel(0.0, corners, r0),
+        turbulenceChannel(1.0, corners, r0),
+        turbulenceChannel(2.0, corners, r0),
+        turbulenceChannel(3.0, corners, r0)
+    );
+}
+
+vec4 renderTurbulence() {
+    // frag[0] holds the base frequency, the octave count (at most 10) and 1
+    // for fractal noise, frag[1].x enables stitching and frag[1].y is 1 when
+    // the target stores its rows bottom-up. frag[2] holds the stitching
+    // values of the first octave.
+    bool fractalNoise = frag[0].w > 0.5;
+    bool stitching = frag[1].x > 0.5;
+    vec4 stitch = frag[2];
+    // The noise is evaluated at the top-left corner of each target pixel.
+    vec2 point = vec2(fpos.x, frag[1].y > 0.5 ? fpos.y : extent.y - fpos.y) - 0.5;
+    vec2 position = point * frag[0].xy;
+
+    vec4 sum = vec4(0.0);
+    float ratio = 1.0;
+    for (float octave = 0.0; octave < 10.0; octave += 1.0) {
+        // GLES 2.0 only allows constant loop bounds, see renderFilteredImage.
+        if (octave >= frag[0].z) {
+            break;
+        }
+        vec4 noise = turbulenceNoise(position, stitching, stitch);
+        sum += (fractalNoise ? noise : abs(noise)) / ratio;
+        position *= 2.0;
+        ratio *= 2.0;
+        stitch = vec4(stitch.xy * 2.0, stitch.zw * 2.0 - 4096.0);
+    }
+
+    vec4 color = clamp(fractalNoise ? (sum + 1.0) * 0.5 : sum, 0.0, 1.0);
+    return vec4(color.rgb * color.a, color.a);
+}
+
+vec4 renderDisplacementMap() {
+    // frag[0] holds the scale, the x and y channel indices (red, green, blue,
+    // alpha) and the texture-space step of one image row down. The map is
+    // bound as the glyph texture and read unpremultiplied.
+    vec4 m = texture2D(glyphtex, fpos / extent);
+    if (texType == 0 && m.a > 0.0) m.rgb /= m.a;
+    if (texType == 2) m = vec4(m.x);
+    float dx = dot(m, vec4(equal(ivec4(0, 1, 2, 3), ivec4(int(frag[0].y + 0.5)))));
+    float dy = dot(m, vec4(equal(ivec4(0, 1, 2, 3), ivec4(int(frag[0].z + 0.5)))));
+    vec2 offset = frag[0].x * (vec2(dx, dy) - 0.5);
+
+    vec2 uv = (fpos + vec2(offset.x, offset.y * frag[0].w)) / extent;
+    vec4 c = texture2D(tex, uv);
+    if (texType == 1) c = vec4(c.xyz * c.w, c.w);
+    if (texType == 2) c = vec4(c.x);
+    if (uv.x < 0.0 || uv.y < 0.0 || uv.x > 1.0 || uv.y > 1.0) c = vec4(0.0);
+    return c;
+}
+
+vec4 renderComponentTransfer() {
+    // The lookup table is bound as the glyph texture, 256x1 texels: texel x
+    // holds each channel remapped from x / 255. Channels are remapped
+    // unpremultiplied.
+    vec4 c = texture2D(tex, fpos / extent);
+    if (texType == 0 && c.a > 0.0) c.rgb /= c.a;
+    if (texType == 2) c = vec4(c.x);
+    vec4 u = (floor(clamp(c, 0.0, 1.0) * 255.0 + 0.5) + 0.5) / 256.0;
+    vec4 result = vec4(
+        texture2D(glyphtex, vec2(u.r, 0.5)).r,
+        texture2D(glyphtex, vec2(u.g, 0.5)).g,
+        texture2D(glyphtex, vec2(u.b, 0.5)).b,
+        texture2D(glyphtex, vec2(u.a, 0.5)).a
+    );
+    return vec4(result.rgb * result.a, result.a);
+}
+
 vec4 renderColorMatrix() {
     // The 4x5 color matrix is packed row-major into frag[0..4] (the scissor/paint
     // matrix slots, unused during a filter pass). Apply it in unpremultiplied
@@ -200,6 +469,139 @@ vec4 renderColorMatrix() {
     return outc;
 }
 
+// Blend modes of the CSS compositing spec, on unpremultiplied colors: `cb` is
+// the backdrop and `cs` the source.
+float colorDodge(float cb, float cs) {
+    if (cb <= 0.0) return 0.0;
+    if (cs >= 1.0) return 1.0;
+    return min(1.0, cb / (1.0 - cs));
+}
+
+float colorBurn(float cb, float cs) {
+    if (cb >= 1.0) return 1.0;
+    if (cs <= 0.0) return 0.0;
+    return 1.0 - min(1.0, (1.0 - cb) / cs);
+}
+
+float hardLight(float cb, float cs) {
+    if (cs <= 0.5) return cb * 2.0 * cs;
+    float s = 2.0 * cs - 1.0;
+    return cb + s - cb * s;
+}
+
+float softLight(float cb, float cs) {
+    if (cs <= 0.5) return cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb);
+    float d = cb <= 0.25 ? ((16.0 * cb - 12.0) * cb + 4.0) * cb : sqrt(cb);
+    return cb + (2.0 * cs - 1.0) * (d - cb);
+}
+
+float lum(vec3 c) {
+    return dot(c, vec3(0.3, 0.59, 0.11));
+}
+
+vec3 clipColor(vec3 c) {
+    float l = lum(c);
+    float n = min(c.r, min(c.g, c.b));
+    float x = max(c.r, max(c.g, c.b));
+    if (n < 0.0) c = l + (c - l) * l / (l - n);
+    if (x > 1.0) c = l + (c - l) * (1.0 - l) / (x - l);
+    return c;
+}
+
+vec3 setLum(vec3 c, float l) {
+    return clipColor(c + (l - lum(c)));
+}
+
+float sat(vec3 c) {
+    return max(c.r, max(c.g, c.b)) - min(c.r, min(c.g, c.b));
+}
+
+vec3 setSat(vec3 c, float s) {
+    float n = min(c.r, min(c.g, c.b));
+    float range = max(c.r, max(c.g, c.b)) - n;
+    return range > 0.0 ? (c - n) * s / range : vec3(0.0);
+}
+
+vec3 blendColors(int mode, vec3 cb, vec3 cs) {
+    if (mode == 1) return cs;
+    if (mode == 2) return cb * cs;
+    if (mode == 3) return cb + cs - cb * cs;
+    if (mode == 4) return vec3(hardLight(cs.r, cb.r), hardLight(cs.g, cb.g), hardLight(cs.b, cb.b));
+    if (mode == 5) return min(cb, cs);
+    if (mode == 6) return max(cb, cs);
+    if (mode == 7) return vec3(colorDodge(cb.r, cs.r), colorDodge(cb.g, cs.g), colorDodge(cb.b, cs.b));
+    if (mode == 8) return vec3(colorBurn(cb.r, cs.r), colorBurn(cb.g, cs.g), colorBurn(cb.b, cs.b));
+    if (mode == 9) return vec3(hardLight(cb.r, cs.r), hardLight(cb.g, cs.g), hardLight(cb.b, cs.b));
+    if (mode == 10) return vec3(softLight(cb.r, cs.r), softLight(cb.g, cs.g), softLight(cb.b, cs.b));
+    if (mode == 11) return abs(cb - cs);
+    if (mode == 12) return cb + cs - 2.0 * cb * cs;
+    if (mode == 13) return setLum(setSat(cs, sat(cb)), lum(cb));
+    if (mode == 14) return setLum(setSat(cb, sat(cs)), lum(cb));
+    if (mode == 15) return setLum(cs, lum(cb));
+    return setLum(cb, lum(cs));
+}
+
+vec4 renderBlendFilter() {
+    // The operation rides the scissor matrix slots: frag[0].x selects the
+    // arithmetic composite (0) or a blend mode (1 + BlendMode), the arithmetic
+    // weights k1..k4 follow. The backdrop is bound as the glyph texture.
+    vec2 uv = fpos.xy / extent;
+    vec4 s = texture2D(tex, uv);
+    vec4 b = texture2D(glyphtex, uv);
+    if (texType == 1) {
+        s = vec4(s.xyz * s.w, s.w);
+        b = vec4(b.xyz * b.w, b.w);
+    }
+    if (texType == 2) {
+        s = vec4(s.x);
+        b = vec4(b.x);
+    }
+
+    int mode = int(frag[0].x + 0.5);
+    if (mode == 0) {
+        vec4 k = vec4(frag[0].yzw, frag[1].x);
+        vec4 result = clamp(k.x * s * b + k.y * s + k.z * b + k.w, 0.0, 1.0);
+        return vec4(min(result.rgb, result.a), result.a);
+    }
+
+    vec3 cs = s.a > 0.0 ? s.rgb / s.a : vec3(0.0);
+    vec3 cb = b.a > 0.0 ? b.rgb / b.a : vec3(0.0);
+    vec3 mixed = clamp(blendColors(mode, cb, cs), 0.0, 1.0);
+    return vec4((1.0 - b.a) * s.rgb + (1.0 - s.a) * b.rgb + s.a * b.a * mixed, s.a + b.a - s.a * b.a);
+}
+
+float median(float r, float g, float b) {
+    return max(min(r, g), min(max(r, g), b));
+}
+
+vec4 renderDistanceField() {
+    // The RGB channels hold a multi-channel distance field whose median gives
+    // the distance to the outline with sharp corners; alpha holds the true
+    // distance, which stays smooth far from the outline and drives the glow.
+    // 0.5 is the outline and sdfPxRange the encoded range in device pixels.
+    vec4 field = texture2D(glyphtex, ftcoord);
+    float dist = (median(field.r, field.g, field.b) - 0.5) * sdfPxRange;
+    float trueDist = (field.a - 0.5) * sdfPxRange;
+
+    // Distance inside the painted shape: the glyph itself, or a band around
+    // its outline when stroking.
+    if (sdfStrokeHalfWidth > 0.0) {
+        dist = sdfStrokeHalfWidth - abs(dist);
+        trueDist = sdfStrokeHalfWidth - abs(trueDist);
+    }
+
+    // Layer fill over outline over glow (premultiplied "source over").
+    vec4 color = innerCol * clamp(dist + 0.5, 0.0, 1.0);
+    if (sdfOutlineWidth > 0.0) {
+        color += outerCol * clamp(dist + sdfOutlineWidth + 0.5, 0.0, 1.0) * (1.0 - color.a);
+    }
+    if (sdfGlowRadius > 0.0) {
+        float glow = 1.0 - smoothstep(0.0, sdfGlowRadius, -(trueDist + sdfOutlineWidth));
+        color += sdfGlowCol * glow * (1.0 - color.a);
+    }
+    return color;
+}
+
 void main(void) {
     vec4 result;
 
@@ -241,21 +643,56 @@ void main(void) {
     result = renderImageGradientConic();
 #elif SELECT_SHADER == SHADER_TYPE_FilterImageColorMatrix
     result = renderColorMatrix();
+#elif SELECT_SHADER == SHADER_TYPE_FillDistanceField
+    result = renderDistanceField();
+#elif SELECT_SHADER == SHADER_TYPE_FilterImageBlend
+    gl_FragColor = renderBlendFilter();
+    return;
+#elif SELECT_SHADER == SHADER_TYPE_FilterImageMorphology
+    gl_FragColor = renderMorphology();
+    return;
+#elif SELECT_SHADER == SHADER_TYPE_FilterImageConvolve
+    gl_FragColor = renderConvolve();
+    return;
+#elif SELECT_SHADER == SHADER_TYPE_FilterImageTurbulence
+    gl_FragColor = renderTurbulence();
+    return;
+#elif SELECT_SHADER == SHADER_TYPE_FilterImageDisplacementMap
+    gl_FragColor = renderDisplacementMap();
+    return;
+#elif SELECT_SHADER == SHADER_TYPE_FilterImageComponentTransfer
+    gl_FragColor = renderComponentTransfer();
+    return;
+#elif SELECT_SHADER == SHADER_TYPE_BoxShadow
+    result = renderBoxShadow();
 #else
 #error A shader variant must be selected with the SELECT_SHADER pre-processor variable
 #endif
 
     float scissor = scissorMask(fpos);
 
-#ifdef ENABLE_GLYPH_TEXTURE
+#if SELECT_SHADER == SHADER_TYPE_FillDistanceField
+    // The distance field already yields the coverage, only clip it.
+    result *= scissor;
+#elif defined(ENABLE_GLYPH_TEXTURE)
     // Textured tris
     vec4 mask = texture2D(glyphtex, ftcoord);
 
     if (glyphTextureType == 1) {
         mask = vec4(mask.x);
+    } else if (glyphTextureType == 4 || glyphTextureType == 5) {
+        // Subpixel mask: one coverage per channel (component alpha). The
+        // coverage pass outputs the paint alpha, which the blend state turns
+        // into dst * (1 - alpha * coverage); the color pass adds the color.
+        if (glyphTextureType == 4) {
+            result = vec4(result.a);
+        }
+        mask = vec4(mask.xyz, (mask.x + mask.y + mask.z) / 3.0);
     } else {
         result = vec4(1, 1, 1, 1);
-        mask = vec4(mask.xyz * mask.w, mask.w);
+        if (glyphTextureType == 2) {
+            mask = vec4(mask.xyz * mask.w, mask.w);
+        }
     }
 
     mask *= scissor;
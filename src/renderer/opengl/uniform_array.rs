@@ -1,16 +1,12 @@
 use super::Params;
 
-const UNIFORMARRAY_SIZE: usize = 14;
+const UNIFORMARRAY_SIZE: usize = 16;
 
 pub struct UniformArray([f32; UNIFORMARRAY_SIZE * 4]);
 
 impl Default for UniformArray {
     fn default() -> Self {
-        Self([
-            0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
-            0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
-            0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
-        ])
+        Self([0.0; UNIFORMARRAY_SIZE * 4])
     }
 }
 
@@ -95,6 +91,14 @@ impl UniformArray {
         // frag[13].x in the fragment shader; frag[12].w holds the scissor radius.
         self.0[52] = angle;
     }
+
+    pub fn set_sdf_glow_col(&mut self, col: [f32; 4]) {
+        self.0[56..60].copy_from_slice(&col);
+    }
+
+    pub fn set_sdf_params(&mut self, sdf_params: [f32; 4]) {
+        self.0[60..64].copy_from_slice(&sdf_params);
+    }
 }
 
 impl From<&Params> for UniformArray {
@@ -120,6 +124,8 @@ impl From<&Params> for UniformArray {
         arr.set_image_blur_filter_sigma(params.image_blur_filter_sigma);
         arr.set_image_blur_filter_coeff(params.image_blur_filter_coeff);
         arr.set_conic_start_angle(params.conic_start_angle);
+        arr.set_sdf_glow_col(params.sdf_glow_col);
+        arr.set_sdf_params(params.sdf_params);
 
         arr
     }
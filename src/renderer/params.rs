@@ -1,7 +1,8 @@
 use crate::{
     geometry::Position,
     paint::{GlyphTexture, GradientColors},
-    ImageFlags, ImageStore, PaintFlavor, PixelFormat, Scissor, Transform2D,
+    Color, ImageFilter, ImageFlags, ImageInfo, ImageStore, MorphologyOperator, PaintFlavor, PixelFormat, Scissor,
+    Transform2D, TurbulenceKind,
 };
 
 use super::ShaderType;
@@ -22,11 +23,16 @@ pub struct Params {
     pub(crate) stroke_thr: f32,
     pub(crate) tex_type: f32,
     pub(crate) shader_type: ShaderType,
-    pub(crate) glyph_texture_type: u8, // 0 -> no glyph rendering, 1 -> alpha mask, 2 -> color texture
+    pub(crate) glyph_texture_type: u8, // 0 -> no glyph rendering, 1 -> alpha mask, 2 -> color texture, 3 -> distance field, 4/5 -> subpixel coverage/color pass, 6 -> premultiplied color texture, 7 -> filter backdrop
     pub(crate) image_blur_filter_direction: [f32; 2],
     pub(crate) image_blur_filter_sigma: f32,
     pub(crate) image_blur_filter_coeff: [f32; 3],
     pub(crate) conic_start_angle: f32,
+    // Distance-field text: glow color, then the field's range in device pixels,
+    // the stroke half width, the outline width and the glow radius (all in
+    // device pixels).
+    pub(crate) sdf_glow_col: [f32; 4],
+    pub(crate) sdf_params: [f32; 4],
 }
 
 impl Params {
@@ -68,7 +74,21 @@ impl Params {
         params.glyph_texture_type = match glyph_texture {
             GlyphTexture::None => 0,
             GlyphTexture::AlphaMask(_) => 1,
-            GlyphTexture::ColorTexture(_) => 2,
+            // The glyph atlas stores color glyphs premultiplied.
+            GlyphTexture::ColorTexture(id) => {
+                if images
+                    .info(*id)
+                    .is_some_and(|info| info.flags().contains(ImageFlags::PREMULTIPLIED))
+                {
+                    6
+                } else {
+                    2
+                }
+            }
+            GlyphTexture::DistanceField(_) => 3,
+            GlyphTexture::SubpixelCoverage(_) => 4,
+            GlyphTexture::SubpixelColor(_) => 5,
+            GlyphTexture::FilterBackdrop(_) => 7,
         };
 
         let inv_transform;
@@ -200,6 +220,33 @@ impl Params {
                     }
                 }
             }
+            &PaintFlavor::BoxShadow {
+                pos: Position { x, y },
+                width,
+                height,
+                radius,
+                sigma,
+                color,
+                inset,
+            } => {
+                let mut transform = Transform2D::translation(x + width * 0.5, y + height * 0.5);
+                transform *= *global_transform;
+                inv_transform = transform.inverse();
+
+                params.extent[0] = width * 0.5;
+                params.extent[1] = height * 0.5;
+                params.radius = *radius;
+                // A zero sigma would divide by zero in the shader
+                params.feather = sigma.max(0.01);
+                let (inner, outer) = if *inset {
+                    (Color::rgbaf(0.0, 0.0, 0.0, 0.0), *color)
+                } else {
+                    (*color, Color::rgbaf(0.0, 0.0, 0.0, 0.0))
+                };
+                params.inner_col = inner.premultiplied().to_array();
+                params.outer_col = outer.premultiplied().to_array();
+                params.shader_type = ShaderType::BoxShadow;
+            }
             &PaintFlavor::RadialGradient {
                 center: Position { x: cx, y: cy },
                 in_radius: (in_rx, in_ry),
@@ -264,4 +311,170 @@ impl Params {
     pub(crate) fn uses_glyph_texture(self) -> bool {
         self.glyph_texture_type != 0
     }
+
+    // Selects the shader of a single-pass image filter and packs its
+    // parameters into the scissor/paint matrix slots, which are dead during a
+    // filter pass, so no uniform-array growth is needed.
+    pub(crate) fn set_single_pass_filter(&mut self, filter: ImageFilter, source: ImageInfo, target: ImageInfo) {
+        match filter {
+            ImageFilter::ColorMatrix { matrix } => {
+                // frag[0..2] hold the first 12 values, frag[3..4] the last 8;
+                // the shader reads them back as a row-major 4x5 matrix.
+                self.shader_type = ShaderType::FilterImageColorMatrix;
+                self.scissor_mat.copy_from_slice(&matrix[..12]);
+                self.paint_mat[..8].copy_from_slice(&matrix[12..20]);
+            }
+            ImageFilter::Blend { mode, .. } => {
+                // The operation comes first: 0 for arithmetic, 1 + the blend
+                // mode otherwise, followed by the arithmetic weights.
+                self.shader_type = ShaderType::FilterImageBlend;
+                self.scissor_mat[0] = 1.0 + mode as u8 as f32;
+            }
+            ImageFilter::Arithmetic { k1, k2, k3, k4, .. } => {
+                self.shader_type = ShaderType::FilterImageBlend;
+                self.scissor_mat[..5].copy_from_slice(&[0.0, k1, k2, k3, k4]);
+            }
+            ImageFilter::Convolve {
+                kernel,
+                size: (columns, rows),
+                divisor,
+                bias,
+                edge_mode,
+                preserve_alpha,
+            } => {
+                // The kernel, pre-divided, fills frag[0..5] and frag[6].x (the
+                // inner color); the rest of the inner color and the outer color
+                // hold the settings. An oversized kernel gets no taps and no
+                // bias, which renders transparent black.
+                self.shader_type = ShaderType::FilterImageConvolve;
+                let count = columns.saturating_mul(rows);
+                let (columns, rows, bias, preserve_alpha) = if count <= kernel.len() {
+                    (columns, rows, bias, preserve_alpha)
+                } else {
+                    (0, 0, 0.0, false)
+                };
+                let divisor = if divisor != 0.0 {
+                    divisor
+                } else {
+                    match kernel[..count.min(kernel.len())].iter().sum::<f32>() {
+                        sum if sum != 0.0 => sum,
+                        _ => 1.0,
+                    }
+                };
+                let kernel = kernel.map(|value| value / divisor);
+                self.scissor_mat.copy_from_slice(&kernel[..12]);
+                self.paint_mat.copy_from_slice(&kernel[12..24]);
+                self.inner_col = [kernel[24], bias, columns as f32, rows as f32];
+                // The last value steps one row down the image in texture space:
+                // images rendered to bottom-up store their rows reversed.
+                self.outer_col = [
+                    edge_mode as u8 as f32,
+                    if preserve_alpha { 1.0 } else { 0.0 },
+                    if source.flags().contains(ImageFlags::FLIP_Y) {
+                        -1.0
+                    } else {
+                        1.0
+                    },
+                    0.0,
+                ];
+            }
+            ImageFilter::Turbulence {
+                kind,
+                base_frequency: (frequency_x, frequency_y),
+                octaves,
+                stitch_tiles,
+                ..
+            } => {
+                // frag[0] holds the base frequency, the octave count and 1 for
+                // fractal noise, frag[1].x enables stitching and frag[1].y is 1
+                // when the target stores its rows bottom-up. frag[2] holds the
+                // stitching tile size and wrap-around points in lattice units,
+                // as in the reference code of the spec. Negative frequencies
+                // are an error in SVG and get no octaves: transparent black.
+                self.shader_type = ShaderType::FilterImageTurbulence;
+                const PERLIN_N: f32 = 4096.0;
+                let (width, height) = (source.width() as f32, source.height() as f32);
+                let (mut frequency_x, mut frequency_y) = (frequency_x, frequency_y);
+                let valid = frequency_x >= 0.0 && frequency_y >= 0.0;
+                let mut stitch = [0.0; 4];
+                if stitch_tiles && valid {
+                    // Snap the frequencies to whole cycles per tile.
+                    let snap = |frequency: f32, size: f32| {
+                        if frequency == 0.0 {
+                            return frequency;
+                        }
+                        let low = (size * frequency).floor() / size;
+                        let high = (size * frequency).ceil() / size;
+                        if frequency / low < high / frequency {
+                            low
+                        } else {
+                            high
+                        }
+                    };
+                    frequency_x = snap(frequency_x, width);
+                    frequency_y = snap(frequency_y, height);
+                    let tile_width = (width * frequency_x + 0.5).floor();
+                    let tile_height = (height * frequency_y + 0.5).floor();
+                    stitch = [tile_width, tile_height, PERLIN_N + tile_width, PERLIN_N + tile_height];
+                }
+                self.scissor_mat[..6].copy_from_slice(&[
+                    frequency_x,
+                    frequency_y,
+                    if valid { octaves.min(10) as f32 } else { 0.0 },
+                    if valid && kind == TurbulenceKind::FractalNoise {
+                        1.0
+                    } else {
+                        0.0
+                    },
+                    if stitch_tiles && valid { 1.0 } else { 0.0 },
+                    if target.flags().contains(ImageFlags::FLIP_Y) {
+                        1.0
+                    } else {
+                        0.0
+                    },
+                ]);
+                self.scissor_mat[8..12].copy_from_slice(&stitch);
+            }
+            ImageFilter::DisplacementMap {
+                scale,
+                x_channel,
+                y_channel,
+                ..
+            } => {
+                // frag[0] holds the scale, the channel indices and, as for the
+                // convolution, the texture-space step of one image row down.
+                self.shader_type = ShaderType::FilterImageDisplacementMap;
+                self.scissor_mat[..4].copy_from_slice(&[
+                    scale,
+                    x_channel as u8 as f32,
+                    y_channel as u8 as f32,
+                    if source.flags().contains(ImageFlags::FLIP_Y) {
+                        -1.0
+                    } else {
+                        1.0
+                    },
+                ]);
+            }
+            ImageFilter::ComponentTransfer { .. } => {
+                // Everything lives in the lookup table.
+                self.shader_type = ShaderType::FilterImageComponentTransfer;
+            }
+            ImageFilter::GaussianBlur { .. } | ImageFilter::Morphology { .. } => {
+                unreachable!("separable filters take two passes")
+            }
+        }
+    }
+
+    // Sets up one pass of a morphology filter along `direction`: the radius,
+    // rounded and clamped to the shader's loop bound, then 1 to dilate or 0 to
+    // erode.
+    pub(crate) fn set_morphology_pass(&mut self, operator: MorphologyOperator, radius: f32, direction: [f32; 2]) {
+        self.shader_type = ShaderType::FilterImageMorphology;
+        self.image_blur_filter_direction = direction;
+        self.scissor_mat[0] = radius.round().clamp(0.0, 32.0);
+        self.scissor_mat[1] = match operator {
+            MorphologyOperator::Erode => 0.0,
+            MorphologyOperator::Dilate => 1.0,
+        };
+    }
 }
@@ -61,7 +61,7 @@ impl std::fmt::Debug for WGPURenderOutput {
 use super::Params;
 use super::Vertex;
 
-const UNIFORMARRAY_SIZE: usize = 14;
+const UNIFORMARRAY_SIZE: usize = 16;
 const UNIFORM_BYTES: u64 = (UNIFORMARRAY_SIZE * 4 * 4) as u64;
 // A concave fill and a stencil stroke record two sets of params, every other command one.
 const UNIFORM_SLOTS_PER_COMMAND: u64 = 2;
@@ -92,11 +92,7 @@ pub struct UniformArray([f32; UNIFORMARRAY_SIZE * 4]);
 
 impl Default for UniformArray {
     fn default() -> Self {
-        Self([
-            0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
-            0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
-            0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
-        ])
+        Self([0.0; UNIFORMARRAY_SIZE * 4])
     }
 }
 
@@ -182,6 +178,14 @@ impl UniformArray {
         // float 51 (byte offset 204) holds the scissor radius.
         self.0[52] = angle;
     }
+
+    pub fn set_sdf_glow_col(&mut self, col: [f32; 4]) {
+        self.0[56..60].copy_from_slice(&col);
+    }
+
+    pub fn set_sdf_params(&mut self, sdf_params: [f32; 4]) {
+        self.0[60..64].copy_from_slice(&sdf_params);
+    }
 }
 
 impl From<&Params> for UniformArray {
@@ -207,6 +211,8 @@ impl From<&Params> for UniformArray {
         arr.set_image_blur_filter_sigma(params.image_blur_filter_sigma);
         arr.set_image_blur_filter_coeff(params.image_blur_filter_coeff);
         arr.set_conic_start_angle(params.conic_start_angle);
+        arr.set_sdf_glow_col(params.sdf_glow_col);
+        arr.set_sdf_params(params.sdf_params);
 
         arr
     }
@@ -673,7 +679,10 @@ impl Renderer for WGPURenderer {
                         images,
                     );
                 }
-                super::CommandType::RenderFilteredImage { target_image, filter } => match filter {
+                super::CommandType::RenderFilteredImage {
+                    target_image,
+                    ref filter,
+                } => match filter.clone() {
                     crate::ImageFilter::GaussianBlur { sigma } => {
                         gaussian_blur_filter(
                             &self.device,
@@ -686,18 +695,47 @@ impl Renderer for WGPURenderer {
                             target_image,
                         );
                     }
-                    crate::ImageFilter::ColorMatrix { matrix } => {
-                        color_matrix_filter(
+                    crate::ImageFilter::Morphology {
+                        operator,
+                        radius_x,
+                        radius_y,
+                    } => {
+                        morphology_filter(
+                            &self.device,
+                            &mut current_render_target,
+                            images,
+                            command,
+                            operator,
+                            [radius_x, radius_y],
+                            &mut render_pass_builder,
+                            &mut pipeline_and_bindgroup_mapper,
+                            target_image,
+                        );
+                    }
+                    filter => {
+                        single_pass_filter(
                             &mut current_render_target,
                             images,
                             command,
-                            matrix,
+                            filter,
                             &mut render_pass_builder,
                             &mut pipeline_and_bindgroup_mapper,
                             target_image,
                         );
                     }
                 },
+                super::CommandType::CopyRenderTarget { target_image, x, y } => {
+                    copy_render_target(
+                        &self.device,
+                        current_render_target,
+                        images,
+                        &command,
+                        [x, y],
+                        &mut render_pass_builder,
+                        &mut pipeline_and_bindgroup_mapper,
+                        target_image,
+                    );
+                }
             }
         }
 
@@ -740,6 +778,7 @@ impl Renderer for WGPURenderer {
                     crate::PixelFormat::Gray8 => wgpu::TextureFormat::R8Unorm,
                 },
                 usage: wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::COPY_SRC
                     | wgpu::TextureUsages::COPY_DST
                     | wgpu::TextureUsages::RENDER_ATTACHMENT,
                 view_formats: &[],
@@ -862,10 +901,68 @@ fn gaussian_blur_filter(
     pipeline_and_bindgroup_mapper: &mut CommandToPipelineAndBindGroupMapper,
     target_image: ImageId,
 ) {
-    let blend_state = blend_state(&command).into();
+    let mut blur_params = filter_params(images, &command);
+    blur_params.shader_type = ShaderType::FilterImage;
 
-    let previous_render_target = *current_render_target;
+    let gauss_coeff_x = 1. / ((2. * std::f32::consts::PI).sqrt() * sigma);
+    let gauss_coeff_y = f32::exp(-0.5 / (sigma * sigma));
+    let gauss_coeff_z = gauss_coeff_y * gauss_coeff_y;
+
+    blur_params.image_blur_filter_coeff[0] = gauss_coeff_x;
+    blur_params.image_blur_filter_coeff[1] = gauss_coeff_y;
+    blur_params.image_blur_filter_coeff[2] = gauss_coeff_z;
+
+    blur_params.image_blur_filter_direction = [1.0, 0.0];
+    // GLES 2.0 does not allow non-constant loop indices, so limit the standard devitation to allow for a upper fixed limit
+    // on the number of iterations in the fragment shader.
+    blur_params.image_blur_filter_sigma = sigma.min(8.);
+
+    let mut vertical_params = blur_params;
+    vertical_params.image_blur_filter_direction = [0.0, 1.0];
 
+    separable_filter(
+        device,
+        current_render_target,
+        images,
+        command,
+        [blur_params, vertical_params],
+        render_pass_builder,
+        pipeline_and_bindgroup_mapper,
+        target_image,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+fn morphology_filter(
+    device: &wgpu::Device,
+    current_render_target: &mut RenderTarget,
+    images: &mut ImageStore<Image>,
+    command: super::Command,
+    operator: crate::MorphologyOperator,
+    radius: [f32; 2],
+    render_pass_builder: &mut RenderPassBuilder<'_>,
+    pipeline_and_bindgroup_mapper: &mut CommandToPipelineAndBindGroupMapper,
+    target_image: ImageId,
+) {
+    let mut horizontal_params = filter_params(images, &command);
+    let mut vertical_params = horizontal_params;
+    horizontal_params.set_morphology_pass(operator, radius[0], [1.0, 0.0]);
+    vertical_params.set_morphology_pass(operator, radius[1], [0.0, 1.0]);
+
+    separable_filter(
+        device,
+        current_render_target,
+        images,
+        command,
+        [horizontal_params, vertical_params],
+        render_pass_builder,
+        pipeline_and_bindgroup_mapper,
+        target_image,
+    );
+}
+
+/// Parameters sampling the command's source image over the whole filter quad.
+fn filter_params(images: &ImageStore<Image>, command: &super::Command) -> Params {
     let source_image = images.get(command.image.unwrap()).unwrap();
 
     let image_paint = crate::Paint::image(
@@ -878,7 +975,7 @@ fn gaussian_blur_filter(
         1.,
     );
 
-    let mut blur_params = Params::new(
+    Params::new(
         images,
         &Default::default(),
         &image_paint.flavor,
@@ -887,24 +984,30 @@ fn gaussian_blur_filter(
         0.,
         0.,
         0.,
-    );
-    blur_params.shader_type = ShaderType::FilterImage;
+    )
+}
 
-    let gauss_coeff_x = 1. / ((2. * std::f32::consts::PI).sqrt() * sigma);
-    let gauss_coeff_y = f32::exp(-0.5 / (sigma * sigma));
-    let gauss_coeff_z = gauss_coeff_y * gauss_coeff_y;
+/// Two-pass filters (blur, morphology): the first pass renders into a
+/// temporary texture, the second one from there into the target image.
+#[allow(clippy::too_many_arguments)]
+fn separable_filter(
+    device: &wgpu::Device,
+    current_render_target: &mut RenderTarget,
+    images: &mut ImageStore<Image>,
+    command: super::Command,
+    passes: [Params; 2],
+    render_pass_builder: &mut RenderPassBuilder<'_>,
+    pipeline_and_bindgroup_mapper: &mut CommandToPipelineAndBindGroupMapper,
+    target_image: ImageId,
+) {
+    let blend_state = blend_state(&command).into();
 
-    blur_params.image_blur_filter_coeff[0] = gauss_coeff_x;
-    blur_params.image_blur_filter_coeff[1] = gauss_coeff_y;
-    blur_params.image_blur_filter_coeff[2] = gauss_coeff_z;
+    let previous_render_target = *current_render_target;
 
-    blur_params.image_blur_filter_direction = [1.0, 0.0];
-    // GLES 2.0 does not allow non-constant loop indices, so limit the standard devitation to allow for a upper fixed limit
-    // on the number of iterations in the fragment shader.
-    blur_params.image_blur_filter_sigma = sigma.min(8.);
+    let source_image = images.get(command.image.unwrap()).unwrap();
 
-    let horizontal_blur_buffer = device.create_texture(&wgpu::TextureDescriptor {
-        label: Some("blur horizontal"),
+    let first_pass_buffer = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("filter first pass"),
         size: wgpu::Extent3d {
             width: source_image.info.width() as _,
             height: source_image.info.height() as _,
@@ -923,7 +1026,7 @@ fn gaussian_blur_filter(
     });
 
     render_pass_builder.set_render_target_texture(
-        &horizontal_blur_buffer,
+        &first_pass_buffer,
         None,
         wgpu::LoadOp::Clear(wgpu::Color::default()),
     );
@@ -935,7 +1038,7 @@ fn gaussian_blur_filter(
             wgpu::PrimitiveTopology::TriangleList,
             StencilTest::Disabled,
             Some(wgpu::Face::Back),
-            &blur_params,
+            &passes[0],
             images,
             command.image.map(ImageOrTexture::Image),
             command.glyph_texture,
@@ -945,8 +1048,6 @@ fn gaussian_blur_filter(
 
     render_pass_builder.set_render_target_image(images, target_image, wgpu::LoadOp::Clear(wgpu::Color::default()));
 
-    blur_params.image_blur_filter_direction = [0.0, 1.0];
-
     if let Some((start, count)) = command.triangles_verts {
         pipeline_and_bindgroup_mapper.update_renderpass(
             render_pass_builder,
@@ -954,9 +1055,9 @@ fn gaussian_blur_filter(
             wgpu::PrimitiveTopology::TriangleList,
             StencilTest::Disabled,
             Some(wgpu::Face::Back),
-            &blur_params,
+            &passes[1],
             images,
-            Some(ImageOrTexture::Texture(horizontal_blur_buffer)),
+            Some(ImageOrTexture::Texture(first_pass_buffer)),
             command.glyph_texture,
         );
         render_pass_builder.draw(start as u32..(start + count) as u32);
@@ -973,14 +1074,16 @@ fn gaussian_blur_filter(
     }
 }
 
-/// Single-pass color-matrix filter: sample the source once and apply the 4x5
-/// matrix. Mirrors `gaussian_blur_filter` but without the intermediate texture.
+/// Single-pass filters (color matrix, blend, arithmetic, ...): sample the
+/// source, and the backdrop or noise lattice the command carries as its glyph
+/// texture, once per pixel. Mirrors
+/// `gaussian_blur_filter` but without the intermediate texture.
 #[allow(clippy::too_many_arguments)]
-fn color_matrix_filter(
+fn single_pass_filter(
     current_render_target: &mut RenderTarget,
     images: &mut ImageStore<Image>,
     command: super::Command,
-    matrix: [f32; 20],
+    filter: crate::ImageFilter,
     render_pass_builder: &mut RenderPassBuilder<'_>,
     pipeline_and_bindgroup_mapper: &mut CommandToPipelineAndBindGroupMapper,
     target_image: ImageId,
@@ -988,6 +1091,7 @@ fn color_matrix_filter(
     let blend_state = blend_state(&command).into();
     let previous_render_target = *current_render_target;
 
+    let target_image_info = images.get(target_image).unwrap().info;
     let source_image = images.get(command.image.unwrap()).unwrap();
     let image_paint = crate::Paint::image(
         command.image.unwrap(),
@@ -1002,17 +1106,13 @@ fn color_matrix_filter(
         images,
         &Default::default(),
         &image_paint.flavor,
-        &Default::default(),
+        &command.glyph_texture,
         &Scissor::default(),
         0.,
         0.,
         0.,
     );
-    params.shader_type = ShaderType::FilterImageColorMatrix;
-    // The 4x5 matrix rides the dead scissor/paint-mat slots during the filter
-    // pass (see `renderColorMatrix` in the shader) — no uniform-array growth.
-    params.scissor_mat.copy_from_slice(&matrix[..12]);
-    params.paint_mat[..8].copy_from_slice(&matrix[12..20]);
+    params.set_single_pass_filter(filter, source_image.info, target_image_info);
 
     render_pass_builder.set_render_target_image(images, target_image, wgpu::LoadOp::Clear(wgpu::Color::default()));
 
@@ -1042,6 +1142,117 @@ fn color_matrix_filter(
     }
 }
 
+/// Copies a region of the current render target into `target_image`. Image
+/// targets already keep their rows bottom-up and are copied as they are; the
+/// screen keeps its rows top-down, so it is copied into a scratch texture and
+/// flipped into the target by an identity color matrix pass over the quad the
+/// command carries. Reading the screen requires `COPY_SRC` usage on the output
+/// texture; without it the target is left untouched.
+#[allow(clippy::too_many_arguments)]
+fn copy_render_target(
+    device: &wgpu::Device,
+    current_render_target: RenderTarget,
+    images: &mut ImageStore<Image>,
+    command: &super::Command,
+    [x, y]: [u32; 2],
+    render_pass_builder: &mut RenderPassBuilder<'_>,
+    pipeline_and_bindgroup_mapper: &mut CommandToPipelineAndBindGroupMapper,
+    target_image: ImageId,
+) {
+    let Some(target) = images.get(target_image) else {
+        return;
+    };
+    let target_info = target.info;
+    let Texture::Internal(target_texture) = &target.texture else {
+        return;
+    };
+    let target_texture = target_texture.clone();
+    let (width, height) = (target_texture.width(), target_texture.height());
+
+    match current_render_target {
+        RenderTarget::Image(source_image) => {
+            let Some(Texture::Internal(source_texture)) = images.get(source_image).map(|image| &image.texture) else {
+                return;
+            };
+            let source_texture = source_texture.clone();
+            if source_texture.format() != target_texture.format()
+                || x + width > source_texture.width()
+                || y + height > source_texture.height()
+            {
+                return;
+            }
+            let origin = [x, source_texture.height() - y - height];
+            render_pass_builder.copy_texture(&source_texture, origin, &target_texture);
+            render_pass_builder.set_render_target_image(images, source_image, wgpu::LoadOp::Load);
+        }
+        RenderTarget::Screen => {
+            let screen_texture = render_pass_builder.surface_view.texture().clone();
+            if !screen_texture.usage().contains(wgpu::TextureUsages::COPY_SRC)
+                || x + width > screen_texture.width()
+                || y + height > screen_texture.height()
+            {
+                return;
+            }
+
+            let scratch = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("render target copy"),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: screen_texture.format(),
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            });
+            render_pass_builder.copy_texture(&screen_texture, [x, y], &scratch);
+
+            let image_paint = crate::Paint::image(target_image, 0., 0., width as _, height as _, 0., 1.);
+            let mut params = Params::new(
+                images,
+                &Default::default(),
+                &image_paint.flavor,
+                &GlyphTexture::None,
+                &Scissor::default(),
+                0.,
+                0.,
+                0.,
+            );
+            params.set_single_pass_filter(
+                crate::ImageFilter::ColorMatrix {
+                    matrix: crate::ImageFilter::IDENTITY_MATRIX,
+                },
+                target_info,
+                target_info,
+            );
+
+            render_pass_builder.set_render_target_image(
+                images,
+                target_image,
+                wgpu::LoadOp::Clear(wgpu::Color::default()),
+            );
+            if let Some((start, count)) = command.triangles_verts {
+                pipeline_and_bindgroup_mapper.update_renderpass(
+                    render_pass_builder,
+                    Some(wgpu::BlendState::REPLACE),
+                    wgpu::PrimitiveTopology::TriangleList,
+                    StencilTest::Disabled,
+                    Some(wgpu::Face::Back),
+                    &params,
+                    images,
+                    Some(ImageOrTexture::Texture(scratch)),
+                    GlyphTexture::None,
+                );
+                render_pass_builder.draw(start as u32..(start + count) as u32);
+            }
+            render_pass_builder.set_render_target_screen();
+        }
+    }
+}
+
 fn triangles(
     command: &super::Command,
     pipeline_and_bindgroup_mapper: &mut CommandToPipelineAndBindGroupMapper,
@@ -1902,6 +2113,36 @@ impl<'a> RenderPassBuilder<'a> {
         self.rpass = Some(rpass.forget_lifetime());
     }
 
+    /// Copies the top-left `destination`-sized region of `source` at `origin`
+    /// into `destination`. Copies cannot be recorded inside a render pass, so
+    /// this ends the current one; set a render target before drawing again.
+    fn copy_texture(&mut self, source: &wgpu::Texture, origin: [u32; 2], destination: &wgpu::Texture) {
+        drop(self.rpass.take());
+        self.encoder.copy_texture_to_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: source,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: origin[0],
+                    y: origin[1],
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyTextureInfo {
+                texture: destination,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::Extent3d {
+                width: destination.width(),
+                height: destination.height(),
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
     fn draw(&mut self, vertices: std::ops::Range<u32>) {
         self.rpass.as_mut().unwrap().draw(vertices, 0..1);
     }
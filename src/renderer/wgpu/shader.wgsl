@@ -12,7 +12,7 @@ struct Params {
     stroke_thr: f32,
     tex_type: f32,
     shader_type: f32,
-    glyph_texture_type: f32, // 0 -> no glyph rendering, 1 -> alpha mask, 2 -> color texture
+    glyph_texture_type: f32, // 0 -> no glyph rendering, 1 -> alpha mask, 2 -> color texture, 3 -> distance field, 4/5 -> subpixel coverage/color pass, 6 -> premultiplied color texture
     image_blur_filter_sigma: f32,
     image_blur_filter_direction: vec2<f32>,
     image_blur_filter_coeff: vec3<f32>,
@@ -21,6 +21,15 @@ struct Params {
     // is frag[13].x in the flat uniform array written from Rust.
     scissor_radius: f32,
     conic_start_angle: f32,
+    // Unused remainder of the conic_start_angle row (frag[13].yzw).
+    _pad0: f32,
+    _pad1: f32,
+    _pad2: f32,
+    // Distance-field text (frag[14] and frag[15]): glow color, then the
+    // field's range, stroke half width, outline width and glow radius, all in
+    // device pixels.
+    sdf_glow_col: vec4<f32>,
+    sdf_params: vec4<f32>,
 }
 
 const SHADER_TYPE_FillGradient: i32 = 0;
@@ -34,6 +43,14 @@ const SHADER_TYPE_FillColorUnclipped: i32 = 7;
 const SHADER_TYPE_FillGradientConic: i32 = 8;
 const SHADER_TYPE_FillImageGradientConic: i32 = 9;
 const SHADER_TYPE_FilterImageColorMatrix: i32 = 10;
+const SHADER_TYPE_FillDistanceField: i32 = 11;
+const SHADER_TYPE_FilterImageBlend: i32 = 12;
+const SHADER_TYPE_FilterImageMorphology: i32 = 13;
+const SHADER_TYPE_FilterImageConvolve: i32 = 14;
+const SHADER_TYPE_FilterImageTurbulence: i32 = 15;
+const SHADER_TYPE_FilterImageDisplacementMap: i32 = 16;
+const SHADER_TYPE_FilterImageComponentTransfer: i32 = 17;
+const SHADER_TYPE_BoxShadow: i32 = 18;
 
 const TAU: f32 = 6.28318530717958647692528676655900577;
 
@@ -161,6 +178,30 @@ fn fs_main(vertex: VertexOutput) -> @location(0) vec4<f32> {
         case SHADER_TYPE_FilterImageColorMatrix: {
             return renderColorMatrix(vertex, params);
         }
+        case SHADER_TYPE_FillDistanceField: {
+            result = renderDistanceField(vertex, params);
+        }
+        case SHADER_TYPE_FilterImageBlend: {
+            return renderBlendFilter(vertex, params);
+        }
+        case SHADER_TYPE_FilterImageMorphology: {
+            return renderMorphology(vertex, params);
+        }
+        case SHADER_TYPE_FilterImageConvolve: {
+            return renderConvolve(vertex, params);
+        }
+        case SHADER_TYPE_FilterImageTurbulence: {
+            return renderTurbulence(vertex, params);
+        }
+        case SHADER_TYPE_FilterImageDisplacementMap: {
+            return renderDisplacementMap(vertex, params);
+        }
+        case SHADER_TYPE_FilterImageComponentTransfer: {
+            return renderComponentTransfer(vertex, params);
+        }
+        case SHADER_TYPE_BoxShadow: {
+            result = renderBoxShadow(vertex, params);
+        }
         default: {
             result = vec4<f32>(0.0, 0.0, 1.0, 1.0);
         }
@@ -168,15 +209,28 @@ fn fs_main(vertex: VertexOutput) -> @location(0) vec4<f32> {
 
     var scissor: f32 = scissorMask(vertex.fpos, params);
 
-    if (params.glyph_texture_type != 0.0) {
+    if (shader_type_int == SHADER_TYPE_FillDistanceField) {
+        // The distance field already yields the coverage, only clip it.
+        result *= scissor;
+    } else if (params.glyph_texture_type != 0.0) {
         // Textured tris
         var mask: vec4<f32> = textureSample(glyph_texture, glyph_sampler, vertex.ftcoord);
 
         if (params.glyph_texture_type == 1) {
             mask = vec4<f32>(mask.x);
+        } else if (params.glyph_texture_type == 4 || params.glyph_texture_type == 5) {
+            // Subpixel mask: one coverage per channel (component alpha). The
+            // coverage pass outputs the paint alpha, which the blend state turns
+            // into dst * (1 - alpha * coverage); the color pass adds the color.
+            if (params.glyph_texture_type == 4) {
+                result = vec4<f32>(result.a);
+            }
+            mask = vec4<f32>(mask.xyz, (mask.x + mask.y + mask.z) / 3.0);
         } else {
             result = vec4<f32>(1, 1, 1, 1);
-            mask = vec4<f32>(mask.xyz * mask.w, mask.w);
+            if (params.glyph_texture_type == 2) {
+                mask = vec4<f32>(mask.xyz * mask.w, mask.w);
+            }
         }
 
         mask *= scissor;
@@ -190,6 +244,253 @@ fn fs_main(vertex: VertexOutput) -> @location(0) vec4<f32> {
     return result;
 }
 
+// A premultiplied source texel, transparent black outside the image.
+fn morphologySample(pos: vec2<f32>, params: Params) -> vec4<f32> {
+    let uv = pos / params.extent;
+    var c: vec4<f32> = textureSampleLevel(image_texture, image_sampler, uv, 0.0);
+    if (params.tex_type == 1) {
+        c = vec4<f32>(c.xyz * c.w, c.w);
+    }
+    if (params.tex_type == 2) {
+        c = vec4<f32>(c.x);
+    }
+    if (uv.x < 0.0 || uv.y < 0.0 || uv.x > 1.0 || uv.y > 1.0) {
+        c = vec4<f32>(0.0);
+    }
+    return c;
+}
+
+fn renderMorphology(vertex: VertexOutput, params: Params) -> vec4<f32> {
+    // One pass along image_blur_filter_direction: scissor_mat[0].x is the
+    // radius in pixels (at most 32, enforced on the Rust side),
+    // scissor_mat[0].y is 1 to dilate (maximum) and 0 to erode (minimum).
+    let radius = params.scissor_mat[0].x;
+    let dilate = params.scissor_mat[0].y > 0.5;
+
+    var result: vec4<f32> = morphologySample(vertex.fpos, params);
+    for (var i: f32 = 1.0; i <= 32.0; i += 1.0) {
+        if (i > radius) {
+            break;
+        }
+        let before = morphologySample(vertex.fpos - i * params.image_blur_filter_direction, params);
+        let after = morphologySample(vertex.fpos + i * params.image_blur_filter_direction, params);
+        if (dilate) {
+            result = max(result, max(before, after));
+        } else {
+            result = min(result, min(before, after));
+        }
+    }
+
+    return result;
+}
+
+// A premultiplied source texel, with the image extended beyond its edges by
+// the convolution's edge mode (outer_col.x): 0 duplicates the edge pixels, 1
+// wraps around and 2 reads transparent black.
+fn convolveSample(pos: vec2<f32>, params: Params) -> vec4<f32> {
+    let edge_mode = i32(params.outer_col.x + 0.5);
+    var p = pos;
+    if (edge_mode == 0) {
+        p = clamp(p, vec2<f32>(0.5), params.extent - 0.5);
+    } else if (edge_mode == 1) {
+        p = p - params.extent * floor(p / params.extent);
+    }
+    let uv = p / params.extent;
+    var c: vec4<f32> = textureSampleLevel(image_texture, image_sampler, uv, 0.0);
+    if (params.tex_type == 1) {
+        c = vec4<f32>(c.xyz * c.w, c.w);
+    }
+    if (params.tex_type == 2) {
+        c = vec4<f32>(c.x);
+    }
+    if (uv.x < 0.0 || uv.y < 0.0 || uv.x > 1.0 || uv.y > 1.0) {
+        c = vec4<f32>(0.0);
+    }
+    return c;
+}
+
+fn renderConvolve(vertex: VertexOutput, params: Params) -> vec4<f32> {
+    // The pre-divided kernel fills the scissor and paint matrices and
+    // inner_col.x, inner_col.yzw hold the bias and the kernel's columns and
+    // rows. outer_col.y selects preserveAlpha and outer_col.z is the
+    // texture-space step of one image row down.
+    var kernel = array<vec4<f32>, 7>(
+        params.scissor_mat[0],
+        params.scissor_mat[1],
+        params.scissor_mat[2],
+        params.paint_mat[0],
+        params.paint_mat[1],
+        params.paint_mat[2],
+        params.inner_col,
+    );
+    let bias = params.inner_col.y;
+    let columns = params.inner_col.z;
+    let rows = params.inner_col.w;
+    let preserve_alpha = params.outer_col.y > 0.5;
+    let kernel_target = floor(vec2<f32>(columns, rows) * 0.5);
+
+    var sum = vec4<f32>(0.0);
+    for (var i: i32 = 0; i < 25; i++) {
+        let index = f32(i);
+        if (index >= columns * rows) {
+            break;
+        }
+        let y = floor((index + 0.5) / columns);
+        let x = index - y * columns;
+        // As in SVG, the kernel is applied rotated by 180 degrees.
+        let offset = vec2<f32>(columns - 1.0 - x, rows - 1.0 - y) - kernel_target;
+        var c = convolveSample(vertex.fpos + vec2<f32>(offset.x, offset.y * params.outer_col.z), params);
+        if (preserve_alpha && c.a > 0.0) {
+            c = vec4<f32>(c.rgb / c.a, c.a);
+        }
+        sum += kernel[i / 4][i % 4] * c;
+    }
+
+    let alpha = convolveSample(vertex.fpos, params).a;
+    if (preserve_alpha) {
+        return vec4<f32>(clamp(sum.rgb + bias, vec3<f32>(0.0), vec3<f32>(1.0)) * alpha, alpha);
+    }
+    let result = clamp(sum + bias * alpha, vec4<f32>(0.0), vec4<f32>(1.0));
+    return vec4<f32>(min(result.rgb, vec3<f32>(result.a)), result.a);
+}
+
+// See the GLSL shader: the feTurbulence lattice is bound as the glyph
+// texture, row 0 holds the lattice selector, rows 1 to 4 the gradients of each
+// channel as two 16-bit fixed-point values in [-1, 1].
+fn turbulenceLattice(index: f32) -> f32 {
+    let texel = vec2<i32>(i32(index) % 256, 0);
+    return round(textureLoad(glyph_texture, texel, 0).r * 255.0);
+}
+
+fn turbulenceGradient(channel: i32, index: f32) -> vec2<f32> {
+    let g = round(textureLoad(glyph_texture, vec2<i32>(i32(index), channel + 1), 0) * 255.0);
+    return (g.xz * 256.0 + g.yw) / 65535.0 * 2.0 - 1.0;
+}
+
+fn turbulenceChannel(channel: i32, corners: vec4<f32>, r0: vec2<f32>) -> f32 {
+    let r1 = r0 - 1.0;
+    let s = r0 * r0 * (3.0 - 2.0 * r0);
+    var u = dot(r0, turbulenceGradient(channel, corners.x));
+    var v = dot(vec2<f32>(r1.x, r0.y), turbulenceGradient(channel, corners.y));
+    let a = mix(u, v, s.x);
+    u = dot(vec2<f32>(r0.x, r1.y), turbulenceGradient(channel, corners.z));
+    v = dot(r1, turbulenceGradient(channel, corners.w));
+    let b = mix(u, v, s.x);
+    return mix(a, b, s.y);
+}
+
+fn turbulenceNoise(position: vec2<f32>, stitching: bool, stitch: vec4<f32>) -> vec4<f32> {
+    let t = position + 4096.0;
+    var b0 = floor(t);
+    var b1 = b0 + 1.0;
+    let r0 = t - b0;
+    if (stitching) {
+        if (b0.x >= stitch.z) { b0.x -= stitch.x; }
+        if (b1.x >= stitch.z) { b1.x -= stitch.x; }
+        if (b0.y >= stitch.w) { b0.y -= stitch.y; }
+        if (b1.y >= stitch.w) { b1.y -= stitch.y; }
+    }
+    let i = turbulenceLattice(b0.x);
+    let j = turbulenceLattice(b1.x);
+    let y0 = f32(i32(b0.y) % 256);
+    let y1 = f32(i32(b1.y) % 256);
+    let corners = vec4<f32>(
+        turbulenceLattice(i + y0),
+        turbulenceLattice(j + y0),
+        turbulenceLattice(i + y1),
+        turbulenceLattice(j + y1),
+    );
+    return vec4<f32>(
+        turbulenceChannel(0, corners, r0),
+        turbulenceChannel(1, corners, r0),
+        turbulenceChannel(2, corners, r0),
+        turbulenceChannel(3, corners, r0),
+    );
+}
+
+fn renderTurbulence(vertex: VertexOutput, params: Params) -> vec4<f32> {
+    // See the GLSL shader: scissor_mat[0] holds the base frequency, the octave
+    // count and 1 for fractal noise, scissor_mat[1].x enables stitching and
+    // scissor_mat[1].y is 1 when the target stores its rows bottom-up.
+    // scissor_mat[2] holds the stitching values of the first octave.
+    let fractal_noise = params.scissor_mat[0].w > 0.5;
+    let stitching = params.scissor_mat[1].x > 0.5;
+    var stitch = params.scissor_mat[2];
+    var y = params.extent.y - vertex.fpos.y;
+    if (params.scissor_mat[1].y > 0.5) {
+        y = vertex.fpos.y;
+    }
+    var position = (vec2<f32>(vertex.fpos.x, y) - 0.5) * params.scissor_mat[0].xy;
+
+    var sum = vec4<f32>(0.0);
+    var ratio = 1.0;
+    for (var octave = 0.0; octave < params.scissor_mat[0].z; octave += 1.0) {
+        let noise = turbulenceNoise(position, stitching, stitch);
+        if (fractal_noise) {
+            sum += noise / ratio;
+        } else {
+            sum += abs(noise) / ratio;
+        }
+        position *= 2.0;
+        ratio *= 2.0;
+        stitch = vec4<f32>(stitch.xy * 2.0, stitch.zw * 2.0 - 4096.0);
+    }
+
+    var color = clamp(sum, vec4<f32>(0.0), vec4<f32>(1.0));
+    if (fractal_noise) {
+        color = clamp((sum + 1.0) * 0.5, vec4<f32>(0.0), vec4<f32>(1.0));
+    }
+    return vec4<f32>(color.rgb * color.a, color.a);
+}
+
+fn renderDisplacementMap(vertex: VertexOutput, params: Params) -> vec4<f32> {
+    // See the GLSL shader: scissor_mat[0] holds the scale, the x and y channel
+    // indices and the texture-space step of one image row down. The map is
+    // bound as the glyph texture and read unpremultiplied.
+    let settings = params.scissor_mat[0];
+    var m = textureSample(glyph_texture, glyph_sampler, vertex.fpos / params.extent);
+    if (params.tex_type == 0 && m.a > 0.0) {
+        m = vec4<f32>(m.rgb / m.a, m.a);
+    }
+    if (params.tex_type == 2) {
+        m = vec4<f32>(m.x);
+    }
+    let offset = settings.x * (vec2<f32>(m[i32(settings.y + 0.5)], m[i32(settings.z + 0.5)]) - 0.5);
+
+    let uv = (vertex.fpos + vec2<f32>(offset.x, offset.y * settings.w)) / params.extent;
+    var c = textureSampleLevel(image_texture, image_sampler, uv, 0.0);
+    if (params.tex_type == 1) {
+        c = vec4<f32>(c.xyz * c.w, c.w);
+    }
+    if (params.tex_type == 2) {
+        c = vec4<f32>(c.x);
+    }
+    if (uv.x < 0.0 || uv.y < 0.0 || uv.x > 1.0 || uv.y > 1.0) {
+        c = vec4<f32>(0.0);
+    }
+    return c;
+}
+
+fn renderComponentTransfer(vertex: VertexOutput, params: Params) -> vec4<f32> {
+    // See the GLSL shader: texel x of the lookup table, bound as the glyph
+    // texture, holds each channel remapped from x / 255.
+    var c = textureSample(image_texture, image_sampler, vertex.fpos / params.extent);
+    if (params.tex_type == 0 && c.a > 0.0) {
+        c = vec4<f32>(c.rgb / c.a, c.a);
+    }
+    if (params.tex_type == 2) {
+        c = vec4<f32>(c.x);
+    }
+    let x = vec4<i32>(round(clamp(c, vec4<f32>(0.0), vec4<f32>(1.0)) * 255.0));
+    let result = vec4<f32>(
+        textureLoad(glyph_texture, vec2<i32>(x.r, 0), 0).r,
+        textureLoad(glyph_texture, vec2<i32>(x.g, 0), 0).g,
+        textureLoad(glyph_texture, vec2<i32>(x.b, 0), 0).b,
+        textureLoad(glyph_texture, vec2<i32>(x.a, 0), 0).a,
+    );
+    return vec4<f32>(result.rgb * result.a, result.a);
+}
+
 fn renderColorMatrix(vertex: VertexOutput, params: Params) -> vec4<f32> {
     // The 4x5 color matrix is packed into the scissor/paint matrix slots (dead
     // during a filter pass): scissor_mat columns 0..2 hold the first 12 values,
@@ -213,6 +514,170 @@ fn renderColorMatrix(vertex: VertexOutput, params: Params) -> vec4<f32> {
     return vec4<f32>(outc.rgb * outc.a, outc.a);
 }
 
+// Blend modes of the CSS compositing spec, on unpremultiplied colors: `cb` is
+// the backdrop and `cs` the source.
+fn colorDodge(cb: f32, cs: f32) -> f32 {
+    if (cb <= 0.0) {
+        return 0.0;
+    }
+    if (cs >= 1.0) {
+        return 1.0;
+    }
+    return min(1.0, cb / (1.0 - cs));
+}
+
+fn colorBurn(cb: f32, cs: f32) -> f32 {
+    if (cb >= 1.0) {
+        return 1.0;
+    }
+    if (cs <= 0.0) {
+        return 0.0;
+    }
+    return 1.0 - min(1.0, (1.0 - cb) / cs);
+}
+
+fn hardLight(cb: f32, cs: f32) -> f32 {
+    if (cs <= 0.5) {
+        return cb * 2.0 * cs;
+    }
+    let s = 2.0 * cs - 1.0;
+    return cb + s - cb * s;
+}
+
+fn softLight(cb: f32, cs: f32) -> f32 {
+    if (cs <= 0.5) {
+        return cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb);
+    }
+    var d: f32 = sqrt(cb);
+    if (cb <= 0.25) {
+        d = ((16.0 * cb - 12.0) * cb + 4.0) * cb;
+    }
+    return cb + (2.0 * cs - 1.0) * (d - cb);
+}
+
+fn lum(c: vec3<f32>) -> f32 {
+    return dot(c, vec3<f32>(0.3, 0.59, 0.11));
+}
+
+fn clipColor(color: vec3<f32>) -> vec3<f32> {
+    var c = color;
+    let l = lum(c);
+    let n = min(c.r, min(c.g, c.b));
+    let x = max(c.r, max(c.g, c.b));
+    if (n < 0.0) {
+        c = l + (c - l) * l / (l - n);
+    }
+    if (x > 1.0) {
+        c = l + (c - l) * (1.0 - l) / (x - l);
+    }
+    return c;
+}
+
+fn setLum(c: vec3<f32>, l: f32) -> vec3<f32> {
+    return clipColor(c + (l - lum(c)));
+}
+
+fn sat(c: vec3<f32>) -> f32 {
+    return max(c.r, max(c.g, c.b)) - min(c.r, min(c.g, c.b));
+}
+
+fn setSat(c: vec3<f32>, s: f32) -> vec3<f32> {
+    let n = min(c.r, min(c.g, c.b));
+    let range = max(c.r, max(c.g, c.b)) - n;
+    if (range > 0.0) {
+        return (c - n) * s / range;
+    }
+    return vec3<f32>(0.0);
+}
+
+fn blendColors(mode: i32, cb: vec3<f32>, cs: vec3<f32>) -> vec3<f32> {
+    switch (mode) {
+        case 1: { return cs; }
+        case 2: { return cb * cs; }
+        case 3: { return cb + cs - cb * cs; }
+        case 4: { return vec3<f32>(hardLight(cs.r, cb.r), hardLight(cs.g, cb.g), hardLight(cs.b, cb.b)); }
+        case 5: { return min(cb, cs); }
+        case 6: { return max(cb, cs); }
+        case 7: { return vec3<f32>(colorDodge(cb.r, cs.r), colorDodge(cb.g, cs.g), colorDodge(cb.b, cs.b)); }
+        case 8: { return vec3<f32>(colorBurn(cb.r, cs.r), colorBurn(cb.g, cs.g), colorBurn(cb.b, cs.b)); }
+        case 9: { return vec3<f32>(hardLight(cb.r, cs.r), hardLight(cb.g, cs.g), hardLight(cb.b, cs.b)); }
+        case 10: { return vec3<f32>(softLight(cb.r, cs.r), softLight(cb.g, cs.g), softLight(cb.b, cs.b)); }
+        case 11: { return abs(cb - cs); }
+        case 12: { return cb + cs - 2.0 * cb * cs; }
+        case 13: { return setLum(setSat(cs, sat(cb)), lum(cb)); }
+        case 14: { return setLum(setSat(cb, sat(cs)), lum(cb)); }
+        case 15: { return setLum(cs, lum(cb)); }
+        default: { return setLum(cb, lum(cs)); }
+    }
+}
+
+fn renderBlendFilter(vertex: VertexOutput, params: Params) -> vec4<f32> {
+    // See the GLSL shader: scissor_mat[0].x selects the arithmetic composite
+    // (0) or a blend mode (1 + BlendMode), the arithmetic weights k1..k4
+    // follow. The backdrop is bound as the glyph texture.
+    let uv = vertex.fpos.xy / params.extent;
+    var s: vec4<f32> = textureSample(image_texture, image_sampler, uv);
+    var b: vec4<f32> = textureSample(glyph_texture, glyph_sampler, uv);
+    if (params.tex_type == 1) {
+        s = vec4<f32>(s.xyz * s.w, s.w);
+        b = vec4<f32>(b.xyz * b.w, b.w);
+    }
+    if (params.tex_type == 2) {
+        s = vec4<f32>(s.x);
+        b = vec4<f32>(b.x);
+    }
+
+    let mode = i32(params.scissor_mat[0].x + 0.5);
+    if (mode == 0) {
+        let k = vec4<f32>(params.scissor_mat[0].yzw, params.scissor_mat[1].x);
+        let result = clamp(k.x * s * b + k.y * s + k.z * b + k.w, vec4<f32>(0.0), vec4<f32>(1.0));
+        return vec4<f32>(min(result.rgb, vec3<f32>(result.a)), result.a);
+    }
+
+    var cs = vec3<f32>(0.0);
+    if (s.a > 0.0) {
+        cs = s.rgb / s.a;
+    }
+    var cb = vec3<f32>(0.0);
+    if (b.a > 0.0) {
+        cb = b.rgb / b.a;
+    }
+    let mixed = clamp(blendColors(mode, cb, cs), vec3<f32>(0.0), vec3<f32>(1.0));
+    return vec4<f32>((1.0 - b.a) * s.rgb + (1.0 - s.a) * b.rgb + s.a * b.a * mixed, s.a + b.a - s.a * b.a);
+}
+
+fn median(r: f32, g: f32, b: f32) -> f32 {
+    return max(min(r, g), min(max(r, g), b));
+}
+
+fn renderDistanceField(vertex: VertexOutput, params: Params) -> vec4<f32> {
+    // See the GLSL shader: RGB hold a multi-channel distance field (median =
+    // distance with sharp corners), alpha the true distance used for the glow.
+    let field = textureSample(glyph_texture, glyph_sampler, vertex.ftcoord);
+    let px_range = params.sdf_params.x;
+    let stroke_half_width = params.sdf_params.y;
+    let outline_width = params.sdf_params.z;
+    let glow_radius = params.sdf_params.w;
+
+    var dist = (median(field.r, field.g, field.b) - 0.5) * px_range;
+    var true_dist = (field.a - 0.5) * px_range;
+
+    if (stroke_half_width > 0.0) {
+        dist = stroke_half_width - abs(dist);
+        true_dist = stroke_half_width - abs(true_dist);
+    }
+
+    var color = params.inner_col * clamp(dist + 0.5, 0.0, 1.0);
+    if (outline_width > 0.0) {
+        color += params.outer_col * clamp(dist + outline_width + 0.5, 0.0, 1.0) * (1.0 - color.a);
+    }
+    if (glow_radius > 0.0) {
+        let glow = 1.0 - smoothstep(0.0, glow_radius, -(true_dist + outline_width));
+        color += params.sdf_glow_col * glow * (1.0 - color.a);
+    }
+    return color;
+}
+
 fn conicAngleFraction(vertex: VertexOutput, params: Params) -> f32 {
     let pt: vec2<f32> = (params.paint_mat * vec3<f32>(vertex.fpos, 1.0)).xy;
     // Measure the angle clockwise from the positive x axis. In the gradient's
@@ -269,6 +734,49 @@ fn renderGradient(vertex: VertexOutput, params: Params) -> vec4<f32> {
     return ditherGradient(mix(params.inner_col,params.outer_col,d), vertex.position.xy);
 }
 
+// Abramowitz and Stegun 7.1.27, within 5e-4 of the error function.
+fn erfApprox(v: vec2<f32>) -> vec2<f32> {
+    let s = sign(v);
+    let a = abs(v);
+    var x = 1.0 + (0.278393 + (0.230389 + 0.078108 * (a * a)) * a) * a;
+    x *= x;
+    return s - s / (x * x);
+}
+
+// The row at height y of a rounded rectangle blurred along x only, which the
+// error function gives exactly.
+fn boxShadowRow(x: f32, y: f32, sigma: f32, corner: f32, halfSize: vec2<f32>) -> f32 {
+    let delta = min(halfSize.y - corner - abs(y), 0.0);
+    let curved = halfSize.x - corner + sqrt(max(0.0, corner * corner - delta * delta));
+    let integral = 0.5 + 0.5 * erfApprox((x + vec2<f32>(-curved, curved)) * (sqrt(0.5) / sigma));
+    return integral.y - integral.x;
+}
+
+// Box shadow: the rounded rectangle (extent, radius) blurred with a Gaussian
+// of standard deviation feather, in paint space. The rows are blurred
+// exactly and summed over 8 samples of the Gaussian along y (E. Wallace's
+// closed form). Inner color where covered, outer color elsewhere.
+fn renderBoxShadow(vertex: VertexOutput, params: Params) -> vec4<f32> {
+    let pt: vec2<f32> = (params.paint_mat * vec3<f32>(vertex.fpos, 1.0)).xy;
+    let sigma = params.feather;
+    let corner = min(params.radius, min(params.extent.x, params.extent.y));
+
+    // The Gaussian is only non-zero within 3 sigma, and the rows only
+    // within the rectangle.
+    let start = clamp(-3.0 * sigma, pt.y - params.extent.y, pt.y + params.extent.y);
+    let end = clamp(3.0 * sigma, pt.y - params.extent.y, pt.y + params.extent.y);
+    let dy = (end - start) / 8.0;
+    var y = start + dy * 0.5;
+    var coverage = 0.0;
+    for (var i = 0; i < 8; i++) {
+        let weight = exp(-(y * y) / (2.0 * sigma * sigma)) / (sqrt(TAU) * sigma);
+        coverage += boxShadowRow(pt.x, pt.y - y, sigma, corner, params.extent) * weight * dy;
+        y += dy;
+    }
+
+    return mix(params.outer_col, params.inner_col, clamp(coverage, 0.0, 1.0));
+}
+
 // Image-based Gradient; sample a texture using the gradient position.
 fn renderImageGradient(vertex: VertexOutput, params: Params) -> vec4<f32> {
     // Calculate gradient color using box gradient
@@ -0,0 +1,205 @@
+use std::hash::Hasher;
+
+use fnv::{FnvHashMap, FnvHasher};
+
+use crate::{image::ImageStore, ImageId, Renderer};
+
+/// Default budget for the blurred shadow images kept across frames.
+pub const DEFAULT_SHADOW_CACHE_LIMIT: usize = 32 * 1024 * 1024;
+
+/// `ShadowCache` keeps the blurred images of drop shadows across frames, so
+/// that a shape drawn with the same shadow frame after frame is only rendered
+/// and blurred once. The images are contained by the Canvas's `ImageStore`.
+///
+/// Entries are keyed by everything that goes into the image: the path,
+/// transform, paint, shadow blur and shadow color. Once the images take
+/// more than the limit, the least recently used ones are released at the end
+/// of the frame, when no draw command refers to them anymore.
+#[derive(Debug)]
+pub struct ShadowCache {
+    // Keyed by the hash of the `ShadowKey` stored in the entry.
+    entries: FnvHashMap<u64, ShadowEntry>,
+    limit: usize,
+    size: usize,
+    clock: u64,
+}
+
+/// The full key of a shadow: the bytes of everything hashed into it. It is
+/// stored with the entry and compared on lookup, so shapes whose keys merely
+/// hash the same never share an image.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShadowKey(Vec<u8>);
+
+impl Hasher for ShadowKey {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let mut hasher = FnvHasher::default();
+        hasher.write(&self.0);
+        hasher.finish()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ShadowEntry {
+    key: ShadowKey,
+    image: ImageId,
+    origin: (f32, f32),
+    size: (usize, usize),
+    last_used: u64,
+}
+
+impl ShadowEntry {
+    fn bytes(&self) -> usize {
+        self.size.0 * self.size.1 * 4
+    }
+}
+
+impl ShadowCache {
+    /// Create a new empty shadow cache
+    pub fn new() -> Self {
+        Self {
+            entries: FnvHashMap::default(),
+            limit: DEFAULT_SHADOW_CACHE_LIMIT,
+            size: 0,
+            clock: 0,
+        }
+    }
+
+    /// Returns whether shadows should be cached at all.
+    pub fn is_enabled(&self) -> bool {
+        self.limit > 0
+    }
+
+    /// Lookup the image of a shadow whose offscreen area has the given
+    /// device-space origin and size.
+    pub fn lookup(&mut self, key: &ShadowKey, origin: (f32, f32), size: (usize, usize)) -> Option<ImageId> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(&key.finish())?;
+        if entry.key != *key || entry.origin != origin || entry.size != size {
+            return None;
+        }
+        entry.last_used = self.clock;
+        Some(entry.image)
+    }
+
+    /// Add the image of a shadow. Returns the image of an entry it replaces,
+    /// which may still be in use this frame and must be released with the
+    /// transient images.
+    pub fn insert(
+        &mut self,
+        key: ShadowKey,
+        origin: (f32, f32),
+        size: (usize, usize),
+        image: ImageId,
+    ) -> Option<ImageId> {
+        self.clock += 1;
+        let hash = key.finish();
+        let entry = ShadowEntry {
+            key,
+            image,
+            origin,
+            size,
+            last_used: self.clock,
+        };
+        self.size += entry.bytes();
+        let replaced = self.entries.insert(hash, entry)?;
+        self.size -= replaced.bytes();
+        Some(replaced.image)
+    }
+
+    /// Set the budget in bytes. Images over it are released by the next `evict`.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+    }
+
+    /// Release the least recently used images until the cache fits its
+    /// budget. This method should be called when all the commands have been
+    /// submitted.
+    pub fn evict<R: Renderer>(&mut self, images: &mut ImageStore<R::Image>, renderer: &mut R) {
+        if self.size <= self.limit {
+            return;
+        }
+
+        let mut by_age: Vec<(u64, u64)> = self
+            .entries
+            .iter()
+            .map(|(key, entry)| (entry.last_used, *key))
+            .collect();
+        by_age.sort_unstable();
+
+        for (_, key) in by_age {
+            if self.size <= self.limit {
+                break;
+            }
+            if let Some(entry) = self.entries.remove(&key) {
+                self.size -= entry.bytes();
+                images.remove(renderer, entry.image);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ImageFlags, ImageInfo, PixelFormat, RecordingRenderer};
+
+    fn key(id: u8) -> ShadowKey {
+        let mut key = ShadowKey::default();
+        key.write_u8(id);
+        key
+    }
+
+    #[test]
+    fn least_recently_used_shadows_are_evicted() {
+        let mut renderer = RecordingRenderer::default();
+        let mut images = ImageStore::new();
+        let mut cache = ShadowCache::new();
+
+        let mut add = |cache: &mut ShadowCache, id: u8| {
+            let info = ImageInfo::new(ImageFlags::empty(), 16, 16, PixelFormat::Rgba8);
+            let image = images.alloc(&mut renderer, info).unwrap();
+            assert!(cache.insert(key(id), (0.0, 0.0), (16, 16), image).is_none());
+            image
+        };
+        let first = add(&mut cache, 1);
+        let second = add(&mut cache, 2);
+        let third = add(&mut cache, 3);
+
+        // A different area under the same key is a miss.
+        assert_eq!(cache.lookup(&key(1), (0.0, 0.0), (16, 16)), Some(first));
+        assert_eq!(cache.lookup(&key(2), (1.0, 0.0), (16, 16)), None);
+
+        // Room for two: the second shadow was used least recently.
+        cache.set_limit(2 * 16 * 16 * 4);
+        cache.evict(&mut images, &mut renderer);
+        assert_eq!(cache.lookup(&key(2), (0.0, 0.0), (16, 16)), None);
+        assert!(images.info(second).is_none());
+        assert_eq!(cache.lookup(&key(1), (0.0, 0.0), (16, 16)), Some(first));
+        assert_eq!(cache.lookup(&key(3), (0.0, 0.0), (16, 16)), Some(third));
+
+        cache.set_limit(0);
+        cache.evict(&mut images, &mut renderer);
+        assert!(!cache.is_enabled());
+        assert!(images.info(first).is_none() && images.info(third).is_none());
+    }
+
+    #[test]
+    fn colliding_shadow_keys_are_told_apart() {
+        let mut renderer = RecordingRenderer::default();
+        let mut images = ImageStore::new();
+        let mut cache = ShadowCache::new();
+
+        let info = ImageInfo::new(ImageFlags::empty(), 16, 16, PixelFormat::Rgba8);
+        let image = images.alloc(&mut renderer, info).unwrap();
+        cache.insert(key(1), (0.0, 0.0), (16, 16), image);
+
+        // File the entry under the hash of another key, as if they collided.
+        let entry = cache.entries.remove(&key(1).finish()).unwrap();
+        cache.entries.insert(key(2).finish(), entry);
+        assert_eq!(cache.lookup(&key(2), (0.0, 0.0), (16, 16)), None);
+    }
+}
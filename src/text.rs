@@ -1,4 +1,12 @@
-use std::{borrow::Borrow, cell::RefCell, ffi::OsStr, fs, hash::Hash, path::Path as FilePath, rc::Rc};
+use std::{
+    borrow::Borrow,
+    cell::{Cell, RefCell},
+    ffi::OsStr,
+    fs,
+    hash::Hash,
+    path::Path as FilePath,
+    rc::Rc,
+};
 
 use fnv::FnvHashMap;
 #[cfg(feature = "textlayout")]
@@ -7,8 +15,8 @@ use slotmap::{DefaultKey, SlotMap};
 
 use crate::{
     paint::{FontVariations, PaintFlavor, StrokeSettings},
-    Canvas, Color, ErrorKind, FillRule, ImageFlags, ImageId, ImageInfo, Paint, PixelFormat, PositionedGlyph,
-    RenderTarget, Renderer,
+    Canvas, Color, ErrorKind, FillRule, ImageFlags, ImageId, ImageInfo, Paint, Path, PixelFormat, PositionedGlyph,
+    RenderTarget, Renderer, Transform2D,
 };
 
 mod atlas;
@@ -16,7 +24,14 @@ pub use atlas::Atlas;
 
 mod font;
 use font::{Font, GlyphRendering};
-pub use font::{FontMetrics, VariationAxisInfo};
+pub use font::{FontInfo, FontMetrics, FontStyle, NamedInstance, VariationAxisInfo};
+
+mod matching;
+
+#[cfg(feature = "textlayout")]
+mod colr;
+
+pub(crate) mod distance_field;
 
 #[cfg(feature = "textlayout")]
 mod textlayout;
@@ -33,6 +48,8 @@ const GLYPH_PADDING: u32 = 1;
 const GLYPH_MARGIN: u32 = 1;
 
 const TEXTURE_SIZE: usize = 512;
+// Memory taken by one atlas page (RGBA8).
+pub(crate) const PAGE_BYTES: usize = TEXTURE_SIZE * TEXTURE_SIZE * 4;
 #[cfg(feature = "textlayout")]
 const DEFAULT_LRU_CACHE_CAPACITY: usize = 1000;
 
@@ -55,6 +72,12 @@ pub enum Baseline {
     Alphabetic,
     /// The text baseline is the bottom of the bounding box.
     Bottom,
+    /// The text baseline is the hanging baseline, used by scripts such as
+    /// Devanagari and Tibetan.
+    Hanging,
+    /// The text baseline is the ideographic baseline, the bottom of the
+    /// ideographic em box used by CJK scripts.
+    Ideographic,
 }
 
 /// Represents the horizontal alignment of text.
@@ -70,6 +93,107 @@ pub enum Align {
     Center,
     /// The text is right-aligned.
     Right,
+    /// The text is aligned to the start of its line: left in left-to-right
+    /// text and right in right-to-left text.
+    Start,
+    /// The text is aligned to the end of its line: right in left-to-right
+    /// text and left in right-to-left text.
+    End,
+    /// The text is stretched to the width of its line, as selected by the
+    /// paint's [`TextJustify`], when drawn by
+    /// [`Canvas::fill_text_wrapped`](crate::Canvas::fill_text_wrapped).
+    /// The last line, and text drawn any other way, is aligned like `Start`.
+    Justify,
+}
+
+/// Selects where [`Align::Justify`] puts the extra space of a line, like the
+/// CSS `text-justify` property.
+///
+/// The default value is `Auto`.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TextJustify {
+    /// Between words, or between characters on lines without word gaps, as
+    /// in CJK text.
+    #[default]
+    Auto,
+    /// Between words only; lines without word gaps are not stretched.
+    InterWord,
+    /// Between all characters.
+    InterCharacter,
+}
+
+/// Selects the side of a path that text drawn along it runs on, like the SVG
+/// `side` attribute of `<textPath>`.
+///
+/// The default value is `Left`.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TextPathSide {
+    /// The text follows the path in the direction it was drawn, with the
+    /// glyphs standing on the left of the path.
+    #[default]
+    Left,
+    /// The text follows the path from its end back to its start, which puts
+    /// it on the other side of the path.
+    Right,
+}
+
+/// Selects the direction lines of text are laid out in, like the CSS
+/// `writing-mode` property.
+///
+/// In the vertical modes, glyphs advance down the column. CJK characters
+/// stand upright, using the fonts' vertical alternates (`vert`/`vrt2`) and
+/// vertical advances, while other characters are set sideways, following
+/// UAX #50. The text's `x` is the center line of the column.
+///
+/// The default value is `HorizontalTb`.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum WritingMode {
+    /// Lines run horizontally and stack from top to bottom.
+    #[default]
+    HorizontalTb,
+    /// Lines run vertically and stack from right to left.
+    VerticalRl,
+    /// Lines run vertically and stack from left to right.
+    VerticalLr,
+}
+
+impl WritingMode {
+    /// Returns whether lines run vertically.
+    pub fn is_vertical(self) -> bool {
+        self != Self::HorizontalTb
+    }
+}
+
+/// Represents how glyph edges are antialiased.
+///
+/// The default value is `Grayscale`.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TextAntialiasing {
+    /// Each pixel is covered by a single coverage value.
+    #[default]
+    Grayscale,
+    /// Coverage is computed per color channel for displays whose subpixels
+    /// are laid out red, green, blue from left to right.
+    SubpixelRgb,
+    /// Coverage is computed per color channel for displays whose subpixels
+    /// are laid out blue, green, red from left to right.
+    SubpixelBgr,
+}
+
+impl TextAntialiasing {
+    /// Horizontal offsets of the red, green and blue subpixel centers from the
+    /// pixel center, in pixels, or `None` for grayscale antialiasing.
+    pub(crate) fn subpixel_offsets(self) -> Option<[f32; 3]> {
+        match self {
+            Self::Grayscale => None,
+            Self::SubpixelRgb => Some([-1.0 / 3.0, 0.0, 1.0 / 3.0]),
+            Self::SubpixelBgr => Some([1.0 / 3.0, 0.0, -1.0 / 3.0]),
+        }
+    }
 }
 
 /// Represents the rendering mode for a path.
@@ -84,6 +208,103 @@ pub enum RenderMode {
     Stroke,
 }
 
+// Palette and foreground color that `COLR` glyphs are painted with. Only part
+// of the cache key of those glyphs, so that other glyphs are shared between
+// paints.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub(crate) struct ColorGlyphStyle {
+    pub(crate) palette: u16,
+    pub(crate) foreground: [u8; 4],
+}
+
+// Faux bold and oblique drawn for fonts without the requested weight or
+// slant. Part of the cache key of rendered glyphs, so that synthesized glyphs
+// don't stand in for real ones.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub(crate) struct GlyphSynthesis {
+    pub(crate) bold: bool,
+    pub(crate) oblique: bool,
+}
+
+impl GlyphSynthesis {
+    // tan(14°), the default angle of CSS `font-style: oblique`.
+    const OBLIQUE_SKEW: f32 = 0.249_328;
+
+    pub(crate) fn is_none(self) -> bool {
+        !self.bold && !self.oblique
+    }
+
+    // The width outlines are stroked with to embolden them, in font units:
+    // 1/24 of the em, like FreeType's `FT_GlyphSlot_Embolden`.
+    pub(crate) fn embolden_width(self, units_per_em: f32) -> f32 {
+        if self.bold {
+            units_per_em / 24.0
+        } else {
+            0.0
+        }
+    }
+
+    // The shear applied to outlines in font units, y pointing up.
+    pub(crate) fn transform(self) -> Transform2D {
+        let skew = if self.oblique { Self::OBLIQUE_SKEW } else { 0.0 };
+        Transform2D::new(1.0, 0.0, skew, 1.0, 0.0, 0.0)
+    }
+
+    // Grows glyph metrics in font units to cover the synthesized outline.
+    fn grow_metrics(self, metrics: &mut font::GlyphMetrics, units_per_em: f32) {
+        let half = self.embolden_width(units_per_em) / 2.0;
+        metrics.bearing_x -= half;
+        metrics.bearing_y += half;
+        metrics.width += 2.0 * half;
+        metrics.height += 2.0 * half;
+        if self.oblique {
+            let bottom = metrics.bearing_y - metrics.height;
+            metrics.bearing_x += bottom.min(0.0) * Self::OBLIQUE_SKEW;
+            metrics.width += metrics.height * Self::OBLIQUE_SKEW;
+        }
+    }
+
+    // Fills or strokes `path`, given in font units, with the synthesized
+    // styles applied.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_outline<T: Renderer>(
+        self,
+        canvas: &mut Canvas<T>,
+        path: &Path,
+        units_per_em: f32,
+        paint_flavor: &PaintFlavor,
+        anti_alias: bool,
+        mode: RenderMode,
+        stroke: &StrokeSettings,
+    ) {
+        canvas.save();
+        if self.oblique {
+            canvas.set_transform(&self.transform());
+        }
+
+        let embolden_width = self.embolden_width(units_per_em);
+        if mode == RenderMode::Stroke {
+            let stroke = StrokeSettings {
+                line_width: stroke.line_width + embolden_width,
+                ..stroke.clone()
+            };
+            canvas.stroke_path_internal(path, paint_flavor, anti_alias, &stroke);
+        } else if embolden_width > 0.0 {
+            // Stroking the outline as well dilates it by half the width on
+            // either side, in the same coverage pass as the fill.
+            let pen = StrokeSettings {
+                line_width: embolden_width,
+                ..stroke.clone()
+            };
+            canvas.fill_path_grown(path, paint_flavor, anti_alias, FillRule::NonZero, Some(&pen));
+        } else {
+            canvas.fill_path_internal(path, paint_flavor, anti_alias, FillRule::NonZero);
+        }
+
+        canvas.restore();
+    }
+}
+
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 pub struct RenderedGlyphId {
     glyph_index: u16,
@@ -92,10 +313,14 @@ pub struct RenderedGlyphId {
     line_width: u32,
     render_mode: RenderMode,
     subpixel_location: u8,
+    antialiasing: TextAntialiasing,
+    color_style: ColorGlyphStyle,
+    synthesis: GlyphSynthesis,
     variation_hash: u64,
 }
 
 impl RenderedGlyphId {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         glyph_index: u16,
         font_id: FontId,
@@ -103,6 +328,9 @@ impl RenderedGlyphId {
         line_width: f32,
         mode: RenderMode,
         subpixel_location: u8,
+        antialiasing: TextAntialiasing,
+        color_style: ColorGlyphStyle,
+        synthesis: GlyphSynthesis,
         normalized_coords: &[i16],
     ) -> Self {
         use std::hash::Hasher;
@@ -115,6 +343,9 @@ impl RenderedGlyphId {
             line_width: (line_width * 10.0).trunc() as u32,
             render_mode: mode,
             subpixel_location,
+            antialiasing,
+            color_style,
+            synthesis,
             variation_hash: hasher.finish(),
         }
     }
@@ -130,12 +361,17 @@ pub struct RenderedGlyph {
     atlas_x: u32,
     atlas_y: u32,
     color_glyph: bool,
+    subpixel_mask: bool,
 }
 
 #[derive(Debug)]
 pub struct FontTexture {
     pub atlas: Atlas,
     pub(crate) image_id: ImageId,
+    // Frame in which a glyph of this page was last drawn or rasterized.
+    last_used: u64,
+    // Area taken by the glyphs packed into this page, in pixels.
+    used_area: usize,
 }
 
 /// `TextContext` provides functionality for text processing in femtovg.
@@ -211,6 +447,36 @@ impl TextContext {
         let font = ctx.font(font_id).ok_or(ErrorKind::NoFontFound)?;
         Ok(font.variation_axes())
     }
+
+    /// Returns the family, subfamily and PostScript names of the specified font,
+    /// along with the named instances of variable fonts.
+    pub fn font_info(&self, font_id: FontId) -> Result<FontInfo, ErrorKind> {
+        let ctx = RefCell::borrow(&self.0);
+        let font = ctx.font(font_id).ok_or(ErrorKind::NoFontFound)?;
+        Ok(font.info())
+    }
+
+    /// Selects the registered font that best matches a family name, weight,
+    /// style and stretch, following the
+    /// [CSS font matching algorithm](https://www.w3.org/TR/css-fonts-4/#font-style-matching).
+    ///
+    /// `family` is compared case-insensitively against the family names in each
+    /// font's `name` table. Among the fonts of that family, the closest width
+    /// is chosen first, then the closest style and finally the closest weight,
+    /// each searched in the direction CSS prescribes (for example, a request for
+    /// weight 500 prefers 400 over 600). `weight` uses the OpenType scale (400 is
+    /// regular, 700 bold, see [`Paint::FONT_WEIGHT_BOLD`](crate::Paint::FONT_WEIGHT_BOLD)
+    /// and friends) and `stretch` is a percentage of the normal width (100.0).
+    ///
+    /// Variable fonts match every weight and width inside their `wght` and
+    /// `wdth` axis ranges, and italic or oblique through their `ital` and `slnt`
+    /// axes. The returned font is not instanced: set the requested weight and
+    /// style on the [`Paint`] as well to select them from a variable font.
+    ///
+    /// Returns [`ErrorKind::NoFontFound`] if no registered font belongs to `family`.
+    pub fn query_font(&self, family: &str, weight: f32, style: FontStyle, stretch: f32) -> Result<FontId, ErrorKind> {
+        RefCell::borrow(&self.0).query_font(family, weight, style, stretch)
+    }
 }
 
 pub struct TextContextImpl {
@@ -334,6 +600,24 @@ impl TextContextImpl {
         self.fonts.get_mut(id.0)
     }
 
+    pub fn query_font(&self, family: &str, weight: f32, style: FontStyle, stretch: f32) -> Result<FontId, ErrorKind> {
+        let candidates: Vec<(DefaultKey, &Font)> = self
+            .fonts
+            .iter()
+            .filter(|(_, font)| font.matches_family(family))
+            .collect();
+
+        let best = matching::best_match(
+            candidates.iter().map(|(_, font)| font.face_traits()),
+            weight,
+            style,
+            stretch,
+        )
+        .ok_or(ErrorKind::NoFontFound)?;
+
+        Ok(FontId(candidates[best].0))
+    }
+
     #[cfg(feature = "textlayout")]
     pub fn find_font<F, T>(&mut self, font_ids: &[Option<FontId>; 8], mut callback: F) -> Result<T, ErrorKind>
     where
@@ -425,18 +709,77 @@ pub struct Quad {
     pub t1: f32,
 }
 
-/// Represents the drawing commands for glyphs, separated into alpha and color glyphs.
+/// Represents the drawing commands for glyphs, separated into alpha, color and subpixel glyphs.
 #[derive(Default, Debug)]
 pub struct GlyphDrawCommands {
     /// Drawing commands for alpha (opacity) glyphs.
     pub alpha_glyphs: Vec<DrawCommand>,
     /// Drawing commands for color glyphs.
     pub color_glyphs: Vec<DrawCommand>,
+    /// Drawing commands for glyphs whose texture holds a separate coverage in
+    /// each of its red, green and blue channels (subpixel antialiasing).
+    pub subpixel_glyphs: Vec<DrawCommand>,
+}
+
+/// Memory usage and eviction counters of the glyph atlases.
+///
+/// Returned by [`Canvas::glyph_atlas_stats`](crate::Canvas::glyph_atlas_stats).
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct GlyphAtlasStats {
+    /// Number of atlas textures (pages) currently allocated.
+    pub pages: usize,
+    /// Memory taken by the atlas textures, in bytes.
+    pub texture_bytes: usize,
+    /// Number of glyphs currently cached in the atlases.
+    pub glyphs: usize,
+    /// Fraction of the allocated page area occupied by glyphs, between 0 and 1.
+    pub fill_ratio: f32,
+    /// Number of cached glyphs evicted so far to stay within the budget.
+    pub evicted_glyphs: u64,
+    /// Number of pages cleared and reused for new glyphs so far.
+    pub recycled_pages: u64,
+}
+
+impl GlyphAtlasStats {
+    pub(crate) fn merge(self, other: Self) -> Self {
+        let area = |stats: &Self| stats.fill_ratio * stats.pages as f32;
+        let pages = self.pages + other.pages;
+        Self {
+            pages,
+            texture_bytes: self.texture_bytes + other.texture_bytes,
+            glyphs: self.glyphs + other.glyphs,
+            fill_ratio: if pages == 0 {
+                0.0
+            } else {
+                (area(&self) + area(&other)) / pages as f32
+            },
+            evicted_glyphs: self.evicted_glyphs + other.evicted_glyphs,
+            recycled_pages: self.recycled_pages + other.recycled_pages,
+        }
+    }
+}
+
+// Color glyph pixels are stored premultiplied in the atlas.
+#[cfg(any(feature = "image-loading", feature = "swash"))]
+fn premultiplied(r: u8, g: u8, b: u8, a: u8) -> rgb::RGBA8 {
+    let scale = |c: u8| ((c as u16 * a as u16 + 127) / 255) as u8;
+    rgb::RGBA8::new(scale(r), scale(g), scale(b), a)
 }
 
 pub struct GlyphAtlas {
     pub rendered_glyphs: RefCell<FnvHashMap<RenderedGlyphId, Option<RenderedGlyph>>>,
     pub glyph_textures: RefCell<Vec<FontTexture>>,
+    // Flags of the atlas textures: coverage masks are sampled pixel-exact,
+    // distance fields need bilinear filtering. Color glyphs are stored
+    // premultiplied, as that is what drawing them into the atlas produces.
+    image_flags: ImageFlags,
+    // Maximum number of pages, or None for no limit. See `find_texture_or_alloc`.
+    max_pages: Cell<Option<usize>>,
+    // Incremented on every flush; pages used in the current frame may still be
+    // sampled by pending draw commands and must not be recycled.
+    frame: Cell<u64>,
+    evicted_glyphs: Cell<u64>,
+    recycled_pages: Cell<u64>,
     #[cfg(feature = "swash")]
     swash_scale_context: Rc<RefCell<swash::scale::ScaleContext>>,
 }
@@ -446,10 +789,23 @@ impl GlyphAtlas {
         Self {
             rendered_glyphs: RefCell::default(),
             glyph_textures: RefCell::default(),
+            image_flags: ImageFlags::NEAREST | ImageFlags::PREMULTIPLIED,
+            max_pages: Cell::new(None),
+            frame: Cell::new(0),
+            evicted_glyphs: Cell::new(0),
+            recycled_pages: Cell::new(0),
             #[cfg(feature = "swash")]
             swash_scale_context: (**_text_context).borrow().swash_scale_context(),
         }
     }
+
+    /// Creates an atlas for distance-field glyphs, see [`Self::render_distance_field_atlas`].
+    pub(crate) fn new_distance_field(text_context: &Rc<RefCell<TextContextImpl>>) -> Self {
+        Self {
+            image_flags: ImageFlags::empty(),
+            ..Self::new(text_context)
+        }
+    }
 }
 
 impl std::fmt::Debug for GlyphAtlas {
@@ -473,10 +829,14 @@ impl GlyphAtlas {
         font_size: f32,
         line_width: f32,
         mode: RenderMode,
+        antialiasing: TextAntialiasing,
+        color_style: ColorGlyphStyle,
+        synthesis: GlyphSynthesis,
         normalized_coords: &[i16],
     ) -> Result<GlyphDrawCommands, ErrorKind> {
         let mut alpha_cmd_map = FnvHashMap::default();
         let mut color_cmd_map = FnvHashMap::default();
+        let mut subpixel_cmd_map = FnvHashMap::default();
 
         let line_width_offset = if mode == RenderMode::Stroke {
             (line_width / 2.0).ceil()
@@ -489,6 +849,12 @@ impl GlyphAtlas {
         for glyph in glyphs {
             let subpixel_location = crate::geometry::quantize(glyph.x.fract(), 0.1) * 10.0;
 
+            let color_style = if font.is_color_layer_glyph(font_face, glyph.glyph_id) {
+                color_style
+            } else {
+                ColorGlyphStyle::default()
+            };
+
             let id = RenderedGlyphId::new(
                 glyph.glyph_id,
                 font_id,
@@ -496,34 +862,43 @@ impl GlyphAtlas {
                 line_width,
                 mode,
                 subpixel_location as u8,
+                antialiasing,
+                color_style,
+                synthesis,
                 normalized_coords,
             );
 
-            let mut rendered_glyphs = self.rendered_glyphs.borrow_mut();
-            let glyph_cache_entry = rendered_glyphs.entry(id);
-            let glyph_cache_entry = match glyph_cache_entry {
-                std::collections::hash_map::Entry::Occupied(occupied_entry) => occupied_entry,
-                std::collections::hash_map::Entry::Vacant(_) => {
-                    let result = self.render_glyph(
+            // Rendering may recycle a page and evict cache entries, so the
+            // cache must not stay borrowed while the glyph is rasterized.
+            let cached = self.rendered_glyphs.borrow().get(&id).copied();
+            let rendered = match cached {
+                Some(rendered) => rendered,
+                None => {
+                    let rendered = self.render_glyph(
                         canvas,
                         font_size,
                         line_width,
                         mode,
+                        antialiasing,
+                        color_style,
+                        synthesis,
                         font,
                         font_face,
                         glyph.glyph_id,
                         subpixel_location / 10.0,
                         normalized_coords,
                     )?;
-                    glyph_cache_entry.insert_entry(result)
+                    self.rendered_glyphs.borrow_mut().insert(id, rendered);
+                    rendered
                 }
             };
 
-            let Some(rendered) = glyph_cache_entry.get() else {
+            let Some(rendered) = rendered else {
                 continue;
             };
 
-            if let Some(texture) = self.glyph_textures.borrow().get(rendered.texture_index) {
+            if let Some(texture) = self.glyph_textures.borrow_mut().get_mut(rendered.texture_index) {
+                texture.last_used = self.frame.get();
                 let image_id = texture.image_id;
                 let size = texture.atlas.size();
                 let itw = 1.0 / size.0 as f32;
@@ -531,6 +906,8 @@ impl GlyphAtlas {
 
                 let cmd_map = if rendered.color_glyph {
                     &mut color_cmd_map
+                } else if rendered.subpixel_mask {
+                    &mut subpixel_cmd_map
                 } else {
                     &mut alpha_cmd_map
                 };
@@ -563,6 +940,7 @@ impl GlyphAtlas {
         Ok(GlyphDrawCommands {
             alpha_glyphs: alpha_cmd_map.drain().map(|(_, cmd)| cmd).collect(),
             color_glyphs: color_cmd_map.drain().map(|(_, cmd)| cmd).collect(),
+            subpixel_glyphs: subpixel_cmd_map.drain().map(|(_, cmd)| cmd).collect(),
         })
     }
 
@@ -575,17 +953,28 @@ impl GlyphAtlas {
         font_size: f32,
         line_width: f32,
         mode: RenderMode,
+        antialiasing: TextAntialiasing,
+        color_style: ColorGlyphStyle,
+        synthesis: GlyphSynthesis,
         font: &Font,
         font_face: &font::FontFaceRef<'_>,
         glyph_id: u16,
         _subpixel_x: f32,
         normalized_coords: &[i16],
     ) -> Result<Option<RenderedGlyph>, ErrorKind> {
+        // swash paints COLR glyphs with the default palette only, and knows
+        // nothing of synthesized styles.
         #[cfg(feature = "swash")]
-        if mode == RenderMode::Fill {
-            if let Some(result) =
-                self.render_glyph_swash(canvas, font, font_size, glyph_id, _subpixel_x, normalized_coords)?
-            {
+        if mode == RenderMode::Fill && synthesis.is_none() && !font.is_color_layer_glyph(font_face, glyph_id) {
+            if let Some(result) = self.render_glyph_swash(
+                canvas,
+                font,
+                font_size,
+                antialiasing,
+                glyph_id,
+                _subpixel_x,
+                normalized_coords,
+            )? {
                 return Ok(Some(result));
             }
         }
@@ -598,20 +987,36 @@ impl GlyphAtlas {
                 .glyph(font_face, glyph_id, normalized_coords)
                 .map(|g| g.metrics.clone());
 
-            if let (Some(glyph_representation), Some(glyph_metrics)) = (
-                font.glyph_rendering_representation(font_face, glyph_id, font_size as u16, normalized_coords),
-                maybe_glyph_metrics,
-            ) {
-                (glyph_representation, glyph_metrics, scale)
-            } else {
+            let Some(glyph_representation) = font.glyph_rendering_representation(
+                font_face,
+                glyph_id,
+                font_size as u16,
+                normalized_coords,
+                color_style,
+            ) else {
+                return Ok(None);
+            };
+
+            // Color layers need not have an outline of their own.
+            let maybe_glyph_metrics = match &glyph_representation {
+                #[cfg(feature = "textlayout")]
+                GlyphRendering::RenderAsColorLayers(color_glyph) => Some(color_glyph.metrics()),
+                _ => maybe_glyph_metrics,
+            };
+
+            let Some(mut glyph_metrics) = maybe_glyph_metrics else {
                 return Ok(None);
+            };
+
+            // Only outlines are synthesized; color glyphs keep their design.
+            if matches!(glyph_representation, GlyphRendering::RenderAsPath(..)) {
+                synthesis.grow_metrics(&mut glyph_metrics, font.units_per_em());
             }
+
+            (glyph_representation, glyph_metrics, scale)
         };
 
-        #[cfg(feature = "image-loading")]
-        let color_glyph = matches!(glyph_representation, GlyphRendering::RenderAsImage(..));
-        #[cfg(not(feature = "image-loading"))]
-        let color_glyph = false;
+        let color_glyph = !matches!(glyph_representation, GlyphRendering::RenderAsPath(..));
 
         let line_width = if color_glyph || mode != RenderMode::Stroke {
             0.0
@@ -621,6 +1026,13 @@ impl GlyphAtlas {
 
         let line_width_offset = (line_width / 2.0).ceil();
 
+        // Color glyphs carry their own colors and are never subpixel antialiased.
+        let subpixel_offsets = if color_glyph {
+            None
+        } else {
+            antialiasing.subpixel_offsets()
+        };
+
         let width = (glyph_metrics.width * scale).ceil() as u32 + (line_width_offset * 2.0) as u32 + padding * 2;
         let height = (glyph_metrics.height * scale).ceil() as u32 + (line_width_offset * 2.0) as u32 + padding * 2;
 
@@ -645,6 +1057,7 @@ impl GlyphAtlas {
             atlas_y: dst_y as u32 + GLYPH_MARGIN,
             texture_index: dst_index,
             color_glyph,
+            subpixel_mask: subpixel_offsets.is_some(),
         };
 
         match glyph_representation {
@@ -691,27 +1104,40 @@ impl GlyphAtlas {
                     (-5.0 / 16.0, 3.0 / 16.0),
                 ];
 
-                for point in &points {
-                    canvas.save();
-                    canvas.translate(point.0, point.1);
+                // Subpixel masks sample each channel around its own subpixel
+                // center, writing only to that channel. Sampling at an offset
+                // means moving the outline the opposite way.
+                let passes = match subpixel_offsets {
+                    Some([r, g, b]) => vec![
+                        (r, Color::rgbf(factor, 0.0, 0.0)),
+                        (g, Color::rgbf(0.0, factor, 0.0)),
+                        (b, Color::rgbf(0.0, 0.0, factor)),
+                    ],
+                    None => vec![(0.0, mask_color)],
+                };
+
+                for (offset_x, mask_color) in passes {
+                    for point in &points {
+                        canvas.save();
+                        canvas.translate(point.0 - offset_x, point.1);
 
-                    canvas.scale(scale, scale);
+                        canvas.scale(scale, scale);
 
-                    if mode == RenderMode::Stroke {
-                        canvas.stroke_path_internal(
+                        synthesis.draw_outline(
+                            canvas,
                             path,
+                            font.units_per_em(),
                             &PaintFlavor::Color(mask_color),
                             false,
+                            mode,
                             &StrokeSettings {
                                 line_width,
                                 ..Default::default()
                             },
                         );
-                    } else {
-                        canvas.fill_path_internal(path, &PaintFlavor::Color(mask_color), false, FillRule::NonZero);
-                    }
 
-                    canvas.restore();
+                        canvas.restore();
+                    }
                 }
             }
             #[cfg(feature = "image-loading")]
@@ -721,10 +1147,41 @@ impl GlyphAtlas {
                 let target_width = rendered_glyph.width;
                 let target_height = rendered_glyph.height;
 
-                let image_buffer =
-                    image_buffer.resize(target_width, target_height, image::imageops::FilterType::Nearest);
-                if let Ok(image) = crate::image::ImageSource::try_from(&image_buffer) {
-                    canvas.update_image(dst_image_id, image, target_x, target_y).unwrap();
+                let image_buffer = image_buffer
+                    .resize(target_width, target_height, image::imageops::FilterType::Nearest)
+                    .into_rgba8();
+                let pixels: Vec<_> = image_buffer
+                    .pixels()
+                    .map(|&image::Rgba([r, g, b, a])| premultiplied(r, g, b, a))
+                    .collect();
+                let image = imgref::Img::new(
+                    &pixels[..],
+                    image_buffer.width() as usize,
+                    image_buffer.height() as usize,
+                );
+                canvas
+                    .update_image(dst_image_id, crate::image::ImageSource::from(image), target_x, target_y)
+                    .unwrap();
+            }
+            #[cfg(feature = "textlayout")]
+            GlyphRendering::RenderAsColorLayers(color_glyph) => {
+                let region_y = TEXTURE_SIZE as u32 - dst_y as u32 - height;
+
+                canvas.set_render_target(RenderTarget::Image(dst_image_id));
+                canvas.clear_rect(dst_x as u32, region_y, width, height, Color::rgba(0, 0, 0, 0));
+                // Keep the composite operations of the layers from reaching
+                // the neighboring glyphs.
+                canvas.scissor(dst_x as f32, region_y as f32, width as f32, height as f32);
+
+                canvas.translate(x, y);
+                canvas.scale(scale, scale);
+
+                for layer in &color_glyph.layers {
+                    canvas.save();
+                    canvas.set_transform(&layer.transform);
+                    canvas.state_mut().composite_operation = layer.composite_operation;
+                    canvas.fill_path_internal(&layer.path, &layer.paint, true, FillRule::NonZero);
+                    canvas.restore();
                 }
             }
         }
@@ -735,11 +1192,13 @@ impl GlyphAtlas {
     }
 
     #[cfg(feature = "swash")]
+    #[allow(clippy::too_many_arguments)]
     fn render_glyph_swash<T: Renderer>(
         &self,
         canvas: &mut Canvas<T>,
         font: &Font,
         font_size: f32,
+        antialiasing: TextAntialiasing,
         glyph_id: u16,
         subpixel_x: f32,
         normalized_coords: &[i16],
@@ -766,7 +1225,12 @@ impl GlyphAtlas {
                 Source::ColorBitmap(StrikeWith::BestFit),
                 Source::Outline,
             ])
-            .format(Format::Alpha)
+            // Like the offset below, swash's subpixel offsets move the outline.
+            .format(
+                antialiasing
+                    .subpixel_offsets()
+                    .map_or(Format::Alpha, |offsets| Format::CustomSubpixel(offsets.map(|x| -x))),
+            )
             .offset(swash::zeno::Vector::new(subpixel_x, 0.0))
             .render(&mut scaler, glyph_id)
         };
@@ -787,6 +1251,7 @@ impl GlyphAtlas {
             self.find_texture_or_alloc(canvas, alloc_width as usize, alloc_height as usize)?;
 
         let is_color = image.content == swash::scale::image::Content::Color;
+        let is_subpixel = image.content == swash::scale::image::Content::SubpixelMask;
 
         // Create a padded image with zeroed borders to avoid sampling undefined
         // texture data at glyph edges (the atlas texture is not zero-initialized).
@@ -794,17 +1259,22 @@ impl GlyphAtlas {
         let padded_height = (glyph_height + 2 * GLYPH_PADDING) as usize;
         let mut pixels = vec![rgb::RGBA8::new(0, 0, 0, 0); padded_width * padded_height];
 
-        if is_color {
+        if is_color || is_subpixel {
             for y in 0..glyph_height as usize {
                 for x in 0..glyph_width as usize {
                     let src_idx = (y * glyph_width as usize + x) * 4;
                     let dst_idx = (y + GLYPH_PADDING as usize) * padded_width + x + GLYPH_PADDING as usize;
-                    pixels[dst_idx] = rgb::RGBA8::new(
+                    let (r, g, b, a) = (
                         image.data[src_idx],
                         image.data[src_idx + 1],
                         image.data[src_idx + 2],
                         image.data[src_idx + 3],
                     );
+                    pixels[dst_idx] = if is_color {
+                        premultiplied(r, g, b, a)
+                    } else {
+                        rgb::RGBA8::new(r, g, b, a)
+                    };
                 }
             }
         } else {
@@ -832,6 +1302,146 @@ impl GlyphAtlas {
             atlas_x: dst_x as u32 + GLYPH_MARGIN,
             atlas_y: dst_y as u32 + GLYPH_MARGIN,
             color_glyph: is_color,
+            subpixel_mask: is_subpixel,
+        }))
+    }
+
+    /// Renders glyphs as distance fields and returns the quads to draw them with.
+    ///
+    /// Unlike [`Self::render_atlas`], every glyph is rasterized once, at
+    /// [`distance_field::REFERENCE_SIZE`], whatever `font_size` it is drawn at;
+    /// the quads are scaled to `font_size` instead and may be drawn under any
+    /// transform. Glyphs without an outline (color glyphs) are skipped.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn render_distance_field_atlas<T: Renderer>(
+        &self,
+        canvas: &mut Canvas<T>,
+        font_id: FontId,
+        font: &Font,
+        font_face: &font::FontFaceRef<'_>,
+        glyphs: impl Iterator<Item = PositionedGlyph>,
+        font_size: f32,
+        normalized_coords: &[i16],
+    ) -> Result<Vec<DrawCommand>, ErrorKind> {
+        let mut cmd_map = FnvHashMap::default();
+
+        let scale = font_size / distance_field::REFERENCE_SIZE;
+
+        for glyph in glyphs {
+            let id = RenderedGlyphId::new(
+                glyph.glyph_id,
+                font_id,
+                distance_field::REFERENCE_SIZE,
+                0.0,
+                RenderMode::Fill,
+                0,
+                TextAntialiasing::Grayscale,
+                ColorGlyphStyle::default(),
+                GlyphSynthesis::default(),
+                normalized_coords,
+            );
+
+            let cached = self.rendered_glyphs.borrow().get(&id).copied();
+            let rendered = match cached {
+                Some(rendered) => rendered,
+                None => {
+                    let rendered =
+                        self.render_distance_field_glyph(canvas, font, font_face, glyph.glyph_id, normalized_coords)?;
+                    self.rendered_glyphs.borrow_mut().insert(id, rendered);
+                    rendered
+                }
+            };
+
+            let Some(rendered) = rendered else {
+                continue;
+            };
+
+            if let Some(texture) = self.glyph_textures.borrow_mut().get_mut(rendered.texture_index) {
+                texture.last_used = self.frame.get();
+                let size = texture.atlas.size();
+                let itw = 1.0 / size.0 as f32;
+                let ith = 1.0 / size.1 as f32;
+
+                let cmd = cmd_map.entry(rendered.texture_index).or_insert_with(|| DrawCommand {
+                    image_id: texture.image_id,
+                    quads: Vec::new(),
+                });
+
+                // Distance fields are resolution independent, so the quad keeps
+                // the glyph's exact position rather than snapping to pixels.
+                let x0 = glyph.x + rendered.bearing_x as f32 * scale;
+                let y0 = glyph.y - rendered.bearing_y as f32 * scale;
+
+                cmd.quads.push(Quad {
+                    x0,
+                    y0,
+                    s0: rendered.atlas_x as f32 * itw,
+                    t0: rendered.atlas_y as f32 * ith,
+                    x1: x0 + rendered.width as f32 * scale,
+                    y1: y0 + rendered.height as f32 * scale,
+                    s1: (rendered.atlas_x + rendered.width) as f32 * itw,
+                    t1: (rendered.atlas_y + rendered.height) as f32 * ith,
+                });
+            }
+        }
+
+        Ok(cmd_map.into_values().collect())
+    }
+
+    // Generates the distance field of a glyph into the atlas. The sampled area
+    // covers the glyph's bounding box plus the field's spread, so that outlines
+    // and glows fit inside the quad.
+    fn render_distance_field_glyph<T: Renderer>(
+        &self,
+        canvas: &mut Canvas<T>,
+        font: &Font,
+        font_face: &font::FontFaceRef<'_>,
+        glyph_id: u16,
+        normalized_coords: &[i16],
+    ) -> Result<Option<RenderedGlyph>, ErrorKind> {
+        let Some(glyph) = font.glyph(font_face, glyph_id, normalized_coords) else {
+            return Ok(None);
+        };
+        let Some(path) = glyph.path.as_ref() else {
+            return Ok(None);
+        };
+
+        let scale = font.scale(distance_field::REFERENCE_SIZE);
+        let metrics = &glyph.metrics;
+        let border = distance_field::SPREAD.ceil() as i32 + GLYPH_PADDING as i32;
+
+        // Glyph box in reference pixels, y pointing up.
+        let left = (metrics.bearing_x * scale).floor() as i32 - border;
+        let right = ((metrics.bearing_x + metrics.width) * scale).ceil() as i32 + border;
+        let top = (metrics.bearing_y * scale).ceil() as i32 + border;
+        let bottom = ((metrics.bearing_y - metrics.height) * scale).floor() as i32 - border;
+
+        let width = (right - left) as u32;
+        let height = (top - bottom) as u32;
+        let alloc_width = (width + 2 * GLYPH_MARGIN) as usize;
+        let alloc_height = (height + 2 * GLYPH_MARGIN) as usize;
+
+        let (dst_index, dst_image_id, (dst_x, dst_y)) =
+            self.find_texture_or_alloc(canvas, alloc_width, alloc_height)?;
+
+        // The margin is filled with distances as well, so bilinear filtering at
+        // the quad's edges blends in valid values.
+        let origin = ((left - GLYPH_MARGIN as i32) as f32, (-top - GLYPH_MARGIN as i32) as f32);
+        let pixels = distance_field::generate(path, scale, origin, alloc_width, alloc_height);
+
+        let img = imgref::Img::new(&pixels[..], alloc_width, alloc_height);
+        canvas.update_image(dst_image_id, crate::image::ImageSource::from(img), dst_x, dst_y)?;
+
+        Ok(Some(RenderedGlyph {
+            texture_index: dst_index,
+            width,
+            height,
+            bearing_x: left,
+            bearing_y: top,
+            atlas_x: dst_x as u32 + GLYPH_MARGIN,
+            atlas_y: dst_y as u32 + GLYPH_MARGIN,
+            color_glyph: false,
+            subpixel_mask: false,
         }))
     }
 
@@ -842,18 +1452,64 @@ impl GlyphAtlas {
         width: usize,
         height: usize,
     ) -> Result<(usize, ImageId, (usize, usize)), ErrorKind> {
+        let frame = self.frame.get();
+
         // Find a free location in one of the atlases
         let mut texture_search_result = {
             let mut glyph_textures = self.glyph_textures.borrow_mut();
             let mut textures = glyph_textures.iter_mut().enumerate();
             textures.find_map(|(index, texture)| {
-                texture
-                    .atlas
-                    .add_rect(width, height)
-                    .map(|loc| (index, texture.image_id, loc))
+                texture.atlas.add_rect(width, height).map(|loc| {
+                    texture.last_used = frame;
+                    texture.used_area += width * height;
+                    (index, texture.image_id, loc)
+                })
             })
         };
 
+        // At the budget, reuse the least recently used page instead of growing.
+        // Packed glyphs can't be removed one by one, so the whole page is
+        // cleared. Pages used in this frame are skipped: pending draw commands
+        // still sample them. If all of them are, the atlas grows past the budget
+        // and is trimmed back at the end of the frame.
+        if texture_search_result.is_none()
+            && self
+                .max_pages
+                .get()
+                .is_some_and(|max_pages| self.glyph_textures.borrow().len() >= max_pages)
+        {
+            // Don't throw a page away for a glyph that wouldn't fit even once
+            // it is empty.
+            if Atlas::new(TEXTURE_SIZE, TEXTURE_SIZE).add_rect(width, height).is_none() {
+                return Err(ErrorKind::FontSizeTooLargeForAtlas);
+            }
+
+            let least_recently_used = self
+                .glyph_textures
+                .borrow()
+                .iter()
+                .enumerate()
+                .filter(|(_, texture)| texture.last_used < frame)
+                .min_by_key(|(_, texture)| texture.last_used)
+                .map(|(index, _)| index);
+
+            if let Some(index) = least_recently_used {
+                self.evict_page_glyphs(index);
+                self.recycled_pages.set(self.recycled_pages.get() + 1);
+
+                let mut glyph_textures = self.glyph_textures.borrow_mut();
+                let texture = &mut glyph_textures[index];
+                texture.atlas.reset(TEXTURE_SIZE, TEXTURE_SIZE);
+                let loc = texture
+                    .atlas
+                    .add_rect(width, height)
+                    .ok_or(ErrorKind::FontSizeTooLargeForAtlas)?;
+                texture.last_used = frame;
+                texture.used_area = width * height;
+                texture_search_result = Some((index, texture.image_id, loc));
+            }
+        }
+
         if texture_search_result.is_none() {
             // All atlases are exausted and a new one must be created
             let mut atlas = Atlas::new(TEXTURE_SIZE, TEXTURE_SIZE);
@@ -867,7 +1523,7 @@ impl GlyphAtlas {
             // anti-aliasing (ClearType®), and the atlas debug display is much
             // clearer with different colors. Also, Rgba8 is required for color
             // fonts (typically used for emojis).
-            let info = ImageInfo::new(ImageFlags::NEAREST, atlas.size().0, atlas.size().1, PixelFormat::Rgba8);
+            let info = ImageInfo::new(self.image_flags, atlas.size().0, atlas.size().1, PixelFormat::Rgba8);
             let image_id = canvas.images.alloc(&mut canvas.renderer, info)?;
 
             #[cfg(feature = "debug_inspector")]
@@ -916,7 +1572,12 @@ impl GlyphAtlas {
                 }
             }
 
-            self.glyph_textures.borrow_mut().push(FontTexture { atlas, image_id });
+            self.glyph_textures.borrow_mut().push(FontTexture {
+                atlas,
+                image_id,
+                last_used: frame,
+                used_area: width * height,
+            });
 
             let index = self.glyph_textures.borrow().len() - 1;
             texture_search_result = Some((index, image_id, loc));
@@ -925,6 +1586,76 @@ impl GlyphAtlas {
         texture_search_result.ok_or(ErrorKind::UnknownError)
     }
 
+    /// Limits the number of pages, see [`Canvas::set_glyph_atlas_budget`](crate::Canvas::set_glyph_atlas_budget).
+    pub(crate) fn set_max_pages(&self, max_pages: Option<usize>) {
+        self.max_pages.set(max_pages);
+    }
+
+    /// Starts a new frame once all draw commands have been flushed, deleting
+    /// the least recently used pages beyond the budget.
+    pub(crate) fn end_frame<T: Renderer>(&self, canvas: &mut Canvas<T>) {
+        self.frame.set(self.frame.get() + 1);
+
+        let Some(max_pages) = self.max_pages.get() else {
+            return;
+        };
+
+        while self.glyph_textures.borrow().len() > max_pages {
+            let least_recently_used = self
+                .glyph_textures
+                .borrow()
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, texture)| texture.last_used)
+                .map(|(index, _)| index);
+            let Some(index) = least_recently_used else {
+                break;
+            };
+
+            self.evict_page_glyphs(index);
+            let texture = self.glyph_textures.borrow_mut().remove(index);
+            canvas.delete_image(texture.image_id);
+
+            // Later pages moved down by one.
+            for rendered in self.rendered_glyphs.borrow_mut().values_mut().flatten() {
+                if rendered.texture_index > index {
+                    rendered.texture_index -= 1;
+                }
+            }
+        }
+    }
+
+    // Drops the cache entries of all glyphs packed into the page at `index`.
+    fn evict_page_glyphs(&self, index: usize) {
+        let mut evicted = 0;
+        self.rendered_glyphs.borrow_mut().retain(|_, rendered| {
+            let on_page = rendered.is_some_and(|rendered| rendered.texture_index == index);
+            evicted += u64::from(on_page);
+            !on_page
+        });
+        self.evicted_glyphs.set(self.evicted_glyphs.get() + evicted);
+    }
+
+    pub(crate) fn stats(&self) -> GlyphAtlasStats {
+        let glyph_textures = self.glyph_textures.borrow();
+        let pages = glyph_textures.len();
+        let page_area = TEXTURE_SIZE * TEXTURE_SIZE;
+        let used_area: usize = glyph_textures.iter().map(|texture| texture.used_area).sum();
+
+        GlyphAtlasStats {
+            pages,
+            texture_bytes: pages * PAGE_BYTES,
+            glyphs: self.rendered_glyphs.borrow().values().flatten().count(),
+            fill_ratio: if pages == 0 {
+                0.0
+            } else {
+                used_area as f32 / (pages * page_area) as f32
+            },
+            evicted_glyphs: self.evicted_glyphs.get(),
+            recycled_pages: self.recycled_pages.get(),
+        }
+    }
+
     pub(crate) fn clear<T: Renderer>(&self, canvas: &mut Canvas<T>) {
         let image_ids = std::mem::take(&mut *self.glyph_textures.borrow_mut())
             .into_iter()
@@ -966,6 +1697,7 @@ pub fn render_direct<T: Renderer>(
     stroke: &StrokeSettings,
     font_size: f32,
     mode: RenderMode,
+    synthesis: GlyphSynthesis,
     normalized_coords: &[i16],
 ) -> Result<(), ErrorKind> {
     let face = font.face_ref_with_normalized_coords(normalized_coords);
@@ -974,9 +1706,13 @@ pub fn render_direct<T: Renderer>(
         let (glyph_rendering, scale) = {
             let scale = font.scale(font_size);
 
-            let Some(glyph_rendering) =
-                font.glyph_rendering_representation(&face, glyph.glyph_id, font_size as u16, normalized_coords)
-            else {
+            let Some(glyph_rendering) = font.glyph_rendering_representation(
+                &face,
+                glyph.glyph_id,
+                font_size as u16,
+                normalized_coords,
+                ColorGlyphStyle::default(),
+            ) else {
                 continue;
             };
 
@@ -995,22 +1731,23 @@ pub fn render_direct<T: Renderer>(
 
         match glyph_rendering {
             GlyphRendering::RenderAsPath(path) => {
-                if mode == RenderMode::Stroke {
-                    canvas.stroke_path_internal(
-                        path.borrow(),
-                        paint_flavor,
-                        anti_alias,
-                        &StrokeSettings {
-                            line_width,
-                            ..stroke.clone()
-                        },
-                    );
-                } else {
-                    canvas.fill_path_internal(path.borrow(), paint_flavor, anti_alias, FillRule::NonZero);
-                }
+                synthesis.draw_outline(
+                    canvas,
+                    path.borrow(),
+                    font.units_per_em(),
+                    paint_flavor,
+                    anti_alias,
+                    mode,
+                    &StrokeSettings {
+                        line_width,
+                        ..stroke.clone()
+                    },
+                );
             }
             #[cfg(feature = "image-loading")]
             GlyphRendering::RenderAsImage(_) => unreachable!(),
+            #[cfg(feature = "textlayout")]
+            GlyphRendering::RenderAsColorLayers(_) => unreachable!(),
         }
 
         canvas.restore();
@@ -0,0 +1,613 @@
+//! Color glyphs from the `COLR`/`CPAL` tables, painted as layers of filled paths.
+//!
+//! ttf-parser walks the paint graph of a glyph and reports it to a
+//! [`colr::Painter`]; [`ColorGlyph::new`] records every paint as a layer: the
+//! clipping outline, the solid color or gradient it is filled with and the
+//! composite operation of the enclosing layer. The layers are then drawn into
+//! the color glyph atlas like any other path.
+//!
+//! A few parts of `COLRv1` are approximated, as the canvas has no isolated
+//! layer groups and only concentric radial gradients:
+//!
+//! * a paint is clipped by the innermost glyph outline only; outer outlines and
+//!   clip boxes merely bound it,
+//! * composite modes apply to the pixels covered by the source layer, and blend
+//!   modes other than screen and multiply fall back to source-over,
+//! * two-point radial gradients are drawn around the center of the smaller circle.
+
+use ttf_parser::{colr, GlyphId, OutlineBuilder, RgbaColor};
+
+use super::font::GlyphMetrics;
+use crate::{
+    geometry::Bounds, paint::PaintFlavor, BlendFactor, Color, CompositeOperation, CompositeOperationState, Paint, Path,
+    Transform2D,
+};
+
+// A repeating or reflecting gradient is unrolled into at most this many stops;
+// beyond that it is padded instead.
+const MAX_SPREAD_STOPS: usize = 256;
+
+/// A color glyph, as layers in font units (y pointing up).
+pub(crate) struct ColorGlyph {
+    pub(crate) layers: Vec<ColorLayer>,
+    bounds: Bounds,
+}
+
+pub(crate) struct ColorLayer {
+    /// The area to fill, in the coordinate space of the paint.
+    pub(crate) path: Path,
+    pub(crate) paint: PaintFlavor,
+    /// Maps the coordinate space of the paint to font units.
+    pub(crate) transform: Transform2D,
+    pub(crate) composite_operation: CompositeOperationState,
+}
+
+impl ColorGlyph {
+    /// Records the layers of `glyph_id`, or returns `None` if the font has no
+    /// color layers for it. Palettes past the last one fall back to palette 0.
+    pub(crate) fn new<'a>(
+        face: &'a ttf_parser::Face<'a>,
+        glyph_id: u16,
+        palette: u16,
+        foreground: [u8; 4],
+    ) -> Option<Self> {
+        let glyph_id = GlyphId(glyph_id);
+        if !face.is_color_glyph(glyph_id) {
+            return None;
+        }
+
+        let palettes = face.color_palettes().map_or(1, |count| count.get());
+        let palette = if palette < palettes { palette } else { 0 };
+        let [r, g, b, a] = foreground;
+
+        let mut painter = LayerPainter {
+            face,
+            palette,
+            transforms: Vec::new(),
+            outline: None,
+            clips: Vec::new(),
+            composite_operations: Vec::new(),
+            layers: Vec::new(),
+            bounds: Bounds::default(),
+        };
+        face.paint_color_glyph(glyph_id, palette, RgbaColor::new(r, g, b, a), &mut painter)?;
+
+        if painter.layers.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            layers: painter.layers,
+            bounds: painter.bounds,
+        })
+    }
+
+    pub(crate) fn metrics(&self) -> GlyphMetrics {
+        GlyphMetrics {
+            width: self.bounds.maxx - self.bounds.minx,
+            height: self.bounds.maxy - self.bounds.miny,
+            bearing_x: self.bounds.minx,
+            bearing_y: self.bounds.maxy,
+        }
+    }
+}
+
+// An outline as set by `outline_glyph`: the glyph and the transform in effect.
+#[derive(Copy, Clone)]
+struct Outline {
+    glyph_id: GlyphId,
+    transform: Transform2D,
+}
+
+enum Clip {
+    Outline(Outline),
+    // In font units.
+    Box(Bounds),
+}
+
+struct LayerPainter<'a> {
+    face: &'a ttf_parser::Face<'a>,
+    palette: u16,
+    // Each entry maps to font units, i.e. already includes the outer transforms.
+    transforms: Vec<Transform2D>,
+    // Outline set by `outline_glyph` and not yet turned into a clip. COLRv0
+    // layers paint it directly.
+    outline: Option<Outline>,
+    clips: Vec<Clip>,
+    // None for layers that leave the backdrop untouched.
+    composite_operations: Vec<Option<CompositeOperationState>>,
+    layers: Vec<ColorLayer>,
+    bounds: Bounds,
+}
+
+impl LayerPainter<'_> {
+    fn transform(&self) -> Transform2D {
+        self.transforms.last().copied().unwrap_or_default()
+    }
+
+    fn paint_flavor(&self, paint: colr::Paint<'_>, bounds: &Bounds) -> Option<PaintFlavor> {
+        let coords = self.face.variation_coordinates();
+        let flavor = match paint {
+            colr::Paint::Solid(color) => PaintFlavor::Color(to_color(color)),
+            colr::Paint::LinearGradient(gradient) => {
+                let stops = sorted_stops(gradient.stops(self.palette, coords))?;
+                let start = (gradient.x0, gradient.y0);
+                // The color line is perpendicular to p0p2, running through p1.
+                let (nx, ny) = (gradient.y2 - gradient.y0, gradient.x0 - gradient.x2);
+                let (dx, dy) = (gradient.x1 - gradient.x0, gradient.y1 - gradient.y0);
+                let norm = nx * nx + ny * ny;
+                let (dx, dy) = if norm > f32::EPSILON {
+                    let k = (dx * nx + dy * ny) / norm;
+                    (nx * k, ny * k)
+                } else {
+                    (dx, dy)
+                };
+
+                let Some((stops, (t0, t1))) = normalize_stops(&stops) else {
+                    return Some(PaintFlavor::Color(last_color(&stops)));
+                };
+                let start = (start.0 + dx * t0, start.1 + dy * t0);
+                let (dx, dy) = (dx * (t1 - t0), dy * (t1 - t0));
+                let length2 = dx * dx + dy * dy;
+                if length2 <= f32::EPSILON {
+                    return Some(PaintFlavor::Color(last_color(&stops)));
+                }
+
+                let corners = corners(bounds);
+                let along = |(x, y): (f32, f32)| ((x - start.0) * dx + (y - start.1) * dy) / length2;
+                let (t_min, t_max) = range(corners.iter().copied().map(along));
+                let (stops, (t0, t1)) = spread(stops, gradient.extend, t_min, t_max);
+
+                Paint::linear_gradient_stops(
+                    start.0 + dx * t0,
+                    start.1 + dy * t0,
+                    start.0 + dx * t1,
+                    start.1 + dy * t1,
+                    stops,
+                )
+                .flavor
+            }
+            colr::Paint::RadialGradient(gradient) => {
+                let stops = sorted_stops(gradient.stops(self.palette, coords))?;
+                let (cx, cy) = if gradient.r0 <= gradient.r1 {
+                    (gradient.x0, gradient.y0)
+                } else {
+                    (gradient.x1, gradient.y1)
+                };
+
+                let Some((mut stops, (t0, t1))) = normalize_stops(&stops) else {
+                    return Some(PaintFlavor::Color(last_color(&stops)));
+                };
+                let mut r0 = gradient.r0 + (gradient.r1 - gradient.r0) * t0;
+                let mut r1 = gradient.r0 + (gradient.r1 - gradient.r0) * t1;
+                if r1 < r0 {
+                    stops = reversed(stops);
+                    std::mem::swap(&mut r0, &mut r1);
+                }
+                if r1 - r0 <= f32::EPSILON {
+                    return Some(PaintFlavor::Color(last_color(&stops)));
+                }
+
+                let farthest = corners(bounds)
+                    .iter()
+                    .map(|(x, y)| (x - cx).hypot(y - cy))
+                    .fold(0.0, f32::max);
+                let (stops, (t0, t1)) = spread(stops, gradient.extend, -r0 / (r1 - r0), (farthest - r0) / (r1 - r0));
+
+                Paint::radial_gradient_stops(cx, cy, r0 + (r1 - r0) * t0, r0 + (r1 - r0) * t1, stops).flavor
+            }
+            colr::Paint::SweepGradient(gradient) => {
+                let stops = sorted_stops(gradient.stops(self.palette, coords))?;
+
+                // Angles are in half turns, counter-clockwise. Font units point
+                // y up, so that is the direction the conic gradient ramps in.
+                let Some((mut stops, (t0, t1))) = normalize_stops(&stops) else {
+                    return Some(PaintFlavor::Color(last_color(&stops)));
+                };
+                let span = gradient.end_angle - gradient.start_angle;
+                let mut start = gradient.start_angle + span * t0;
+                let mut end = gradient.start_angle + span * t1;
+                if end < start {
+                    stops = reversed(stops);
+                    std::mem::swap(&mut start, &mut end);
+                }
+                // Fraction of a full turn covered by one period of the stops.
+                let turns = (end - start) / 2.0;
+                if turns <= f32::EPSILON {
+                    return Some(PaintFlavor::Color(last_color(&stops)));
+                }
+
+                let (stops, (_, periods)) = spread(stops, gradient.extend, 0.0, 1.0 / turns);
+                let mut ramp: ColorRamp = stops
+                    .iter()
+                    .map(|&(offset, color)| (offset * periods * turns, color))
+                    .take_while(|&(offset, _)| offset <= 1.0)
+                    .collect();
+                if periods * turns < 1.0 {
+                    // Padded: split the uncovered angles between the end and
+                    // the start color.
+                    let middle = (periods * turns + 1.0) / 2.0;
+                    ramp.push((middle, last_color(&stops)));
+                    ramp.push((middle, stops[0].1));
+                    ramp.push((1.0, stops[0].1));
+                } else {
+                    ramp.push((1.0, color_at(&stops, 1.0 / (periods * turns))));
+                }
+
+                Paint::conic_gradient_stops_with_angle(
+                    gradient.center_x,
+                    gradient.center_y,
+                    start * std::f32::consts::PI,
+                    ramp,
+                )
+                .flavor
+            }
+        };
+        Some(flavor)
+    }
+}
+
+impl<'a> colr::Painter<'a> for LayerPainter<'a> {
+    fn outline_glyph(&mut self, glyph_id: GlyphId) {
+        self.outline = Some(Outline {
+            glyph_id,
+            transform: self.transform(),
+        });
+    }
+
+    fn paint(&mut self, paint: colr::Paint<'a>) {
+        let composite_operation = match self.composite_operations.last() {
+            Some(None) => return,
+            Some(Some(operation)) => *operation,
+            None => CompositeOperationState::new(CompositeOperation::SourceOver),
+        };
+
+        let transform = self.transform();
+        if determinant(&transform).abs() <= f32::EPSILON {
+            return;
+        }
+        let to_paint = transform.inverse();
+
+        let mut clip_bounds = None;
+        for clip in &self.clips {
+            if let Clip::Box(bounds) = clip {
+                clip_bounds = Some(clip_bounds.map_or(*bounds, |outer| intersect(&outer, bounds)));
+            }
+        }
+
+        let shape = self.outline.take().or_else(|| {
+            self.clips.iter().rev().find_map(|clip| match clip {
+                Clip::Outline(outline) => Some(*outline),
+                Clip::Box(_) => None,
+            })
+        });
+
+        let mut builder = TransformingBuilder::new(to_paint);
+        match shape {
+            Some(outline) => {
+                builder.to_paint = outline.transform * to_paint;
+                builder.to_font = outline.transform;
+                if self.face.outline_glyph(outline.glyph_id, &mut builder).is_none() {
+                    return;
+                }
+            }
+            // A paint that only a clip box bounds fills the whole box.
+            None => {
+                let Some(bounds) = clip_bounds else {
+                    return;
+                };
+                builder.to_font = Transform2D::identity();
+                builder.move_to(bounds.minx, bounds.miny);
+                builder.line_to(bounds.maxx, bounds.miny);
+                builder.line_to(bounds.maxx, bounds.maxy);
+                builder.line_to(bounds.minx, bounds.maxy);
+                builder.close();
+            }
+        }
+
+        let mut bounds = builder.font_bounds;
+        if let Some(clip_bounds) = clip_bounds {
+            bounds = intersect(&bounds, &clip_bounds);
+        }
+        if bounds.minx >= bounds.maxx || bounds.miny >= bounds.maxy {
+            return;
+        }
+
+        let Some(paint) = self.paint_flavor(paint, &builder.paint_bounds) else {
+            return;
+        };
+
+        self.bounds = union(&self.bounds, &bounds);
+        self.layers.push(ColorLayer {
+            path: builder.path,
+            paint,
+            transform,
+            composite_operation,
+        });
+    }
+
+    fn push_clip(&mut self) {
+        if let Some(outline) = self.outline.take() {
+            self.clips.push(Clip::Outline(outline));
+        }
+    }
+
+    fn push_clip_box(&mut self, clip_box: colr::ClipBox) {
+        let transform = self.transform();
+        let corners = [
+            (clip_box.x_min, clip_box.y_min),
+            (clip_box.x_max, clip_box.y_min),
+            (clip_box.x_max, clip_box.y_max),
+            (clip_box.x_min, clip_box.y_max),
+        ];
+        let mut bounds = Bounds::default();
+        for (x, y) in corners {
+            let (x, y) = transform.transform_point(x, y);
+            include(&mut bounds, x, y);
+        }
+        self.clips.push(Clip::Box(bounds));
+    }
+
+    fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+
+    fn push_layer(&mut self, mode: colr::CompositeMode) {
+        self.composite_operations.push(composite_operation(mode));
+    }
+
+    fn pop_layer(&mut self) {
+        self.composite_operations.pop();
+    }
+
+    fn push_transform(&mut self, transform: ttf_parser::Transform) {
+        let ttf_parser::Transform { a, b, c, d, e, f } = transform;
+        let transform = Transform2D::new(a, b, c, d, e, f) * self.transform();
+        self.transforms.push(transform);
+    }
+
+    fn pop_transform(&mut self) {
+        self.transforms.pop();
+    }
+}
+
+fn composite_operation(mode: colr::CompositeMode) -> Option<CompositeOperationState> {
+    use colr::CompositeMode;
+
+    let operation = match mode {
+        CompositeMode::Destination => return None,
+        CompositeMode::Clear => {
+            return Some(CompositeOperationState::with_blend_factors(
+                BlendFactor::Zero,
+                BlendFactor::Zero,
+            ))
+        }
+        CompositeMode::Source => CompositeOperation::Copy,
+        CompositeMode::DestinationOver => CompositeOperation::DestinationOver,
+        CompositeMode::SourceIn => CompositeOperation::SourceIn,
+        CompositeMode::DestinationIn => CompositeOperation::DestinationIn,
+        CompositeMode::SourceOut => CompositeOperation::SourceOut,
+        CompositeMode::DestinationOut => CompositeOperation::DestinationOut,
+        CompositeMode::SourceAtop => CompositeOperation::Atop,
+        CompositeMode::DestinationAtop => CompositeOperation::DestinationAtop,
+        CompositeMode::Xor => CompositeOperation::Xor,
+        CompositeMode::Plus => CompositeOperation::Lighter,
+        // s + d - s * d, exact with premultiplied colors.
+        CompositeMode::Screen => {
+            return Some(CompositeOperationState {
+                src_rgb: BlendFactor::One,
+                src_alpha: BlendFactor::One,
+                dst_rgb: BlendFactor::OneMinusSrcColor,
+                dst_alpha: BlendFactor::OneMinusSrcAlpha,
+            })
+        }
+        // s * d + d * (1 - sa), exact over an opaque backdrop.
+        CompositeMode::Multiply => {
+            return Some(CompositeOperationState {
+                src_rgb: BlendFactor::DstColor,
+                src_alpha: BlendFactor::One,
+                dst_rgb: BlendFactor::OneMinusSrcAlpha,
+                dst_alpha: BlendFactor::OneMinusSrcAlpha,
+            })
+        }
+        _ => CompositeOperation::SourceOver,
+    };
+    Some(CompositeOperationState::new(operation))
+}
+
+// Outline builder that writes the outline in paint space and measures it in
+// both paint space and font units.
+struct TransformingBuilder {
+    path: Path,
+    to_paint: Transform2D,
+    to_font: Transform2D,
+    paint_bounds: Bounds,
+    font_bounds: Bounds,
+}
+
+impl TransformingBuilder {
+    fn new(to_paint: Transform2D) -> Self {
+        Self {
+            path: Path::new(),
+            to_paint,
+            to_font: Transform2D::identity(),
+            paint_bounds: Bounds::default(),
+            font_bounds: Bounds::default(),
+        }
+    }
+
+    fn point(&mut self, x: f32, y: f32) -> (f32, f32) {
+        let (fx, fy) = self.to_font.transform_point(x, y);
+        include(&mut self.font_bounds, fx, fy);
+        let (px, py) = self.to_paint.transform_point(x, y);
+        include(&mut self.paint_bounds, px, py);
+        (px, py)
+    }
+}
+
+impl OutlineBuilder for TransformingBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.path.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.point(x, y);
+        self.path.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x, y) = self.point(x, y);
+        self.path.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.point(x1, y1);
+        let (x2, y2) = self.point(x2, y2);
+        let (x, y) = self.point(x, y);
+        self.path.bezier_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.path.close();
+    }
+}
+
+fn to_color(color: RgbaColor) -> Color {
+    Color::rgba(color.red, color.green, color.blue, color.alpha)
+}
+
+/// Gradient stops as `(offset, color)` pairs, sorted by offset.
+type ColorRamp = Vec<(f32, Color)>;
+
+fn sorted_stops(stops: impl Iterator<Item = colr::ColorStop>) -> Option<ColorRamp> {
+    let mut stops: Vec<_> = stops.map(|stop| (stop.stop_offset, to_color(stop.color))).collect();
+    stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+    (!stops.is_empty()).then_some(stops)
+}
+
+// Rescales the stops to span 0..1 and returns the range they spanned, or None
+// if all stops sit at the same offset.
+fn normalize_stops(stops: &[(f32, Color)]) -> Option<(ColorRamp, (f32, f32))> {
+    let first = stops.first()?.0;
+    let last = stops.last()?.0;
+    if last - first <= f32::EPSILON {
+        return None;
+    }
+    let stops = stops
+        .iter()
+        .map(|&(offset, color)| ((offset - first) / (last - first), color))
+        .collect();
+    Some((stops, (first, last)))
+}
+
+fn reversed(stops: ColorRamp) -> ColorRamp {
+    stops
+        .into_iter()
+        .rev()
+        .map(|(offset, color)| (1.0 - offset, color))
+        .collect()
+}
+
+fn last_color(stops: &[(f32, Color)]) -> Color {
+    stops.last().map_or(Color::rgba(0, 0, 0, 0), |stop| stop.1)
+}
+
+// Color of normalized stops at `t`, padded outside of 0..1.
+fn color_at(stops: &[(f32, Color)], t: f32) -> Color {
+    let mut previous = stops[0];
+    for &(offset, color) in stops {
+        if t <= offset {
+            let span = offset - previous.0;
+            if span <= f32::EPSILON {
+                return color;
+            }
+            let f = (t - previous.0) / span;
+            let c0 = previous.1;
+            return Color::rgbaf(
+                c0.r + (color.r - c0.r) * f,
+                c0.g + (color.g - c0.g) * f,
+                c0.b + (color.b - c0.b) * f,
+                c0.a + (color.a - c0.a) * f,
+            );
+        }
+        previous = (offset, color);
+    }
+    previous.1
+}
+
+// Unrolls repeating and reflecting stops so that they cover t_min..t_max of
+// the gradient. Returns the stops along with the whole periods they now span;
+// padded gradients keep spanning 0..1.
+fn spread(stops: ColorRamp, extend: colr::GradientExtend, t_min: f32, t_max: f32) -> (ColorRamp, (f32, f32)) {
+    let start = t_min.floor().min(0.0);
+    let end = t_max.ceil().max(1.0);
+    let periods = end - start;
+
+    if matches!(extend, colr::GradientExtend::Pad)
+        || !periods.is_finite()
+        || periods * stops.len() as f32 > MAX_SPREAD_STOPS as f32
+    {
+        return (stops, (0.0, 1.0));
+    }
+
+    let mut spread = Vec::with_capacity(periods as usize * stops.len());
+    for period in start as i32..end as i32 {
+        let reflect = matches!(extend, colr::GradientExtend::Reflect) && period.rem_euclid(2) == 1;
+        let period_stops = if reflect {
+            reversed(stops.clone())
+        } else {
+            stops.clone()
+        };
+        for (offset, color) in period_stops {
+            spread.push(((period as f32 + offset - start) / periods, color));
+        }
+    }
+    (spread, (start, end))
+}
+
+fn determinant(transform: &Transform2D) -> f32 {
+    let [a, b, c, d, ..] = transform.0;
+    a * d - b * c
+}
+
+fn corners(bounds: &Bounds) -> [(f32, f32); 4] {
+    [
+        (bounds.minx, bounds.miny),
+        (bounds.maxx, bounds.miny),
+        (bounds.maxx, bounds.maxy),
+        (bounds.minx, bounds.maxy),
+    ]
+}
+
+fn range(values: impl Iterator<Item = f32>) -> (f32, f32) {
+    values.fold((f32::MAX, f32::MIN), |(min, max), value| {
+        (min.min(value), max.max(value))
+    })
+}
+
+fn include(bounds: &mut Bounds, x: f32, y: f32) {
+    bounds.minx = bounds.minx.min(x);
+    bounds.miny = bounds.miny.min(y);
+    bounds.maxx = bounds.maxx.max(x);
+    bounds.maxy = bounds.maxy.max(y);
+}
+
+fn intersect(a: &Bounds, b: &Bounds) -> Bounds {
+    Bounds {
+        minx: a.minx.max(b.minx),
+        miny: a.miny.max(b.miny),
+        maxx: a.maxx.min(b.maxx),
+        maxy: a.maxy.min(b.maxy),
+    }
+}
+
+fn union(a: &Bounds, b: &Bounds) -> Bounds {
+    Bounds {
+        minx: a.minx.min(b.minx),
+        miny: a.miny.min(b.miny),
+        maxx: a.maxx.max(b.maxx),
+        maxy: a.maxy.max(b.maxy),
+    }
+}
@@ -0,0 +1,575 @@
+//! Multi-channel signed distance fields for glyph outlines.
+//!
+//! Glyphs drawn in distance-field mode are rasterized once, at
+//! [`REFERENCE_SIZE`], into a field that stores the distance to the outline
+//! instead of coverage. The field can then be sampled with bilinear filtering
+//! at any scale and rotation, and the shader reconstructs a sharp edge (or an
+//! outline or glow at some distance from it) per pixel.
+//!
+//! A single-channel field rounds off corners, so this follows the approach of
+//! Chlumský's [msdfgen](https://github.com/Chlumsky/msdfgen): the outline is
+//! split at its corners into edges that are assigned colors, each color channel
+//! stores the distance to the nearest edge of its color, and the median of the
+//! three channels reconstructs the corners. The alpha channel holds the true
+//! signed distance, which the shader uses for glows since it stays smooth far
+//! away from the outline.
+
+use rgb::RGBA8;
+
+use crate::{Path, Verb};
+
+/// Em size, in pixels, at which distance-field glyphs are generated.
+pub(crate) const REFERENCE_SIZE: f32 = 48.0;
+
+/// Distance, in reference pixels, that the field encodes on either side of the
+/// outline. Beyond it the field saturates.
+pub(crate) const SPREAD: f32 = 8.0;
+
+// Tolerance, in reference pixels, for flattening curves into line segments.
+const FLATTEN_TOLERANCE: f32 = 0.02;
+
+// Two adjacent edges meet at a corner if the angle between their directions
+// exceeds 3 radians' supplement, i.e. sin(3.0).
+const CORNER_CROSS_THRESHOLD: f32 = 0.141_12;
+
+const RED: u8 = 1;
+const GREEN: u8 = 2;
+const BLUE: u8 = 4;
+const MAGENTA: u8 = RED | BLUE;
+const CYAN: u8 = GREEN | BLUE;
+const WHITE: u8 = RED | GREEN | BLUE;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct Point {
+    x: f32,
+    y: f32,
+}
+
+impl Point {
+    fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Self::new(self.x + (other.x - self.x) * t, self.y + (other.y - self.y) * t)
+    }
+
+    fn dot(self, other: Self) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    fn cross(self, other: Self) -> f32 {
+        self.x * other.y - self.y * other.x
+    }
+
+    fn length(self) -> f32 {
+        self.x.hypot(self.y)
+    }
+
+    fn normalize(self) -> Self {
+        let len = self.length();
+        if len > 0.0 {
+            Self::new(self.x / len, self.y / len)
+        } else {
+            Self::default()
+        }
+    }
+}
+
+/// One line or curve of a contour, flattened into a polyline, and the set of
+/// channels whose distance it contributes to.
+#[derive(Clone, Debug)]
+struct Edge {
+    points: Vec<Point>,
+    start_direction: Point,
+    end_direction: Point,
+    color: u8,
+}
+
+impl Edge {
+    fn from_points(points: Vec<Point>) -> Self {
+        let start_direction = points[1].sub(points[0]).normalize();
+        let end_direction = points[points.len() - 1].sub(points[points.len() - 2]).normalize();
+        Self {
+            points,
+            start_direction,
+            end_direction,
+            color: WHITE,
+        }
+    }
+}
+
+/// Distance from a point to an edge, ordered like msdfgen's `SignedDistance`:
+/// closer wins, and on ties the edge met more orthogonally wins.
+#[derive(Copy, Clone, Debug)]
+struct EdgeDistance {
+    distance: f32,
+    dot: f32,
+    // Segment of the edge the distance was measured to, and the unclamped
+    // parameter of the point's projection onto it.
+    segment: usize,
+    param: f32,
+}
+
+impl EdgeDistance {
+    const FAR: Self = Self {
+        distance: f32::MAX,
+        dot: 1.0,
+        segment: 0,
+        param: 0.0,
+    };
+
+    fn is_closer_than(&self, other: &Self) -> bool {
+        let (a, b) = (self.distance.abs(), other.distance.abs());
+        a < b || (a == b && self.dot < other.dot)
+    }
+}
+
+fn segment_distance(p: Point, a: Point, b: Point, segment: usize) -> EdgeDistance {
+    let ab = b.sub(a);
+    let aq = p.sub(a);
+    let len_sq = ab.dot(ab);
+    let param = if len_sq > 0.0 { aq.dot(ab) / len_sq } else { 0.0 };
+    let endpoint = if param > 0.5 { b } else { a };
+    let eq = endpoint.sub(p);
+    let endpoint_distance = eq.length();
+
+    if param > 0.0 && param < 1.0 {
+        let ortho_distance = aq.cross(ab.normalize());
+        if ortho_distance.abs() < endpoint_distance {
+            return EdgeDistance {
+                distance: ortho_distance,
+                dot: 0.0,
+                segment,
+                param,
+            };
+        }
+    }
+
+    let sign = if aq.cross(ab) >= 0.0 { 1.0 } else { -1.0 };
+    EdgeDistance {
+        distance: sign * endpoint_distance,
+        dot: ab.normalize().dot(eq.normalize()).abs(),
+        segment,
+        param,
+    }
+}
+
+impl Edge {
+    fn distance(&self, p: Point) -> EdgeDistance {
+        let mut best = EdgeDistance::FAR;
+        for (index, window) in self.points.windows(2).enumerate() {
+            let distance = segment_distance(p, window[0], window[1], index);
+            if distance.is_closer_than(&best) {
+                best = distance;
+            }
+        }
+        best
+    }
+
+    /// Extends the edge along its end tangents, so that points beyond an end
+    /// measure their distance to the extended line rather than to the endpoint.
+    /// This is what keeps the corners between differently colored edges sharp.
+    fn pseudo_distance(&self, p: Point, distance: &EdgeDistance) -> f32 {
+        let segment_count = self.points.len() - 1;
+        if distance.segment == 0 && distance.param < 0.0 {
+            let aq = p.sub(self.points[0]);
+            if aq.dot(self.start_direction) < 0.0 {
+                let pseudo = aq.cross(self.start_direction);
+                if pseudo.abs() <= distance.distance.abs() {
+                    return pseudo;
+                }
+            }
+        } else if distance.segment == segment_count - 1 && distance.param > 1.0 {
+            let bq = p.sub(self.points[segment_count]);
+            if bq.dot(self.end_direction) > 0.0 {
+                let pseudo = bq.cross(self.end_direction);
+                if pseudo.abs() <= distance.distance.abs() {
+                    return pseudo;
+                }
+            }
+        }
+        distance.distance
+    }
+}
+
+/// Splits a path into contours of edges in pixel space. The path is in font
+/// units with y pointing up; `scale` converts to pixels and y is flipped so
+/// that the result matches the atlas's y-down layout.
+fn contours(path: &Path, scale: f32) -> Vec<Vec<Edge>> {
+    let to_px = |x: f32, y: f32| Point::new(x * scale, -y * scale);
+
+    let mut contours = Vec::new();
+    let mut edges: Vec<Edge> = Vec::new();
+    let mut start = Point::default();
+    let mut current = Point::default();
+
+    let push_line = |edges: &mut Vec<Edge>, from: Point, to: Point| {
+        if from != to {
+            edges.push(Edge::from_points(vec![from, to]));
+        }
+    };
+
+    for verb in path.verbs() {
+        match verb {
+            Verb::MoveTo(x, y) => {
+                if !edges.is_empty() {
+                    push_line(&mut edges, current, start);
+                    contours.push(std::mem::take(&mut edges));
+                }
+                start = to_px(x, y);
+                current = start;
+            }
+            Verb::LineTo(x, y) => {
+                let to = to_px(x, y);
+                push_line(&mut edges, current, to);
+                current = to;
+            }
+            Verb::BezierTo(c1x, c1y, c2x, c2y, x, y) => {
+                let (c1, c2, to) = (to_px(c1x, c1y), to_px(c2x, c2y), to_px(x, y));
+                if let Some(edge) = flatten_cubic(current, c1, c2, to) {
+                    edges.push(edge);
+                }
+                current = to;
+            }
+            Verb::Close => {
+                push_line(&mut edges, current, start);
+                current = start;
+                if !edges.is_empty() {
+                    contours.push(std::mem::take(&mut edges));
+                }
+            }
+            Verb::Solid | Verb::Hole => {}
+        }
+    }
+
+    if !edges.is_empty() {
+        push_line(&mut edges, current, start);
+        contours.push(edges);
+    }
+
+    contours
+}
+
+fn flatten_cubic(p0: Point, p1: Point, p2: Point, p3: Point) -> Option<Edge> {
+    if p0 == p1 && p1 == p2 && p2 == p3 {
+        return None;
+    }
+
+    // Wang's formula for the number of segments that keeps the flattened
+    // polyline within the tolerance of the curve.
+    let dd = |a: Point, b: Point, c: Point| Point::new(a.x - 2.0 * b.x + c.x, a.y - 2.0 * b.y + c.y).length();
+    let m = dd(p0, p1, p2).max(dd(p1, p2, p3));
+    let count = ((0.75 * m / FLATTEN_TOLERANCE).sqrt().ceil() as usize).clamp(1, 64);
+
+    let mut points = Vec::with_capacity(count + 1);
+    points.push(p0);
+    for i in 1..=count {
+        let t = i as f32 / count as f32;
+        let (a, b, c) = (p0.lerp(p1, t), p1.lerp(p2, t), p2.lerp(p3, t));
+        let (d, e) = (a.lerp(b, t), b.lerp(c, t));
+        points.push(d.lerp(e, t));
+    }
+    points.dedup();
+    if points.len() < 2 {
+        return None;
+    }
+
+    let mut edge = Edge::from_points(points);
+    // The control points give the exact end tangents.
+    let start_tangent = [p1, p2, p3].into_iter().find(|p| *p != p0).unwrap_or(p3);
+    let end_tangent = [p2, p1, p0].into_iter().find(|p| *p != p3).unwrap_or(p0);
+    edge.start_direction = start_tangent.sub(p0).normalize();
+    edge.end_direction = p3.sub(end_tangent).normalize();
+    Some(edge)
+}
+
+fn is_corner(a: Point, b: Point) -> bool {
+    a.dot(b) <= 0.0 || a.cross(b).abs() > CORNER_CROSS_THRESHOLD
+}
+
+// Cycles cyan -> magenta -> yellow, skipping a color if it would share no
+// channel with `banned` (so the last edge of a contour still differs from the
+// first).
+fn switch_color(color: u8, banned: u8) -> u8 {
+    let combined = color & banned;
+    if combined == RED || combined == GREEN || combined == BLUE {
+        return combined ^ WHITE;
+    }
+    let shifted = color << 1;
+    (shifted | shifted >> 3) & WHITE
+}
+
+/// Splits a contour with a single corner (a teardrop) into three parts of
+/// roughly equal segment count, so that it can be colored in three colors.
+fn split_in_three(edges: Vec<Edge>) -> Vec<Edge> {
+    let mut points: Vec<Point> = Vec::new();
+    for edge in &edges {
+        if points.is_empty() {
+            points.extend_from_slice(&edge.points);
+        } else {
+            points.extend_from_slice(&edge.points[1..]);
+        }
+    }
+    // Make sure there are at least three segments to distribute.
+    while points.len() < 4 {
+        let mut subdivided = Vec::with_capacity(points.len() * 2);
+        for window in points.windows(2) {
+            subdivided.push(window[0]);
+            subdivided.push(window[0].lerp(window[1], 0.5));
+        }
+        subdivided.push(points[points.len() - 1]);
+        points = subdivided;
+    }
+
+    let segments = points.len() - 1;
+    let bounds = [0, segments / 3, segments * 2 / 3, segments];
+    let first_direction = edges[0].start_direction;
+    let last_direction = edges[edges.len() - 1].end_direction;
+    let mut parts: Vec<Edge> = bounds
+        .windows(2)
+        .map(|range| Edge::from_points(points[range[0]..=range[1]].to_vec()))
+        .collect();
+    parts[0].start_direction = first_direction;
+    parts[2].end_direction = last_direction;
+    parts
+}
+
+/// Assigns colors to the edges of a contour so that the edges on either side
+/// of every corner share exactly one channel ("simple" edge coloring).
+fn color_contour(edges: Vec<Edge>) -> Vec<Edge> {
+    let corners: Vec<usize> = (0..edges.len())
+        .filter(|&i| {
+            let previous = &edges[(i + edges.len() - 1) % edges.len()];
+            is_corner(previous.end_direction, edges[i].start_direction)
+        })
+        .collect();
+
+    match corners.len() {
+        // Smooth contour: every channel sees every edge.
+        0 => edges,
+        // Teardrop: color the thirds cyan, white and magenta.
+        1 => {
+            let corner = corners[0];
+            let mut edges = edges;
+            edges.rotate_left(corner);
+            let mut edges = if edges.len() >= 3 { edges } else { split_in_three(edges) };
+            let colors = [CYAN, WHITE, MAGENTA];
+            let count = edges.len();
+            for (i, edge) in edges.iter_mut().enumerate() {
+                let third = (3.0 + 2.875 * i as f32 / (count - 1) as f32 - 1.4375 + 0.5) as i32 - 3;
+                edge.color = colors[(1 + third).clamp(0, 2) as usize];
+            }
+            edges
+        }
+        // Switch to the next color at each corner.
+        corner_count => {
+            let start = corners[0];
+            let mut edges = edges;
+            edges.rotate_left(start);
+            let corners: Vec<usize> = corners.iter().map(|c| c - start).collect();
+            let initial = CYAN;
+            let mut color = initial;
+            let mut spline = 0;
+            for (i, edge) in edges.iter_mut().enumerate() {
+                if spline + 1 < corner_count && corners[spline + 1] == i {
+                    spline += 1;
+                    let banned = if spline + 1 == corner_count { initial } else { 0 };
+                    color = switch_color(color, banned);
+                }
+                edge.color = color;
+            }
+            edges
+        }
+    }
+}
+
+fn median(a: f32, b: f32, c: f32) -> f32 {
+    a.min(b).max(a.max(b).min(c))
+}
+
+// Nonzero winding number contribution of the segment a -> b for a ray cast
+// from `p` towards +x.
+fn winding(p: Point, a: Point, b: Point) -> i32 {
+    if a.y <= p.y {
+        if b.y > p.y && b.sub(a).cross(p.sub(a)) > 0.0 {
+            return 1;
+        }
+    } else if b.y <= p.y && b.sub(a).cross(p.sub(a)) < 0.0 {
+        return -1;
+    }
+    0
+}
+
+fn encode(distance: f32) -> u8 {
+    ((0.5 + distance / (2.0 * SPREAD)).clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Generates the multi-channel distance field of a glyph outline.
+///
+/// `path` is in font units with y pointing up and `scale` converts it into
+/// pixels. The field is sampled at the centers of a `width` × `height` pixel
+/// grid whose top-left corner sits at `origin` in the y-down pixel space of
+/// the glyph (the baseline origin is at 0,0). Distances are positive inside
+/// the glyph and encoded so that 128 is the outline and 0 and 255 are
+/// [`SPREAD`] pixels outside and inside.
+pub(crate) fn generate(path: &Path, scale: f32, origin: (f32, f32), width: usize, height: usize) -> Vec<RGBA8> {
+    let contours: Vec<Vec<Edge>> = contours(path, scale).into_iter().map(color_contour).collect();
+
+    // Fonts disagree on whether outer contours run clockwise (TrueType) or
+    // counter-clockwise (CFF). The sign of the total area tells which one
+    // this outline uses, since outer contours enclose their holes.
+    let area: f32 = contours
+        .iter()
+        .flatten()
+        .flat_map(|edge| edge.points.windows(2))
+        .map(|w| w[0].cross(w[1]))
+        .sum();
+    let orientation = if area > 0.0 { -1.0 } else { 1.0 };
+
+    let mut pixels = vec![RGBA8::new(0, 0, 0, 0); width * height];
+
+    for (y, row) in pixels.chunks_exact_mut(width).enumerate() {
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let p = Point::new(origin.0 + x as f32 + 0.5, origin.1 + y as f32 + 0.5);
+
+            let mut channels = [(EdgeDistance::FAR, None::<&Edge>); 3];
+            let mut true_distance = f32::MAX;
+            let mut winding_number = 0;
+
+            for edge in contours.iter().flatten() {
+                for window in edge.points.windows(2) {
+                    winding_number += winding(p, window[0], window[1]);
+                }
+
+                let distance = edge.distance(p);
+                true_distance = true_distance.min(distance.distance.abs());
+                for (channel, (best, best_edge)) in channels.iter_mut().enumerate() {
+                    if edge.color & (1 << channel) != 0 && distance.is_closer_than(best) {
+                        *best = distance;
+                        *best_edge = Some(edge);
+                    }
+                }
+            }
+
+            let inside = winding_number != 0;
+            let true_distance = if inside { true_distance } else { -true_distance };
+
+            let [r, g, b] = channels
+                .map(|(distance, edge)| edge.map_or(-SPREAD, |edge| orientation * edge.pseudo_distance(p, &distance)));
+
+            // Where the channels disagree with the true inside/outside state
+            // the median would produce an artifact; fall back to the true
+            // distance there.
+            let [r, g, b] = if (median(r, g, b) > 0.0) != inside {
+                [true_distance; 3]
+            } else {
+                [r, g, b]
+            };
+
+            *pixel = RGBA8::new(encode(r), encode(g), encode(b), encode(true_distance));
+        }
+    }
+
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(value: u8) -> f32 {
+        (value as f32 / 255.0 - 0.5) * 2.0 * SPREAD
+    }
+
+    fn sample(pixels: &[RGBA8], width: usize, x: usize, y: usize) -> (f32, f32) {
+        let pixel = pixels[y * width + x];
+        (
+            median(decode(pixel.r), decode(pixel.g), decode(pixel.b)),
+            decode(pixel.a),
+        )
+    }
+
+    fn square(clockwise: bool) -> Path {
+        let mut path = Path::new();
+        path.move_to(0.0, 0.0);
+        if clockwise {
+            path.line_to(0.0, 10.0);
+            path.line_to(10.0, 10.0);
+            path.line_to(10.0, 0.0);
+        } else {
+            path.line_to(10.0, 0.0);
+            path.line_to(10.0, 10.0);
+            path.line_to(0.0, 10.0);
+        }
+        path.close();
+        path
+    }
+
+    #[test]
+    fn square_distances_are_signed_for_either_winding() {
+        for clockwise in [true, false] {
+            // The square spans x 0..10 and y -10..0 in y-down pixel space.
+            let pixels = generate(&square(clockwise), 1.0, (-5.0, -15.0), 20, 20);
+
+            // Center of the square: 5 pixels inside.
+            let (median_distance, true_distance) = sample(&pixels, 20, 10, 10);
+            assert!((median_distance - 4.5).abs() < 0.1, "{median_distance}");
+            assert!((true_distance - 4.5).abs() < 0.1, "{true_distance}");
+
+            // Left of the square, outside.
+            let (median_distance, true_distance) = sample(&pixels, 20, 2, 10);
+            assert!((median_distance + 2.5).abs() < 0.1, "{median_distance}");
+            assert!((true_distance + 2.5).abs() < 0.1, "{true_distance}");
+        }
+    }
+
+    #[test]
+    fn corners_stay_sharp() {
+        let pixels = generate(&square(false), 1.0, (-5.0, -15.0), 20, 20);
+        // Diagonally outside the top-right corner (10, -10): the true distance
+        // is the distance to the corner point, while the median measures to
+        // the nearer extended side, which is what reconstructs a sharp corner.
+        let (median_distance, true_distance) = sample(&pixels, 20, 17, 2);
+        assert!(
+            (true_distance + (2.5f32 * 2.5 * 2.0).sqrt()).abs() < 0.1,
+            "{true_distance}"
+        );
+        assert!((median_distance + 2.5).abs() < 0.1, "{median_distance}");
+    }
+
+    #[test]
+    fn adjacent_edges_share_exactly_one_channel_at_corners() {
+        for contour in contours(&square(true), 1.0).into_iter().map(color_contour) {
+            for i in 0..contour.len() {
+                let next = &contour[(i + 1) % contour.len()];
+                let shared = contour[i].color & next.color;
+                assert!(shared.count_ones() == 1, "{:03b} {:03b}", contour[i].color, next.color);
+            }
+        }
+    }
+
+    #[test]
+    fn smooth_contours_use_all_channels() {
+        let mut path = Path::new();
+        path.circle(0.0, 0.0, 10.0);
+        for contour in contours(&path, 1.0).into_iter().map(color_contour) {
+            assert!(contour.iter().all(|edge| edge.color == WHITE));
+        }
+    }
+
+    #[test]
+    fn saturates_beyond_spread() {
+        // Far from the outline the channels may measure to extended edges, but
+        // they still agree on being outside; the true distance saturates.
+        let pixels = generate(&square(true), 1.0, (-40.0, -15.0), 20, 20);
+        for pixel in pixels {
+            assert_eq!(pixel.a, 0);
+            assert!(median(pixel.r as f32, pixel.g as f32, pixel.b as f32) < 128.0);
+        }
+    }
+}
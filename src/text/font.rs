@@ -7,6 +7,7 @@ use std::rc::Rc;
 #[cfg(feature = "textlayout")]
 use ttf_parser::{Face as TtfFont, GlyphId};
 
+use super::ColorGlyphStyle;
 use crate::{paint::FontVariations, ErrorKind, Path};
 
 /// Abstraction over the parsed font face, so callers don't need cfg blocks.
@@ -42,6 +43,68 @@ pub struct VariationAxisInfo {
     pub hidden: bool,
 }
 
+/// The slant of a font face, following CSS `font-style`.
+///
+/// Used by [`TextContext::query_font`](crate::TextContext::query_font) to
+/// select between the faces of a family.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FontStyle {
+    /// Upright glyphs.
+    #[default]
+    Normal,
+    /// Cursive italic glyphs, designed as a separate face.
+    Italic,
+    /// Slanted (sheared) upright glyphs.
+    Oblique,
+}
+
+/// A named instance of a variable font, as listed in the font's `fvar` table.
+#[derive(Clone, Debug)]
+pub struct NamedInstance {
+    /// Subfamily name of the instance (e.g. "Bold Condensed").
+    pub name: Option<String>,
+    /// PostScript name of the instance, if the font provides one.
+    pub postscript_name: Option<String>,
+    /// Design-space axis values of the instance, one per axis in `fvar` order
+    /// (see [`Canvas::font_variation_axes`](crate::Canvas::font_variation_axes)).
+    pub coordinates: Vec<f32>,
+}
+
+/// Naming information about a font, read from its OpenType `name` table.
+///
+/// Returned by [`TextContext::font_info`](crate::TextContext::font_info).
+/// Names are taken in US English when the font provides them, otherwise in
+/// the first language it lists.
+#[derive(Clone, Debug, Default)]
+pub struct FontInfo {
+    /// Family name, preferring the typographic family (name ID 16) over the
+    /// legacy family (name ID 1).
+    pub family: Option<String>,
+    /// Subfamily name (e.g. "Bold Italic"), preferring the typographic
+    /// subfamily (name ID 17) over the legacy subfamily (name ID 2).
+    pub subfamily: Option<String>,
+    /// PostScript name (name ID 6).
+    pub postscript_name: Option<String>,
+    /// Named instances of a variable font. Empty for static fonts.
+    pub named_instances: Vec<NamedInstance>,
+    /// Number of `CPAL` color palettes, selectable with
+    /// [`Paint::set_font_palette`](crate::Paint::set_font_palette). Zero for
+    /// fonts without color glyphs.
+    pub color_palettes: u16,
+    // Legacy and WWS family names, which CSS font matching accepts as well.
+    #[cfg_attr(not(any(feature = "textlayout", feature = "swash")), allow(dead_code))]
+    pub(crate) family_aliases: Vec<String>,
+}
+
+impl FontInfo {
+    // All names CSS font matching accepts for this font's family.
+    #[cfg(any(feature = "textlayout", feature = "swash"))]
+    fn family_names(self) -> Vec<String> {
+        self.family.into_iter().chain(self.family_aliases).collect()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct GlyphMetrics {
     pub width: f32,
@@ -56,10 +119,13 @@ pub struct Glyph {
     pub metrics: GlyphMetrics,
 }
 
+#[allow(clippy::enum_variant_names)]
 pub enum GlyphRendering<'a> {
     RenderAsPath(Ref<'a, Path>),
     #[cfg(feature = "image-loading")]
     RenderAsImage(image::DynamicImage),
+    #[cfg(feature = "textlayout")]
+    RenderAsColorLayers(super::colr::ColorGlyph),
 }
 
 #[derive(Copy, Clone, Default, Debug)]
@@ -127,6 +193,77 @@ mod fallback {
     pub const X_HEIGHT: f32 = 0.5;
     /// Height of an uppercase letter above the baseline: 70% of the em.
     pub const CAP_HEIGHT: f32 = 0.7;
+    /// Hanging baseline above the alphabetic one: 80% of the ascender, the
+    /// same approximation browsers use for fonts without a `BASE` table.
+    pub const HANGING_BASELINE: f32 = 0.8;
+}
+
+/// Baseline positions read from the OpenType `BASE` table's horizontal axis,
+/// in font units above the alphabetic (`romn`) baseline.
+#[cfg(any(feature = "textlayout", feature = "swash"))]
+#[derive(Copy, Clone, Default, Debug)]
+struct BaseBaselines {
+    hanging: Option<f32>,
+    ideographic: Option<f32>,
+}
+
+#[cfg(any(feature = "textlayout", feature = "swash"))]
+impl BaseBaselines {
+    /// Parses the horizontal axis of a raw `BASE` table. The `DFLT` script is
+    /// preferred, then `latn`, then the first script that lists any values.
+    /// Missing or malformed tables yield no baselines.
+    fn parse(data: &[u8]) -> Self {
+        Self::try_parse(data).unwrap_or_default()
+    }
+
+    fn try_parse(data: &[u8]) -> Option<Self> {
+        let u16_at =
+            |offset: usize| -> Option<u16> { data.get(offset..offset + 2).map(|b| u16::from_be_bytes([b[0], b[1]])) };
+        let tag_at = |offset: usize| -> Option<[u8; 4]> { data.get(offset..offset + 4)?.try_into().ok() };
+
+        let axis = match u16_at(4)? {
+            0 => return None,
+            offset => offset as usize,
+        };
+        let tag_list = axis + u16_at(axis)? as usize;
+        let script_list = axis + u16_at(axis + 2)? as usize;
+
+        let tag_count = u16_at(tag_list)? as usize;
+        let tags = (0..tag_count)
+            .map(|i| tag_at(tag_list + 2 + i * 4))
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut scripts = Vec::new();
+        for i in 0..u16_at(script_list)? as usize {
+            let record = script_list + 2 + i * 6;
+            let script = script_list + u16_at(record + 4)? as usize;
+            let values = match u16_at(script)? {
+                0 => continue,
+                offset => script + offset as usize,
+            };
+            scripts.push((tag_at(record)?, values));
+        }
+        let (_, values) = scripts
+            .iter()
+            .find(|(tag, _)| tag == b"DFLT")
+            .or_else(|| scripts.iter().find(|(tag, _)| tag == b"latn"))
+            .or_else(|| scripts.first())?;
+
+        let coord_count = u16_at(values + 2)? as usize;
+        let coord = |tag: &[u8; 4]| -> Option<f32> {
+            let index = tags.iter().position(|t| t == tag).filter(|&i| i < coord_count)?;
+            let coord = values + u16_at(values + 4 + index * 2)? as usize;
+            // All three BaseCoord formats start with the format and the
+            // coordinate; device and variation adjustments are ignored.
+            u16_at(coord + 2).map(|v| v as i16 as f32)
+        };
+
+        let roman = coord(b"romn").unwrap_or(0.0);
+        Some(Self {
+            hanging: coord(b"hang").map(|v| v - roman),
+            ideographic: coord(b"ideo").map(|v| v - roman),
+        })
+    }
 }
 
 /// Information about a font.
@@ -145,6 +282,8 @@ pub struct FontMetrics {
     superscript_offset: (f32, f32),
     x_height: f32,
     cap_height: f32,
+    hanging_baseline: f32,
+    ideographic_baseline: f32,
     line_gap: f32,
     flags: FontFlags,
     weight: u16,
@@ -170,6 +309,8 @@ impl FontMetrics {
         self.superscript_offset.1 *= scale;
         self.x_height *= scale;
         self.cap_height *= scale;
+        self.hanging_baseline *= scale;
+        self.ideographic_baseline *= scale;
         self.line_gap *= scale;
     }
 
@@ -297,6 +438,26 @@ impl FontMetrics {
         self.cap_height
     }
 
+    /// Returns the height of the hanging baseline above the alphabetic one.
+    ///
+    /// Sourced from the `hang` entry of the font's `BASE` table when present,
+    /// otherwise falls back to 0.8 × the ascender. Scripts such as Devanagari
+    /// and Tibetan hang their glyphs from this line.
+    pub fn hanging_baseline(&self) -> f32 {
+        self.hanging_baseline
+    }
+
+    /// Returns the position of the ideographic baseline relative to the
+    /// alphabetic one, with the positive y-axis pointing up, so it is typically
+    /// negative.
+    ///
+    /// Sourced from the `ideo` entry of the font's `BASE` table (the bottom of
+    /// the ideographic em box) when present, otherwise falls back to the
+    /// descender.
+    pub fn ideographic_baseline(&self) -> f32 {
+        self.ideographic_baseline
+    }
+
     /// Returns the recommended additional space between lines of text, beyond
     /// ascender + descender.
     ///
@@ -365,6 +526,8 @@ pub struct Font {
     units_per_em: u16,
     metrics: FontMetrics,
     glyphs: RefCell<FnvHashMap<GlyphCacheKey, Glyph>>,
+    // Family name and aliases, read once at load for font matching.
+    family_names: Vec<String>,
     #[cfg(all(feature = "swash", not(feature = "textlayout")))]
     swash_scale_context: Rc<RefCell<swash::scale::ScaleContext>>,
 }
@@ -404,6 +567,11 @@ impl Font {
             .superscript_metrics()
             .filter(|m| m.x_size != 0 && m.y_size != 0);
         let fallback_script_size = (em * fallback::SCRIPT_SIZE, em * fallback::SCRIPT_SIZE);
+        let baselines = ttf_font
+            .raw_face()
+            .table(ttf_parser::Tag::from_bytes(b"BASE"))
+            .map(BaseBaselines::parse)
+            .unwrap_or_default();
 
         let metrics = FontMetrics {
             ascender,
@@ -445,6 +613,8 @@ impl Font {
                 .capital_height()
                 .filter(|&v| v != 0)
                 .map_or(em * fallback::CAP_HEIGHT, |v| v as f32),
+            hanging_baseline: baselines.hanging.unwrap_or(ascender * fallback::HANGING_BASELINE),
+            ideographic_baseline: baselines.ideographic.unwrap_or(descender),
             line_gap: ttf_font.line_gap() as f32,
             flags: FontFlags::new(
                 ttf_font.is_regular(),
@@ -457,13 +627,16 @@ impl Font {
             weight: ttf_font.weight().to_number(),
         };
 
-        Ok(Self {
+        let mut font = Self {
             data: Box::new(data),
             face_index,
             units_per_em,
             metrics,
             glyphs: RefCell::default(),
-        })
+            family_names: Vec::new(),
+        };
+        font.family_names = font.info().family_names();
+        Ok(font)
     }
 
     #[cfg(all(feature = "swash", not(feature = "textlayout")))]
@@ -525,6 +698,9 @@ impl Font {
         // case the same fallbacks kick in, keeping both backends in agreement
         // on availability.
         let fallback_script_size = (em * fallback::SCRIPT_SIZE, em * fallback::SCRIPT_SIZE);
+        let baselines = swash::TableProvider::table_by_tag(&font_ref, swash::tag_from_bytes(b"BASE"))
+            .map(BaseBaselines::parse)
+            .unwrap_or_default();
 
         let metrics = FontMetrics {
             ascender: swash_metrics.ascent,
@@ -558,6 +734,10 @@ impl Font {
             } else {
                 em * fallback::CAP_HEIGHT
             },
+            hanging_baseline: baselines
+                .hanging
+                .unwrap_or(swash_metrics.ascent * fallback::HANGING_BASELINE),
+            ideographic_baseline: baselines.ideographic.unwrap_or(-swash_metrics.descent),
             // swash's leading is the hhea line gap (or the OS/2 typographic
             // line gap when the font opts into typographic metrics).
             line_gap: swash_metrics.leading,
@@ -566,14 +746,17 @@ impl Font {
             width,
         };
 
-        Ok(Self {
+        let mut font = Self {
             data: Box::new(data),
             face_index,
             units_per_em,
             metrics,
             glyphs: RefCell::default(),
+            family_names: Vec::new(),
             swash_scale_context: text_context.swash_scale_context(),
-        })
+        };
+        font.family_names = font.info().family_names();
+        Ok(font)
     }
 
     #[cfg(not(any(feature = "textlayout", feature = "swash")))]
@@ -716,6 +899,24 @@ impl Font {
         size / self.units_per_em as f32
     }
 
+    pub(crate) fn units_per_em(&self) -> f32 {
+        self.units_per_em as f32
+    }
+
+    /// Returns the styles to synthesize for `variations` that ask for a bold
+    /// weight or an italic slant this font can't render by itself.
+    pub(crate) fn synthesis(&self, variations: &FontVariations) -> super::GlyphSynthesis {
+        let traits = self.face_traits();
+        let axis = |tag: &[u8; 4]| variations.get(FontVariations::tag_to_u32(tag));
+        let bold = axis(b"wght").is_some_and(|weight| weight >= 600.0 && traits.weight.1 < 600.0);
+        let slanted = axis(b"ital").is_some_and(|ital| ital >= 0.5) || axis(b"slnt").is_some_and(|slnt| slnt < 0.0);
+
+        super::GlyphSynthesis {
+            bold,
+            oblique: slanted && !traits.italic && !traits.oblique,
+        }
+    }
+
     #[cfg(feature = "textlayout")]
     pub fn variation_axes(&self) -> Vec<VariationAxisInfo> {
         let face = self.face_ref();
@@ -764,6 +965,173 @@ impl Font {
         Vec::new()
     }
 
+    #[cfg(feature = "textlayout")]
+    pub fn info(&self) -> FontInfo {
+        use ttf_parser::name_id;
+
+        let face = self.face_ref();
+        let name = |id: u16| {
+            let mut fallback = None;
+            for name in face.0.names() {
+                if name.name_id != id {
+                    continue;
+                }
+                let Some(value) = name.to_string() else {
+                    continue;
+                };
+                if name.language() == ttf_parser::Language::English_UnitedStates {
+                    return Some(value);
+                }
+                fallback.get_or_insert(value);
+            }
+            fallback
+        };
+
+        let legacy_family = name(name_id::FAMILY);
+        let family = name(name_id::TYPOGRAPHIC_FAMILY).or_else(|| legacy_family.clone());
+        let family_aliases = [legacy_family, name(name_id::WWS_FAMILY)]
+            .into_iter()
+            .flatten()
+            .filter(|alias| family.as_ref() != Some(alias))
+            .collect();
+
+        // ttf-parser only exposes the `fvar` axes, so read the instance
+        // records that follow them directly.
+        let mut named_instances = Vec::new();
+        if let Some(fvar) = face.0.raw_face().table(ttf_parser::Tag::from_bytes(b"fvar")) {
+            let read_u16 = |offset: usize| fvar.get(offset..offset + 2).map(|b| u16::from_be_bytes([b[0], b[1]]));
+            let read_fixed = |offset: usize| {
+                fvar.get(offset..offset + 4)
+                    .map(|b| i32::from_be_bytes([b[0], b[1], b[2], b[3]]) as f32 / 65536.0)
+            };
+            if let (Some(axes_offset), Some(axis_count), Some(axis_size), Some(instance_count), Some(instance_size)) =
+                (read_u16(4), read_u16(8), read_u16(10), read_u16(12), read_u16(14))
+            {
+                let axis_count = axis_count as usize;
+                let instance_size = instance_size as usize;
+                let instances_offset = axes_offset as usize + axis_count * axis_size as usize;
+                for index in 0..instance_count as usize {
+                    let offset = instances_offset + index * instance_size;
+                    let Some(subfamily_id) = read_u16(offset) else {
+                        break;
+                    };
+                    let coordinates: Option<Vec<f32>> =
+                        (0..axis_count).map(|axis| read_fixed(offset + 4 + axis * 4)).collect();
+                    let Some(coordinates) = coordinates else {
+                        break;
+                    };
+                    // The PostScript name ID is only present when the record
+                    // has room for it after the coordinates.
+                    let postscript_name_offset = 4 + axis_count * 4;
+                    let postscript_name = (instance_size >= postscript_name_offset + 2)
+                        .then(|| read_u16(offset + postscript_name_offset))
+                        .flatten()
+                        .filter(|&id| id != 0xFFFF)
+                        .and_then(name);
+                    named_instances.push(NamedInstance {
+                        name: name(subfamily_id),
+                        postscript_name,
+                        coordinates,
+                    });
+                }
+            }
+        }
+
+        FontInfo {
+            family,
+            subfamily: name(name_id::TYPOGRAPHIC_SUBFAMILY).or_else(|| name(name_id::SUBFAMILY)),
+            postscript_name: name(name_id::POST_SCRIPT_NAME),
+            named_instances,
+            color_palettes: face.0.color_palettes().map_or(0, |count| count.get()),
+            family_aliases,
+        }
+    }
+
+    #[cfg(all(feature = "swash", not(feature = "textlayout")))]
+    pub fn info(&self) -> FontInfo {
+        use swash::StringId;
+
+        let Some(font_ref) = self.swash_font_ref() else {
+            return FontInfo::default();
+        };
+        let strings = font_ref.localized_strings();
+        let name = |id: StringId| {
+            strings
+                .find_by_id(id, Some("en"))
+                .or_else(|| strings.find_by_id(id, None))
+                .map(|s| s.to_string())
+        };
+
+        let legacy_family = name(StringId::Family);
+        let family = name(StringId::TypographicFamily).or_else(|| legacy_family.clone());
+        let family_aliases = [legacy_family, name(StringId::WwsFamily)]
+            .into_iter()
+            .flatten()
+            .filter(|alias| family.as_ref() != Some(alias))
+            .collect();
+
+        FontInfo {
+            family,
+            subfamily: name(StringId::TypographicSubFamily).or_else(|| name(StringId::SubFamily)),
+            postscript_name: name(StringId::PostScript),
+            named_instances: font_ref
+                .instances()
+                .map(|instance| NamedInstance {
+                    name: name(instance.name_id()),
+                    postscript_name: instance.postscript_name_id().and_then(name),
+                    coordinates: instance.values().collect(),
+                })
+                .collect(),
+            color_palettes: font_ref.color_palettes().count() as u16,
+            family_aliases,
+        }
+    }
+
+    #[cfg(not(any(feature = "textlayout", feature = "swash")))]
+    pub fn info(&self) -> FontInfo {
+        FontInfo::default()
+    }
+
+    /// Returns whether `family` names this font, compared ASCII
+    /// case-insensitively against all family names the font provides.
+    pub(crate) fn matches_family(&self, family: &str) -> bool {
+        self.family_names.iter().any(|name| name.eq_ignore_ascii_case(family))
+    }
+
+    /// Returns the weight, width and style ranges this font can render, for
+    /// font matching. Variable fonts report the ranges of their `wght`,
+    /// `wdth`, `ital` and `slnt` axes.
+    pub(crate) fn face_traits(&self) -> super::matching::FaceTraits {
+        let weight = self.metrics.weight as f32;
+        let stretch = super::matching::width_class_to_stretch(self.metrics.width);
+        let mut traits = super::matching::FaceTraits {
+            weight: (weight, weight),
+            stretch: (stretch, stretch),
+            normal: !self.metrics.italic() && !self.metrics.oblique(),
+            italic: self.metrics.italic(),
+            oblique: self.metrics.oblique(),
+        };
+
+        for axis in self.variation_axes() {
+            match &axis.tag {
+                b"wght" => traits.weight = (axis.min_value, axis.max_value),
+                b"wdth" => traits.stretch = (axis.min_value, axis.max_value),
+                b"ital" if axis.max_value >= 1.0 => {
+                    traits.italic = true;
+                    traits.normal |= axis.min_value <= 0.0;
+                }
+                // Negative slant angles lean forward, like an oblique face.
+                b"slnt" if axis.min_value < 0.0 => {
+                    traits.oblique = true;
+                    traits.normal |= axis.max_value >= 0.0;
+                }
+                _ => (),
+            }
+        }
+
+        traits
+    }
+
     #[cfg(feature = "textlayout")]
     pub(crate) fn normalize_variations(&self, variations: &FontVariations) -> Vec<i16> {
         let face = self.face_ref_with_variations(variations);
@@ -894,6 +1262,17 @@ impl Font {
         Ref::filter_map(self.glyphs.borrow(), |glyphs| glyphs.get(&cache_key)).ok()
     }
 
+    /// Returns whether the glyph is painted from the font's `COLR` table.
+    #[cfg(feature = "textlayout")]
+    pub(crate) fn is_color_layer_glyph(&self, face: &FontFaceRef<'_>, codepoint: u16) -> bool {
+        face.0.is_color_glyph(GlyphId(codepoint))
+    }
+
+    #[cfg(not(feature = "textlayout"))]
+    pub(crate) fn is_color_layer_glyph(&self, _face: &FontFaceRef<'_>, _codepoint: u16) -> bool {
+        false
+    }
+
     #[cfg(feature = "textlayout")]
     pub(crate) fn glyph_rendering_representation(
         &self,
@@ -901,7 +1280,14 @@ impl Font {
         codepoint: u16,
         #[allow(unused_variables)] pixels_per_em: u16,
         normalized_coords: &[i16],
+        color_style: ColorGlyphStyle,
     ) -> Option<GlyphRendering<'_>> {
+        if let Some(glyph) =
+            super::colr::ColorGlyph::new(&face.0, codepoint, color_style.palette, color_style.foreground)
+        {
+            return Some(GlyphRendering::RenderAsColorLayers(glyph));
+        }
+
         #[cfg(feature = "image-loading")]
         if let Some(image) =
             face.0
@@ -927,6 +1313,7 @@ impl Font {
         codepoint: u16,
         #[allow(unused_variables)] _pixels_per_em: u16,
         normalized_coords: &[i16],
+        _color_style: ColorGlyphStyle,
     ) -> Option<GlyphRendering<'_>> {
         self.glyph(_face, codepoint, normalized_coords).and_then(|glyph| {
             Ref::filter_map(glyph, |glyph| glyph.path.as_ref())
@@ -942,7 +1329,7 @@ impl Font {
 // the same metrics availability.
 #[cfg(all(test, any(feature = "textlayout", feature = "swash")))]
 mod tests {
-    use super::Font;
+    use super::{BaseBaselines, Font};
 
     fn parse_font(data: Vec<u8>) -> Font {
         Font::new_with_data(data, 0, &super::super::TextContextImpl::default()).expect("font should parse")
@@ -953,6 +1340,12 @@ mod tests {
     /// take its documented fallback. Units per em is 1024 and the
     /// ascender/descender are 800/-200 font units.
     fn minimal_font_without_optional_tables() -> Vec<u8> {
+        minimal_font(None)
+    }
+
+    /// Like [`minimal_font_without_optional_tables`], plus a `BASE` table when
+    /// one is given.
+    fn minimal_font(base: Option<Vec<u8>>) -> Vec<u8> {
         fn push_u16(data: &mut Vec<u8>, value: u16) {
             data.extend_from_slice(&value.to_be_bytes());
         }
@@ -1009,7 +1402,10 @@ mod tests {
         push_u16(&mut maxp, 1); // numGlyphs
 
         // Table records must be sorted by tag.
-        let tables: [(&[u8; 4], &Vec<u8>); 3] = [(b"head", &head), (b"hhea", &hhea), (b"maxp", &maxp)];
+        let mut tables: Vec<(&[u8; 4], &Vec<u8>)> = vec![(b"head", &head), (b"hhea", &hhea), (b"maxp", &maxp)];
+        if let Some(base) = &base {
+            tables.insert(0, (b"BASE", base));
+        }
 
         let mut font = Vec::new();
         push_u32(&mut font, 0x0001_0000); // sfntVersion
@@ -1060,6 +1456,19 @@ mod tests {
         assert_eq!(metrics.strikeout_position(), 160.0); // 40% of the ascender
         assert!(metrics.underline_thickness() > 0.0);
         assert!(metrics.strikeout_thickness() > 0.0);
+
+        // Without a BASE table the hanging baseline sits at 80% of the
+        // ascender and the ideographic one on the descender.
+        assert_eq!(metrics.hanging_baseline(), 320.0);
+        assert_eq!(metrics.ideographic_baseline(), -100.0);
+    }
+
+    #[test]
+    fn base_table_baselines_override_the_fallbacks() {
+        let font = parse_font(minimal_font(Some(base_table(820, -120, 0))));
+        let metrics = font.metrics(512.);
+        assert_eq!(metrics.hanging_baseline(), 410.0);
+        assert_eq!(metrics.ideographic_baseline(), -60.0);
     }
 
     #[test]
@@ -1096,4 +1505,55 @@ mod tests {
         // The hhea line gap is commonly zero, but never negative for this font.
         assert!(metrics.line_gap() >= 0.0);
     }
+
+    fn be16(v: i32) -> [u8; 2] {
+        (v as u16).to_be_bytes()
+    }
+
+    /// A `BASE` table with one `latn` script listing `hang`, `ideo` and `romn`.
+    fn base_table(hang: i16, ideo: i16, romn: i16) -> Vec<u8> {
+        let mut t = Vec::new();
+        // Header: version 1.0, horizontal axis at 8, no vertical axis.
+        for v in [1, 0, 8, 0] {
+            t.extend(be16(v));
+        }
+        // Axis: tag list at +4, script list at +18.
+        t.extend(be16(4));
+        t.extend(be16(18));
+        t.extend(be16(3));
+        t.extend(b"hangideoromn");
+        // Script list: one record, its BaseScript right after it.
+        t.extend(be16(1));
+        t.extend(b"latn");
+        t.extend(be16(8));
+        // BaseScript: values at +6, no min/max, no language systems.
+        for v in [6, 0, 0] {
+            t.extend(be16(v));
+        }
+        // BaseValues: default romn, three format 1 coordinates.
+        for v in [2, 3, 10, 14, 18] {
+            t.extend(be16(v));
+        }
+        for coord in [hang, ideo, romn] {
+            t.extend(be16(1));
+            t.extend(be16(coord.into()));
+        }
+        t
+    }
+
+    #[test]
+    fn base_table_baselines_are_relative_to_roman() {
+        let baselines = BaseBaselines::parse(&base_table(1320, -100, 20));
+        assert_eq!(baselines.hanging, Some(1300.0));
+        assert_eq!(baselines.ideographic, Some(-120.0));
+    }
+
+    #[test]
+    fn truncated_base_tables_yield_no_baselines() {
+        let table = base_table(1320, -100, 0);
+        for len in [0, 6, 20, 40, 52] {
+            let baselines = BaseBaselines::parse(&table[..len]);
+            assert!(baselines.hanging.is_none() && baselines.ideographic.is_none(), "{len}");
+        }
+    }
 }
@@ -0,0 +1,243 @@
+//! CSS font matching over the fonts registered with a text context.
+//!
+//! Implements the narrowing steps of the
+//! [CSS Fonts font matching algorithm](https://www.w3.org/TR/css-fonts-4/#font-style-matching):
+//! candidates are filtered by family name, then by `font-stretch`, `font-style`
+//! and finally `font-weight`, each step keeping only the closest faces. Variable
+//! fonts take part with the ranges of their `wdth`, `wght`, `ital` and `slnt`
+//! axes, so a single variable face matches every value inside its ranges.
+
+use std::cmp::Ordering;
+
+use super::FontStyle;
+
+/// Normal `font-stretch`, in percent of the normal width.
+const NORMAL_STRETCH: f32 = 100.0;
+
+/// The style, weight and width a face can render, as used by the matcher.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct FaceTraits {
+    /// Supported `font-weight` range, inclusive.
+    pub weight: (f32, f32),
+    /// Supported `font-stretch` range in percent, inclusive.
+    pub stretch: (f32, f32),
+    /// Whether the face renders upright glyphs.
+    pub normal: bool,
+    /// Whether the face renders italic glyphs.
+    pub italic: bool,
+    /// Whether the face renders oblique glyphs.
+    pub oblique: bool,
+}
+
+/// Maps an OS/2 `usWidthClass` value to its `font-stretch` percentage, as
+/// defined by the OpenType specification.
+pub(crate) fn width_class_to_stretch(width_class: u16) -> f32 {
+    match width_class {
+        1 => 50.0,
+        2 => 62.5,
+        3 => 75.0,
+        4 => 87.5,
+        6 => 112.5,
+        7 => 125.0,
+        8 => 150.0,
+        9 => 200.0,
+        _ => NORMAL_STRETCH,
+    }
+}
+
+/// How well a face matches the requested value along one axis: a lower tier
+/// is preferred regardless of distance, and within a tier, closer wins.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct AxisScore {
+    tier: u8,
+    distance: f32,
+}
+
+impl AxisScore {
+    const EXACT: Self = Self { tier: 0, distance: 0.0 };
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.tier
+            .cmp(&other.tier)
+            .then_with(|| self.distance.total_cmp(&other.distance))
+    }
+}
+
+/// Scores a range against a desired value where values below the desired one
+/// are preferred (`prefer_lower`) or values above it are.
+fn directional_score(desired: f32, (min, max): (f32, f32), prefer_lower: bool) -> AxisScore {
+    if (min..=max).contains(&desired) {
+        return AxisScore::EXACT;
+    }
+    let below = max < desired;
+    let distance = if below { desired - max } else { min - desired };
+    let tier = if below == prefer_lower { 1 } else { 2 };
+    AxisScore { tier, distance }
+}
+
+fn stretch_score(desired: f32, range: (f32, f32)) -> AxisScore {
+    // Condensed and normal requests look at narrower widths first, expanded
+    // requests at wider ones.
+    directional_score(desired, range, desired <= NORMAL_STRETCH)
+}
+
+fn weight_score(desired: f32, (min, max): (f32, f32)) -> AxisScore {
+    if !(400.0..=500.0).contains(&desired) {
+        // Light requests look at lighter weights first, bold ones at heavier.
+        return directional_score(desired, (min, max), desired < 400.0);
+    }
+    if (min..=max).contains(&desired) {
+        return AxisScore::EXACT;
+    }
+    // Between 400 and 500, weights up to 500 come first (ascending), then
+    // lighter weights (descending), then weights above 500 (ascending).
+    if min > desired && min <= 500.0 {
+        AxisScore {
+            tier: 1,
+            distance: min - desired,
+        }
+    } else if max < desired {
+        AxisScore {
+            tier: 2,
+            distance: desired - max,
+        }
+    } else {
+        AxisScore {
+            tier: 3,
+            distance: min - desired,
+        }
+    }
+}
+
+fn style_rank(desired: FontStyle, traits: &FaceTraits) -> u8 {
+    let order = match desired {
+        FontStyle::Normal => [FontStyle::Normal, FontStyle::Oblique, FontStyle::Italic],
+        FontStyle::Italic => [FontStyle::Italic, FontStyle::Oblique, FontStyle::Normal],
+        FontStyle::Oblique => [FontStyle::Oblique, FontStyle::Italic, FontStyle::Normal],
+    };
+    let supports = |style| match style {
+        FontStyle::Normal => traits.normal,
+        FontStyle::Italic => traits.italic,
+        FontStyle::Oblique => traits.oblique,
+    };
+    order
+        .iter()
+        .position(|&style| supports(style))
+        .map_or(3, |rank| rank as u8)
+}
+
+/// Returns the index of the best match among `candidates` (which must already
+/// be filtered by family), or `None` if there are no candidates. Ties keep the
+/// earliest candidate.
+pub(crate) fn best_match(
+    candidates: impl IntoIterator<Item = FaceTraits>,
+    weight: f32,
+    style: FontStyle,
+    stretch: f32,
+) -> Option<usize> {
+    candidates
+        .into_iter()
+        .map(|traits| {
+            (
+                stretch_score(stretch, traits.stretch),
+                style_rank(style, &traits),
+                weight_score(weight, traits.weight),
+            )
+        })
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.0.cmp(&b.0).then(a.1.cmp(&b.1)).then_with(|| a.2.cmp(&b.2)))
+        .map(|(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn face(weight: f32, style: FontStyle) -> FaceTraits {
+        FaceTraits {
+            weight: (weight, weight),
+            stretch: (NORMAL_STRETCH, NORMAL_STRETCH),
+            normal: style == FontStyle::Normal,
+            italic: style == FontStyle::Italic,
+            oblique: style == FontStyle::Oblique,
+        }
+    }
+
+    fn pick(faces: &[FaceTraits], weight: f32, style: FontStyle, stretch: f32) -> usize {
+        best_match(faces.iter().copied(), weight, style, stretch).unwrap()
+    }
+
+    #[test]
+    fn weight_follows_css_search_order() {
+        let faces = [300., 400., 600., 700.].map(|w| face(w, FontStyle::Normal));
+
+        // Exact matches win.
+        assert_eq!(pick(&faces, 700., FontStyle::Normal, 100.), 3);
+        // 500 looks lighter first (400), not at the closer-by-distance 600.
+        assert_eq!(pick(&faces, 500., FontStyle::Normal, 100.), 1);
+        // Below 400 looks lighter first, then heavier.
+        assert_eq!(pick(&faces, 350., FontStyle::Normal, 100.), 0);
+        assert_eq!(pick(&faces[1..], 350., FontStyle::Normal, 100.), 0);
+        // Above 500 looks heavier first, then lighter.
+        assert_eq!(pick(&faces, 650., FontStyle::Normal, 100.), 3);
+        assert_eq!(pick(&faces[..2], 900., FontStyle::Normal, 100.), 1);
+    }
+
+    #[test]
+    fn weight_between_400_and_500_prefers_up_to_500() {
+        let faces = [face(300., FontStyle::Normal), face(500., FontStyle::Normal)];
+        assert_eq!(pick(&faces, 400., FontStyle::Normal, 100.), 1);
+
+        let faces = [face(300., FontStyle::Normal), face(600., FontStyle::Normal)];
+        assert_eq!(pick(&faces, 400., FontStyle::Normal, 100.), 0);
+    }
+
+    #[test]
+    fn style_falls_back_through_oblique() {
+        let faces = [face(400., FontStyle::Normal), face(400., FontStyle::Oblique)];
+        assert_eq!(pick(&faces, 400., FontStyle::Italic, 100.), 1);
+
+        let faces = [face(400., FontStyle::Italic), face(400., FontStyle::Normal)];
+        assert_eq!(pick(&faces, 400., FontStyle::Oblique, 100.), 0);
+        assert_eq!(pick(&faces, 400., FontStyle::Normal, 100.), 1);
+    }
+
+    #[test]
+    fn style_is_narrowed_before_weight() {
+        let faces = [face(400., FontStyle::Normal), face(700., FontStyle::Italic)];
+        assert_eq!(pick(&faces, 400., FontStyle::Italic, 100.), 1);
+    }
+
+    #[test]
+    fn stretch_is_narrowed_first() {
+        let mut condensed = face(400., FontStyle::Normal);
+        condensed.stretch = (75.0, 75.0);
+        let mut expanded = face(400., FontStyle::Normal);
+        expanded.stretch = (125.0, 125.0);
+        let faces = [expanded, condensed];
+
+        // Normal width looks narrower first even though both are 25% away.
+        assert_eq!(pick(&faces, 400., FontStyle::Normal, 100.), 1);
+        assert_eq!(pick(&faces, 400., FontStyle::Normal, 110.), 0);
+    }
+
+    #[test]
+    fn variable_ranges_match_any_contained_value() {
+        let variable = FaceTraits {
+            weight: (100.0, 1000.0),
+            stretch: (25.0, 151.0),
+            normal: true,
+            italic: false,
+            oblique: true,
+        };
+        let faces = [face(650., FontStyle::Normal), variable];
+        assert_eq!(pick(&faces, 650., FontStyle::Oblique, 120.), 1);
+    }
+
+    #[test]
+    fn width_classes_map_to_percentages() {
+        assert_eq!(width_class_to_stretch(1), 50.0);
+        assert_eq!(width_class_to_stretch(5), 100.0);
+        assert_eq!(width_class_to_stretch(9), 200.0);
+    }
+}
@@ -8,7 +8,7 @@ use lru::LruCache;
 
 use crate::{
     paint::{FontVariations, TextSettings},
-    Align, Baseline, ErrorKind, FontId, Paint,
+    Align, Baseline, ErrorKind, FontId, Paint, Path, TextJustify, Transform2D,
 };
 
 use unicode_bidi::BidiInfo;
@@ -24,10 +24,15 @@ pub struct ShapedGlyph {
     pub glyph_id: u16,
     pub width: f32,
     pub height: f32,
+    pub bearing_x: f32,
+    pub bearing_y: f32,
     pub advance_x: f32,
     pub advance_y: f32,
     pub offset_x: f32,
     pub offset_y: f32,
+    /// Whether the glyph is set sideways, rotated a quarter turn clockwise
+    /// about `(x, y)`, as non-CJK characters are in vertical text.
+    pub rotated: bool,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -51,9 +56,12 @@ pub(super) struct ShapingId {
     // Letter spacing is baked into the cached advances by `shape_word`, so
     // shapings at different spacings must not share an entry.
     letter_spacing_key: u32,
+    // Vertical text is shaped with vertical advances and alternates.
+    vertical: bool,
 }
 
 impl ShapingId {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         font_size: f32,
         font_ids: [Option<FontId>; 8],
@@ -62,6 +70,7 @@ impl ShapingId {
         variations: &FontVariations,
         letter_spacing: f32,
         rtl: Option<bool>,
+        vertical: bool,
     ) -> Self {
         let mut hasher = FnvHasher::default();
         word.hash(&mut hasher);
@@ -76,6 +85,7 @@ impl ShapingId {
             variation_hash: variations.hash(),
             rtl,
             letter_spacing_key: (letter_spacing * 10.0).trunc() as u32,
+            vertical,
         }
     }
 }
@@ -95,6 +105,15 @@ impl super::TextContext {
         self.0.borrow_mut().measure_text(x, y, text, &paint.text)
     }
 
+    /// Returns the outlines of the provided text, shaped and positioned as
+    /// [`Canvas::fill_text`](crate::Canvas::fill_text) would draw it, as a single [`Path`].
+    ///
+    /// Glyphs without outlines, such as bitmap emoji, are left out. Synthesized
+    /// oblique is applied to the outlines, synthesized bold is not.
+    pub fn text_to_path<S: AsRef<str>>(&self, x: f32, y: f32, text: S, paint: &Paint) -> Result<Path, ErrorKind> {
+        self.0.borrow_mut().text_to_path(x, y, text, &paint.text)
+    }
+
     /// Returns the maximum index-th byte of text that will fit inside `max_width`.
     ///
     /// The retuned index will always lie at the start and/or end of a UTF-8 code point sequence or at the start or end of the text
@@ -112,6 +131,22 @@ impl super::TextContext {
         self.0.borrow_mut().break_text_vec(max_width, text, &paint.text)
     }
 
+    /// Fits the provided text into at most `max_lines` lines of `max_width`,
+    /// ending it with an ellipsis where it is cut.
+    ///
+    /// See [`Canvas::truncate_text`](crate::Canvas::truncate_text) for details.
+    pub fn truncate_text<S: AsRef<str>>(
+        &self,
+        max_width: f32,
+        max_lines: usize,
+        text: S,
+        paint: &Paint,
+    ) -> Result<Vec<String>, ErrorKind> {
+        self.0
+            .borrow_mut()
+            .truncate_text(max_width, max_lines, text, &paint.text)
+    }
+
     /// Adjusts the capacity of the shaping run cache. This is a cache for measurements of whole
     /// strings.
     pub fn resize_shaping_run_cache(&self, capacity: std::num::NonZeroUsize) {
@@ -145,6 +180,101 @@ impl super::TextContextImpl {
         shape(x, y, self, text_settings, text.as_ref(), None)
     }
 
+    pub fn text_to_path<S: AsRef<str>>(
+        &mut self,
+        x: f32,
+        y: f32,
+        text: S,
+        text_settings: &TextSettings,
+    ) -> Result<Path, ErrorKind> {
+        let layout = shape(x, y, self, text_settings, text.as_ref(), None)?;
+
+        Ok(self.glyphs_to_path(&layout, text_settings))
+    }
+
+    /// Collects the outlines of the glyphs in `layout`, drawn at the font size
+    /// of `text_settings`, into one path in the layout's coordinate space.
+    pub(crate) fn glyphs_to_path(&self, layout: &TextMetrics, text_settings: &TextSettings) -> Path {
+        let mut path = Path::new();
+
+        // Only the oblique shear is synthesized here: a path has no way to
+        // dilate its outline, so faux bold glyphs keep their regular weight.
+        self.for_each_glyph_outline(layout, text_settings, |outline, transform, _| {
+            path.append_path(outline, transform);
+        });
+
+        path
+    }
+
+    /// Returns the horizontal extent of every glyph in `layout` whose outline
+    /// crosses the band between `top` and `bottom`, in the layout's space.
+    pub(crate) fn glyph_ink_ranges(
+        &self,
+        layout: &TextMetrics,
+        text_settings: &TextSettings,
+        top: f32,
+        bottom: f32,
+        tess_tol: f32,
+    ) -> Vec<(f32, f32)> {
+        let mut ranges = Vec::new();
+
+        self.for_each_glyph_outline(layout, text_settings, |outline, transform, embolden_width| {
+            let mut glyph_path = Path::new();
+            glyph_path.append_path(outline, transform);
+            // Faux bold dilates the outline by half the embolden width.
+            let half = embolden_width / 2.0;
+            ranges.extend(
+                glyph_path
+                    .horizontal_extent_within(top - half, bottom + half, tess_tol)
+                    .map(|(start, end)| (start - half, end + half)),
+            );
+        });
+
+        ranges
+    }
+
+    /// Calls `f` with the outline of each drawable glyph in `layout`, the
+    /// transform that places it in the layout's space, synthesized oblique
+    /// included, and the width its outline is stroked with to synthesize bold,
+    /// in the layout's units.
+    fn for_each_glyph_outline(
+        &self,
+        layout: &TextMetrics,
+        text_settings: &TextSettings,
+        mut f: impl FnMut(&Path, &Transform2D, f32),
+    ) {
+        let normalized_coords =
+            super::normalize_variations(self, &text_settings.font_ids, &text_settings.font_variations);
+
+        for glyph in layout.glyphs.iter().filter(|glyph| !glyph.c.is_control()) {
+            let Some(font) = self.font(glyph.font_id) else {
+                continue;
+            };
+
+            let face = font.face_ref_with_normalized_coords(&normalized_coords);
+            let Some(outline) = font.glyph(&face, glyph.glyph_id, &normalized_coords) else {
+                continue;
+            };
+            let Some(outline) = &outline.path else {
+                continue;
+            };
+
+            // Font units are y-up; flip them onto the baseline like `render_direct` does.
+            let synthesis = font.synthesis(&text_settings.font_variations);
+            let scale = font.scale(text_settings.font_size);
+            let mut transform = synthesis.transform() * Transform2D::scaling(scale, -scale);
+            if glyph.rotated {
+                transform *= Transform2D::rotation(std::f32::consts::FRAC_PI_2);
+            }
+            let transform = transform * Transform2D::translation(glyph.x, glyph.y);
+            f(
+                outline,
+                &transform,
+                synthesis.embolden_width(font.units_per_em()) * scale,
+            );
+        }
+    }
+
     pub fn break_text<S: AsRef<str>>(
         &mut self,
         max_width: f32,
@@ -156,6 +286,81 @@ impl super::TextContextImpl {
         Ok(layout.final_byte_index)
     }
 
+    pub fn truncate_text<S: AsRef<str>>(
+        &mut self,
+        max_width: f32,
+        max_lines: usize,
+        text: S,
+        text_settings: &TextSettings,
+    ) -> Result<Vec<String>, ErrorKind> {
+        let text = text.as_ref();
+
+        let lines = if max_lines > 1 {
+            self.break_text_vec(max_width, text, text_settings)?
+        } else {
+            std::iter::once(0..text.len()).collect()
+        };
+        let line_count = lines.len().min(max_lines.max(1));
+
+        let mut truncated = Vec::with_capacity(line_count);
+        for (index, line) in lines.into_iter().take(line_count).enumerate() {
+            // The last line takes the rest of the text, so the ellipsis marks
+            // everything that didn't fit.
+            let line = if index + 1 == line_count {
+                &text[line.start..]
+            } else {
+                &text[line]
+            };
+            truncated.push(self.truncate_line(max_width, line, text_settings)?);
+        }
+
+        Ok(truncated)
+    }
+
+    /// Cuts `text` at the last grapheme boundary that leaves room for an
+    /// ellipsis within `max_width`, and appends the ellipsis. Text that fits
+    /// is returned unchanged.
+    fn truncate_line(&mut self, max_width: f32, text: &str, text_settings: &TextSettings) -> Result<String, ErrorKind> {
+        let width = |context: &mut Self, text: &str| {
+            shape(0.0, 0.0, context, text_settings, text, None).map(|metrics| metrics.width())
+        };
+
+        // Trailing whitespace may hang past the edge, as it does when wrapping.
+        if width(self, text.trim_end())? <= max_width {
+            return Ok(text.to_owned());
+        }
+
+        // Shaping falls back to any registered font for characters the paint's
+        // fonts lack, so U+2026 is used whenever some font has it.
+        let ellipsis = if self
+            .fonts
+            .values()
+            .any(|font| font.face_ref().0.glyph_index('\u{2026}').is_some())
+        {
+            "\u{2026}"
+        } else {
+            "..."
+        };
+        let available = max_width - width(self, ellipsis)?;
+
+        // Binary search for the longest prefix that fits. The ellipsis goes at
+        // the logical end, so in right-to-left text it is shaped at the left.
+        let boundaries = text.grapheme_indices(true).map(|(index, _)| index).collect::<Vec<_>>();
+        let prefix = |count: usize| text[..boundaries.get(count).copied().unwrap_or_default()].trim_end();
+        let (mut fitting, mut low, mut high) = (0, 1, boundaries.len());
+        while low < high {
+            let mid = (low + high) / 2;
+            if width(self, prefix(mid))? <= available {
+                fitting = mid;
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        Ok(format!("{}{ellipsis}", prefix(fitting)))
+    }
+
     pub fn break_text_vec<S: AsRef<str>>(
         &mut self,
         max_width: f32,
@@ -195,6 +400,20 @@ pub struct TextMetrics {
     width: f32,
     height: f32,
     baseline: f32,
+    actual_bounding_box_left: f32,
+    actual_bounding_box_right: f32,
+    actual_bounding_box_ascent: f32,
+    actual_bounding_box_descent: f32,
+    font_bounding_box_ascent: f32,
+    font_bounding_box_descent: f32,
+    em_height_ascent: f32,
+    em_height_descent: f32,
+    hanging_baseline: f32,
+    alphabetic_baseline: f32,
+    ideographic_baseline: f32,
+    // Whether the paragraph runs right to left, which resolves `Align::Start`
+    // and `Align::End`.
+    rtl: bool,
     /// Vector of shaped glyphs resulting from the text shaping run.
     pub glyphs: Vec<ShapedGlyph>,
     pub(crate) final_byte_index: usize,
@@ -207,16 +426,31 @@ impl TextMetrics {
         self.width *= scale;
         self.height *= scale;
         self.baseline *= scale;
+        self.actual_bounding_box_left *= scale;
+        self.actual_bounding_box_right *= scale;
+        self.actual_bounding_box_ascent *= scale;
+        self.actual_bounding_box_descent *= scale;
+        self.font_bounding_box_ascent *= scale;
+        self.font_bounding_box_descent *= scale;
+        self.em_height_ascent *= scale;
+        self.em_height_descent *= scale;
+        self.hanging_baseline *= scale;
+        self.alphabetic_baseline *= scale;
+        self.ideographic_baseline *= scale;
 
         for glyph in &mut self.glyphs {
             glyph.x *= scale;
             glyph.y *= scale;
             glyph.width *= scale;
             glyph.height *= scale;
+            glyph.bearing_x *= scale;
+            glyph.bearing_y *= scale;
         }
     }
 
     /// width of the glyphs as drawn
+    ///
+    /// In vertical writing modes this is the advance down the column.
     pub fn width(&self) -> f32 {
         self.width
     }
@@ -225,6 +459,9 @@ impl TextMetrics {
     /// glyph positions: the requested `y` adjusted for the paint's
     /// [`Baseline`](crate::Baseline) setting. Glyphs and text decorations are
     /// positioned relative to this line.
+    ///
+    /// In vertical writing modes this is the x-coordinate of the column's
+    /// center line instead.
     pub fn baseline(&self) -> f32 {
         self.baseline
     }
@@ -233,6 +470,75 @@ impl TextMetrics {
     pub fn height(&self) -> f32 {
         self.height
     }
+
+    // The accessors below mirror the Canvas 2D `TextMetrics` interface. They
+    // are distances from the point the text was drawn at: the requested `x`
+    // for the horizontal ones and the requested `y`, which the paint's
+    // `Baseline` setting refers to, for the vertical ones. In vertical writing
+    // modes only the actual bounding box is reported.
+
+    /// Distance from the requested `x` to the left edge of the glyphs' ink,
+    /// positive when the ink extends to the left of it.
+    pub fn actual_bounding_box_left(&self) -> f32 {
+        self.actual_bounding_box_left
+    }
+
+    /// Distance from the requested `x` to the right edge of the glyphs' ink.
+    pub fn actual_bounding_box_right(&self) -> f32 {
+        self.actual_bounding_box_right
+    }
+
+    /// Distance from the requested `y` up to the top of the glyphs' ink.
+    pub fn actual_bounding_box_ascent(&self) -> f32 {
+        self.actual_bounding_box_ascent
+    }
+
+    /// Distance from the requested `y` down to the bottom of the glyphs' ink.
+    pub fn actual_bounding_box_descent(&self) -> f32 {
+        self.actual_bounding_box_descent
+    }
+
+    /// Distance from the requested `y` up to the fonts' ascender line.
+    pub fn font_bounding_box_ascent(&self) -> f32 {
+        self.font_bounding_box_ascent
+    }
+
+    /// Distance from the requested `y` down to the fonts' descender line.
+    pub fn font_bounding_box_descent(&self) -> f32 {
+        self.font_bounding_box_descent
+    }
+
+    /// Distance from the requested `y` up to the top of the em square.
+    ///
+    /// The em square is one font size tall and split between ascent and
+    /// descent in the proportion of the fonts' ascender and descender.
+    pub fn em_height_ascent(&self) -> f32 {
+        self.em_height_ascent
+    }
+
+    /// Distance from the requested `y` down to the bottom of the em square.
+    pub fn em_height_descent(&self) -> f32 {
+        self.em_height_descent
+    }
+
+    /// Distance from the hanging baseline down to the requested `y`, negative
+    /// when `y` lies above it.
+    pub fn hanging_baseline(&self) -> f32 {
+        self.hanging_baseline
+    }
+
+    /// Distance from the alphabetic baseline down to the requested `y`:
+    /// zero with [`Baseline::Alphabetic`](crate::Baseline::Alphabetic),
+    /// negative when `y` lies above it.
+    pub fn alphabetic_baseline(&self) -> f32 {
+        self.alphabetic_baseline
+    }
+
+    /// Distance from the ideographic baseline down to the requested `y`,
+    /// negative when `y` lies above it.
+    pub fn ideographic_baseline(&self) -> f32 {
+        self.ideographic_baseline
+    }
 }
 
 // Shaper
@@ -256,6 +562,7 @@ pub fn shape(
         // The run cache keys the whole string; per-run direction is derived
         // from the text itself below.
         None,
+        text_settings.writing_mode.is_vertical(),
     );
 
     if !context.shaping_run_cache.contains(&id) {
@@ -264,6 +571,7 @@ pub fn shape(
             text_settings.font_size,
             text_settings.font_ids,
             text_settings.letter_spacing,
+            text_settings.writing_mode.is_vertical(),
             text,
             max_width,
             variations,
@@ -280,11 +588,13 @@ pub fn shape(
     Err(ErrorKind::UnknownError)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn shape_run(
     context: &mut super::TextContextImpl,
     font_size: f32,
     font_ids: [Option<FontId>; 8],
     letter_spacing: f32,
+    vertical: bool,
     text: &str,
     max_width: Option<f32>,
     variations: &FontVariations,
@@ -292,11 +602,8 @@ fn shape_run(
     let mut result = TextMetrics {
         x: 0.0,
         y: 0.0,
-        width: 0.0,
-        height: 0.0,
-        baseline: 0.0,
         glyphs: Vec::with_capacity(text.len()),
-        final_byte_index: 0,
+        ..Default::default()
     };
 
     // The paragraph base direction follows the first strong character
@@ -311,6 +618,7 @@ fn shape_run(
     let Some(paragraph) = bidi_info.paragraphs.first() else {
         return result;
     };
+    result.rtl = paragraph.level.is_rtl();
 
     let line = paragraph.range.clone();
 
@@ -344,12 +652,14 @@ fn shape_run(
                 variations,
                 letter_spacing,
                 Some(is_rtl_run),
+                vertical,
             );
 
             if !context.shaped_words_cache.contains(&id) {
                 let word = shape_word(
                     word_txt,
                     hb_direction,
+                    vertical,
                     context,
                     font_size,
                     &font_ids,
@@ -372,7 +682,8 @@ fn shape_run(
                             let target_width = max_width - result.width;
                             for glyph in word.glyphs {
                                 bytes_included = glyph.byte_index;
-                                let glyph_width = glyph.advance_x + letter_spacing;
+                                // Vertical glyphs advance along y instead.
+                                let glyph_width = glyph.advance_x + glyph.advance_y + letter_spacing;
 
                                 // nuance: we want to include the first glyph even if it breaks
                                 // the bounds. this is to allow pathologically small bounds to
@@ -398,11 +709,13 @@ fn shape_run(
                                 variations,
                                 letter_spacing,
                                 Some(is_rtl_run),
+                                vertical,
                             );
                             if !context.shaped_words_cache.contains(&id) {
                                 let subword = shape_word(
                                     subword_txt,
                                     hb_direction,
+                                    vertical,
                                     context,
                                     font_size,
                                     &font_ids,
@@ -467,7 +780,148 @@ fn shape_run(
     result
 }
 
+#[allow(clippy::too_many_arguments)]
 fn shape_word(
+    word: &str,
+    hb_direction: rustybuzz::Direction,
+    vertical: bool,
+    context: &mut super::TextContextImpl,
+    font_size: f32,
+    font_ids: &[Option<FontId>; 8],
+    letter_spacing: f32,
+    variations: &FontVariations,
+) -> Result<ShapedWord, ErrorKind> {
+    if !vertical {
+        return shape_segment(
+            word,
+            hb_direction,
+            context,
+            font_size,
+            font_ids,
+            letter_spacing,
+            variations,
+        );
+    }
+
+    // Vertical text is shaped in runs of equal orientation: upright
+    // characters top to bottom, sideways ones in their horizontal direction.
+    let mut shaped_word = ShapedWord::default();
+    let mut chars = word.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let upright = is_upright_in_vertical_text(c);
+        let mut end = start + c.len_utf8();
+        while let Some(&(index, c)) = chars.peek() {
+            if !c.is_control() && is_upright_in_vertical_text(c) != upright {
+                break;
+            }
+            end = index + c.len_utf8();
+            chars.next();
+        }
+
+        let direction = if upright {
+            rustybuzz::Direction::TopToBottom
+        } else {
+            hb_direction
+        };
+        let segment = shape_segment(
+            &word[start..end],
+            direction,
+            context,
+            font_size,
+            font_ids,
+            letter_spacing,
+            variations,
+        )?;
+
+        for mut glyph in segment.glyphs {
+            glyph.byte_index += start;
+            if !upright {
+                // Sideways glyphs keep their horizontal offsets, which are
+                // rotated along with the glyph, but advance down the column.
+                glyph.rotated = true;
+                glyph.advance_y = glyph.advance_x;
+                glyph.advance_x = 0.0;
+            }
+            shaped_word.glyphs.push(glyph);
+        }
+        shaped_word.width += segment.width;
+    }
+
+    Ok(shaped_word)
+}
+
+/// Returns whether `c` stands upright in vertical text, approximating the
+/// `U` and `Tu` classes of the UAX #50 `Vertical_Orientation` property.
+///
+/// Characters of the `Tr` class (brackets, the prolonged sound mark) are
+/// treated as upright too: the fonts' `vert` alternates supply their
+/// vertical forms.
+fn is_upright_in_vertical_text(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x00A7 | 0x00A9 | 0x00AE | 0x00B1 | 0x00BC..=0x00BE | 0x00D7 | 0x00F7
+            | 0x02EA..=0x02EB
+            | 0x1100..=0x11FF
+            | 0x1401..=0x167F
+            | 0x18B0..=0x18FF
+            | 0x2016
+            | 0x2020..=0x2021
+            | 0x2030..=0x2031
+            | 0x203B..=0x203C
+            | 0x2042
+            | 0x2047..=0x2049
+            | 0x2051
+            | 0x2065
+            | 0x20DD..=0x20E0
+            | 0x20E2..=0x20E4
+            | 0x2100..=0x2101
+            | 0x2103..=0x2109
+            | 0x210F
+            | 0x2113..=0x2114
+            | 0x2116..=0x2117
+            | 0x211E..=0x2123
+            | 0x2125
+            | 0x2127
+            | 0x2129
+            | 0x212E
+            | 0x2135..=0x213F
+            | 0x2145..=0x214A
+            | 0x214C..=0x214D
+            | 0x214F..=0x2189
+            | 0x218C..=0x218F
+            | 0x221E
+            | 0x2234..=0x2235
+            | 0x2300..=0x2307
+            | 0x230C..=0x231F
+            | 0x2324..=0x2328
+            | 0x232B
+            | 0x237D..=0x239A
+            | 0x23BE..=0x23CD
+            | 0x23CF
+            | 0x23D1..=0x23DB
+            | 0x23E2..=0x2422
+            | 0x2424..=0x24FF
+            | 0x25A0..=0x2619
+            | 0x2620..=0x2767
+            | 0x2776..=0x2793
+            | 0x2B12..=0x2B2F
+            | 0x2B50..=0x2B59
+            | 0x2BB8..=0x2BFF
+            | 0x2E80..=0x2FFF
+            | 0x3000..=0x30FF
+            | 0x3100..=0xA4CF
+            | 0xA960..=0xA97F
+            | 0xAC00..=0xD7FF
+            | 0xE000..=0xFAFF
+            | 0xFE10..=0xFE1F
+            | 0xFE30..=0xFE6F
+            | 0xFF00..=0xFFEF
+            | 0x1F000..=0x1FAFF
+            | 0x20000..=0x3FFFD
+    )
+}
+
+fn shape_segment(
     word: &str,
     hb_direction: rustybuzz::Direction,
     context: &mut super::TextContextImpl,
@@ -488,8 +942,19 @@ fn shape_word(
             buffer.push_str(word);
             buffer.set_direction(hb_direction);
 
-            rustybuzz::shape(&face, &[], buffer)
+            // Vertical shaping applies `vert` by itself; `vrt2` is requested
+            // alongside it for fonts that only provide the newer feature.
+            let features = if hb_direction == rustybuzz::Direction::TopToBottom {
+                [b"vert", b"vrt2"]
+                    .map(|tag| rustybuzz::Feature::new(rustybuzz::ttf_parser::Tag::from_bytes(tag), 1, ..))
+                    .to_vec()
+            } else {
+                Vec::new()
+            };
+
+            rustybuzz::shape(&face, &features, buffer)
         };
+        let vertical = hb_direction == rustybuzz::Direction::TopToBottom;
 
         let positions = output.glyph_positions();
         let infos = output.glyph_infos();
@@ -520,18 +985,30 @@ fn shape_word(
                     .expect("rustybuzz guarantees the output glyph id is u16"),
                 width: 0.0,
                 height: 0.0,
+                bearing_x: 0.0,
+                bearing_y: 0.0,
                 advance_x: position.x_advance as f32 * scale,
                 advance_y: position.y_advance as f32 * scale,
                 offset_x: position.x_offset as f32 * scale,
                 offset_y: position.y_offset as f32 * scale,
+                rotated: false,
             };
 
+            if vertical {
+                // Vertical positions are y-up and place the glyph's horizontal
+                // origin relative to the pen on the column's center line.
+                g.advance_y = -g.advance_y;
+                g.offset_y = -g.offset_y;
+            }
+
             if let Some(glyph) = font.glyph(&font_face, g.glyph_id, &normalized_coords) {
                 g.width = glyph.metrics.width * scale;
                 g.height = glyph.metrics.height * scale;
+                g.bearing_x = glyph.metrics.bearing_x * scale;
+                g.bearing_y = glyph.metrics.bearing_y * scale;
             }
 
-            shaped_word.width += g.advance_x + letter_spacing;
+            shaped_word.width += g.advance_x + g.advance_y + letter_spacing;
             shaped_word.glyphs.push(g);
         }
 
@@ -547,14 +1024,22 @@ fn layout(
     res: &mut TextMetrics,
     text_settings: &TextSettings,
 ) -> Result<(), ErrorKind> {
+    if text_settings.writing_mode.is_vertical() {
+        return layout_vertical(x, y, context, res, text_settings);
+    }
+
     let mut cursor_x = x;
     let mut cursor_y = y;
 
+    if let (Align::Justify, Some(width)) = (text_settings.text_align, text_settings.justify_width) {
+        justify(res, width, text_settings.text_justify, false);
+    }
+
     // Horizontal alignment
-    match text_settings.text_align {
+    match resolve_align(text_settings.text_align, res.rtl) {
         Align::Center => cursor_x -= res.width / 2.0,
         Align::Right => cursor_x -= res.width,
-        Align::Left => (),
+        _ => (),
     }
 
     res.x = cursor_x;
@@ -588,6 +1073,8 @@ fn layout(
         Baseline::Middle => ascender.midpoint(descender),
         Baseline::Alphabetic => 0.0,
         Baseline::Bottom => descender,
+        Baseline::Hanging => primary_metrics.hanging_baseline(),
+        Baseline::Ideographic => primary_metrics.ideographic_baseline(),
     };
 
     // Record where the alphabetic baseline lands so consumers (glyph drawing,
@@ -609,5 +1096,166 @@ fn layout(
     res.y = min_y;
     res.height = max_y - min_y;
 
+    set_actual_bounding_box(x, y, res);
+
+    let baseline = res.baseline;
+    res.font_bounding_box_ascent = y - (baseline - ascender);
+    res.font_bounding_box_descent = (baseline - descender) - y;
+
+    // The em square is split in the proportion of ascender to descender.
+    let em_ascent = if ascender - descender > 0.0 {
+        text_settings.font_size * ascender / (ascender - descender)
+    } else {
+        text_settings.font_size
+    };
+    let em_top = baseline - em_ascent;
+    let em_bottom = em_top + text_settings.font_size;
+    res.em_height_ascent = y - em_top;
+    res.em_height_descent = em_bottom - y;
+
+    res.alphabetic_baseline = y - baseline;
+    res.hanging_baseline = y - (baseline - primary_metrics.hanging_baseline());
+    res.ideographic_baseline = y - (baseline - primary_metrics.ideographic_baseline());
+
     Ok(())
 }
+
+// Lays out vertical text down the column whose center line is at `x`. The
+// paint's `Align` positions the run along the column, starting at `y`.
+fn layout_vertical(
+    x: f32,
+    y: f32,
+    context: &mut super::TextContextImpl,
+    res: &mut TextMetrics,
+    text_settings: &TextSettings,
+) -> Result<(), ErrorKind> {
+    let mut cursor_y = y;
+
+    if let (Align::Justify, Some(width)) = (text_settings.text_align, text_settings.justify_width) {
+        justify(res, width, text_settings.text_justify, true);
+    }
+
+    // Columns start at the top whatever the paragraph direction.
+    match resolve_align(text_settings.text_align, false) {
+        Align::Center => cursor_y -= res.width / 2.0,
+        Align::Right => cursor_y -= res.width,
+        _ => (),
+    }
+
+    res.baseline = x;
+    let start_y = cursor_y;
+
+    for glyph in &mut res.glyphs {
+        if glyph.rotated {
+            // Sideways glyphs are centered on the column by their line box,
+            // with their tops towards the right.
+            let font = context.font_mut(glyph.font_id).ok_or(ErrorKind::NoFontFound)?;
+            let metrics = font.metrics(text_settings.font_size);
+            let baseline_x = x - metrics.ascender().midpoint(metrics.descender());
+            glyph.x = baseline_x - glyph.offset_y;
+            glyph.y = cursor_y + glyph.offset_x;
+        } else {
+            glyph.x = x + glyph.offset_x;
+            glyph.y = cursor_y + glyph.offset_y;
+        }
+
+        cursor_y += glyph.advance_y + text_settings.letter_spacing;
+    }
+
+    set_actual_bounding_box(x, y, res);
+
+    // The box spans the column's ink across and the advances along it.
+    res.x = x - res.actual_bounding_box_left;
+    res.y = start_y;
+    res.height = cursor_y - start_y;
+
+    Ok(())
+}
+
+// Sets the actual bounding box of `res` from the glyph outline boxes, relative
+// to the point `(x, y)` the text was drawn at. Empty glyphs (spaces, control
+// characters) carry no ink.
+fn set_actual_bounding_box(x: f32, y: f32, res: &mut TextMetrics) {
+    let mut ink: Option<(f32, f32, f32, f32)> = None;
+    for glyph in &res.glyphs {
+        if glyph.width <= 0.0 || glyph.height <= 0.0 {
+            continue;
+        }
+        let (left, top, right, bottom) = if glyph.rotated {
+            // A quarter turn clockwise maps the glyph's (x, y) to (-y, x).
+            let left = glyph.x + glyph.bearing_y - glyph.height;
+            let top = glyph.y + glyph.bearing_x;
+            (left, top, left + glyph.height, top + glyph.width)
+        } else {
+            let left = glyph.x + glyph.bearing_x;
+            let top = glyph.y - glyph.bearing_y;
+            (left, top, left + glyph.width, top + glyph.height)
+        };
+        ink = Some(ink.map_or((left, top, right, bottom), |(l, t, r, b)| {
+            (l.min(left), t.min(top), r.max(right), b.max(bottom))
+        }));
+    }
+    if let Some((left, top, right, bottom)) = ink {
+        res.actual_bounding_box_left = x - left;
+        res.actual_bounding_box_right = right - x;
+        res.actual_bounding_box_ascent = y - top;
+        res.actual_bounding_box_descent = bottom - y;
+    }
+}
+
+// Resolves `align` to `Left`, `Center` or `Right` for a paragraph running in
+// the given direction. Justified text is anchored like `Start`.
+fn resolve_align(align: Align, rtl: bool) -> Align {
+    match align {
+        Align::Start | Align::Justify if rtl => Align::Right,
+        Align::Start | Align::Justify => Align::Left,
+        Align::End if rtl => Align::Left,
+        Align::End => Align::Right,
+        align => align,
+    }
+}
+
+// Widens the advances of the run's word gaps, or of its clusters, so that the
+// run spans `width`. Whitespace at either end of the run is left alone.
+fn justify(res: &mut TextMetrics, width: f32, mode: TextJustify, vertical: bool) {
+    let extra = width - res.width;
+    if extra <= 0.0 {
+        return;
+    }
+
+    let is_ink = |glyph: &ShapedGlyph| !glyph.c.is_whitespace() && !glyph.c.is_control();
+    let (Some(first), Some(last)) = (res.glyphs.iter().position(is_ink), res.glyphs.iter().rposition(is_ink)) else {
+        return;
+    };
+
+    let gaps = (first..last)
+        .filter(|&index| res.glyphs[index].c.is_whitespace())
+        .collect::<Vec<_>>();
+    let inter_word = match mode {
+        TextJustify::Auto => !gaps.is_empty(),
+        TextJustify::InterWord => true,
+        TextJustify::InterCharacter => false,
+    };
+    let stretched = if inter_word {
+        gaps
+    } else {
+        // The last glyph of every cluster but the final one.
+        (first..last)
+            .filter(|&index| res.glyphs[index].byte_index != res.glyphs[index + 1].byte_index)
+            .collect()
+    };
+    if stretched.is_empty() {
+        return;
+    }
+
+    let share = extra / stretched.len() as f32;
+    for index in stretched {
+        let glyph = &mut res.glyphs[index];
+        if vertical {
+            glyph.advance_y += share;
+        } else {
+            glyph.advance_x += share;
+        }
+    }
+    res.width = width;
+}
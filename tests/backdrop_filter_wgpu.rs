@@ -0,0 +1,79 @@
+//! Headless GPU tests for `Canvas::backdrop_filter`: a red quadrant on white
+//! is filtered under a panel, on the screen and inside an image render
+//! target, then probed. Skips when no GPU adapter is available.
+#![cfg(feature = "wgpu")]
+
+use femtovg::{renderer::WGPURenderer, Canvas, Color, ImageFilter, Paint, Path};
+
+mod common;
+
+use common::{assert_pixel, draw_source, headless_device, render, show_image, H, RED, W, WHITE};
+
+// Paints the top-left quadrant red over white, then filters the backdrop of
+// the panel from (4, 2) to (28, 22), drawn as a half-size rect under a 2x
+// scale so the path goes through the transform. The panel is off-center so
+// a flipped copy is caught.
+fn draw_scene(canvas: &mut Canvas<WGPURenderer>, filter: ImageFilter) {
+    canvas.clear_rect(0, 0, W, H, Color::white());
+    let mut quadrant = Path::new();
+    quadrant.rect(0.0, 0.0, 16.0, 16.0);
+    canvas.fill_path(&quadrant, &Paint::color(Color::rgb(255, 0, 0)).with_anti_alias(false));
+
+    canvas.save();
+    canvas.scale(2.0, 2.0);
+    let mut panel = Path::new();
+    panel.rect(2.0, 1.0, 12.0, 10.0);
+    canvas.backdrop_filter(&panel, filter);
+    canvas.restore();
+}
+
+/// Renders the scene straight to the output, or into an image render target
+/// that is then drawn to the output, and returns the output pixels, row by
+/// row.
+fn render_scene(device: &wgpu::Device, queue: &wgpu::Queue, filter: ImageFilter, via_image: bool) -> Vec<[u8; 4]> {
+    render(device, queue, |canvas| {
+        if via_image {
+            let image = draw_source(canvas, |canvas| draw_scene(canvas, filter));
+            show_image(canvas, image);
+        } else {
+            draw_scene(canvas, filter);
+        }
+    })
+}
+
+#[test]
+fn backdrop_filter() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no wgpu adapter available");
+        return;
+    };
+
+    for via_image in [false, true] {
+        let target = if via_image { "image" } else { "screen" };
+
+        // A single pass: only the backdrop inside the panel turns gray, and
+        // the red stays in the top-left quadrant.
+        let out = render_scene(&device, &queue, ImageFilter::grayscale(1.0), via_image);
+        assert_pixel(&out, 8, 8, [54, 54, 54], target);
+        assert_pixel(&out, 2, 2, RED, target);
+        assert_pixel(&out, 8, 24, WHITE, target);
+        assert_pixel(&out, 24, 8, WHITE, target);
+        assert_pixel(&out, 24, 24, WHITE, target);
+
+        // The blur mixes the quadrant edge inside the panel only.
+        let out = render_scene(&device, &queue, ImageFilter::GaussianBlur { sigma: 2.0 }, via_image);
+        assert_pixel(&out, 8, 8, RED, target);
+        assert_pixel(&out, 24, 24, WHITE, target);
+        assert_pixel(&out, 8, 24, WHITE, target);
+        let edge = out[8 * W as usize + 16];
+        assert!(
+            (100..=160).contains(&edge[1]) && edge[0] == 255,
+            "{target}: the blurred quadrant edge is {edge:?}"
+        );
+        let outside = out[25 * W as usize + 16];
+        assert!(
+            outside[1] > 250,
+            "{target}: the blur must not spill outside the panel, got {outside:?}"
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! Headless GPU tests for closed-form box shadows: the drop shadow of a solid
+//! rounded rectangle and `Canvas::draw_box_shadow` are drawn on a transparent
+//! canvas and their alpha compared with a brute-force Gaussian blur of the
+//! shape. Skips when no GPU adapter is available.
+#![cfg(feature = "wgpu")]
+
+use femtovg::{renderer::WGPURenderer, BoxShadow, Canvas, Color, Paint, Path};
+
+const W: u32 = 64;
+const H: u32 = 64;
+
+fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::default();
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::default(),
+        force_fallback_adapter: false,
+        compatible_surface: None,
+        ..Default::default()
+    }))
+    .ok()?;
+    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        label: Some("femtovg box shadow test device"),
+        required_features: wgpu::Features::empty(),
+        required_limits: wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
+        experimental_features: wgpu::ExperimentalFeatures::disabled(),
+        memory_hints: wgpu::MemoryHints::MemoryUsage,
+        trace: wgpu::Trace::default(),
+    }))
+    .ok()?;
+    Some((device, queue))
+}
+
+/// Lets `draw` paint on a transparent canvas and returns the alpha of the
+/// output pixels, row by row.
+fn render(device: &wgpu::Device, queue: &wgpu::Queue, draw: impl FnOnce(&mut Canvas<WGPURenderer>)) -> Vec<u8> {
+    let target = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("box shadow out"),
+        size: wgpu::Extent3d {
+            width: W,
+            height: H,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+
+    let renderer = WGPURenderer::new(device.clone(), queue.clone());
+    let mut canvas = Canvas::new(renderer).expect("canvas");
+    canvas.set_size(W, H, 1.0);
+    canvas.clear_rect(0, 0, W, H, Color::rgba(0, 0, 0, 0));
+
+    draw(&mut canvas);
+
+    let commands = canvas.flush_to_output(&target);
+    queue.submit(commands);
+
+    let unpadded = W * 4;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let padded = unpadded.div_ceil(align) * align;
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: (padded * H) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut enc = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    enc.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture: &target,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &readback,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded),
+                rows_per_image: Some(H),
+            },
+        },
+        wgpu::Extent3d {
+            width: W,
+            height: H,
+            depth_or_array_layers: 1,
+        },
+    );
+    queue.submit(Some(enc.finish()));
+    let slice = readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| {});
+    device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+    let mapped = slice.get_mapped_range().expect("readback");
+    (0..H as usize)
+        .flat_map(|y| (0..W as usize).map(move |x| (x, y)))
+        .map(|(x, y)| mapped[y * padded as usize + x * 4 + 3])
+        .collect()
+}
+
+/// The coverage at pixel (`x`, `y`) of the rounded rectangle `(x, y, width,
+/// height, radius)` blurred with a Gaussian of standard deviation `sigma`,
+/// summed over a fine grid.
+fn blurred_coverage(x: u32, y: u32, (rx, ry, rw, rh, r): (f32, f32, f32, f32, f32), sigma: f32) -> f32 {
+    let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+    let (cx, cy) = (rx + rw * 0.5, ry + rh * 0.5);
+    let inside = |qx: f32, qy: f32| {
+        let (ax, ay) = ((qx - cx).abs(), (qy - cy).abs());
+        let dx = (ax - (rw * 0.5 - r)).max(0.0);
+        let dy = (ay - (rh * 0.5 - r)).max(0.0);
+        ax <= rw * 0.5 && ay <= rh * 0.5 && dx * dx + dy * dy <= r * r
+    };
+
+    let step = sigma / 16.0;
+    let n = 64;
+    let (mut covered, mut total) = (0.0, 0.0);
+    for i in -n..=n {
+        for j in -n..=n {
+            let (ox, oy) = (i as f32 * step, j as f32 * step);
+            let weight = (-(ox * ox + oy * oy) / (2.0 * sigma * sigma)).exp();
+            total += weight;
+            if inside(px + ox, py + oy) {
+                covered += weight;
+            }
+        }
+    }
+    covered / total
+}
+
+fn assert_alpha(pixels: &[u8], x: u32, y: u32, expected: f32, what: &str) {
+    let alpha = pixels[(y * W + x) as usize];
+    let expected = expected * 255.0;
+    assert!(
+        (alpha as f32 - expected).abs() <= 4.0,
+        "{what}: alpha at ({x}, {y}) is {alpha}, expected {expected:.1}"
+    );
+}
+
+// Probes along a row through the shape, the corner diagonal, and the centre.
+fn probes() -> impl Iterator<Item = (u32, u32)> {
+    (0..W)
+        .step_by(3)
+        .map(|x| (x, 30))
+        .chain((4..32).step_by(3).map(|i| (i, i)))
+        .chain([(32, 32)])
+}
+
+#[test]
+fn rounded_rect_drop_shadow() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no wgpu adapter available");
+        return;
+    };
+
+    // The shape is off screen and only its shadow is offset onto it. A solid
+    // color is shadowed in closed form, a gradient (of one color) offscreen,
+    // and both must match the blurred shape.
+    let shape = (14.0, 16.0, 36.0, 28.0, 8.0);
+    let paints = [
+        Paint::color(Color::rgb(255, 0, 0)),
+        Paint::linear_gradient(0.0, 0.0, 1.0, 0.0, Color::rgb(255, 0, 0), Color::rgb(255, 0, 0)),
+    ];
+    for (paint, what) in paints.iter().zip(["closed form", "offscreen"]) {
+        let out = render(&device, &queue, |canvas| {
+            canvas.set_shadow_color(Color::black());
+            canvas.set_shadow_blur(8.0);
+            canvas.set_shadow_offset(100.0, 0.0);
+            let mut path = Path::new();
+            path.rounded_rect(shape.0 - 100.0, shape.1, shape.2, shape.3, shape.4);
+            canvas.fill_path(&path, paint);
+        });
+        // Antialiasing blurs the edge by about a one pixel box filter first.
+        let sigma = 4f32.hypot(12f32.sqrt().recip());
+        for (x, y) in probes() {
+            assert_alpha(&out, x, y, blurred_coverage(x, y, shape, sigma), what);
+        }
+    }
+}
+
+#[test]
+fn draw_box_shadow() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no wgpu adapter available");
+        return;
+    };
+
+    // The signed distance from the box's edge, to skip its antialiased pixels.
+    let box_distance = |x: u32, y: u32| {
+        let (dx, dy) = (
+            (x as f32 + 0.5 - 32.0).abs() - 10.0,
+            (y as f32 + 0.5 - 30.0).abs() - 6.0,
+        );
+        dx.max(0.0).hypot(dy.max(0.0)) + dx.max(dy).min(0.0) - 6.0
+    };
+
+    // An outer shadow is the box grown by the spread and offset, and is not
+    // painted inside the box.
+    let shadow = BoxShadow::new(2.0, -1.0, 6.0, Color::black()).with_spread(3.0);
+    let out = render(&device, &queue, |canvas| {
+        canvas.draw_box_shadow(16.0, 18.0, 32.0, 24.0, 6.0, &shadow);
+    });
+    for (x, y) in probes().filter(|&(x, y)| box_distance(x, y).abs() > 1.0) {
+        let expected = if box_distance(x, y) < 0.0 {
+            0.0
+        } else {
+            blurred_coverage(x, y, (15.0, 14.0, 38.0, 30.0, 9.0), 3.0)
+        };
+        assert_alpha(&out, x, y, expected, "outer");
+    }
+
+    // An inset shadow surrounds the box shrunk by the spread and offset, and
+    // is only painted inside the box.
+    let out = render(&device, &queue, |canvas| {
+        canvas.draw_box_shadow(16.0, 18.0, 32.0, 24.0, 6.0, &shadow.with_inset(true));
+    });
+    for (x, y) in probes().filter(|&(x, y)| box_distance(x, y).abs() > 1.0) {
+        let expected = if box_distance(x, y) < 0.0 {
+            1.0 - blurred_coverage(x, y, (21.0, 20.0, 26.0, 18.0, 3.0), 3.0)
+        } else {
+            0.0
+        };
+        assert_alpha(&out, x, y, expected, "inset");
+    }
+}
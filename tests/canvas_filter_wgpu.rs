@@ -0,0 +1,94 @@
+//! Headless GPU tests for `Canvas::set_filter`: shapes drawn with a canvas
+//! filter are rendered through an offscreen pass and composited over white,
+//! then probed. Skips when no GPU adapter is available.
+#![cfg(feature = "wgpu")]
+
+use femtovg::{renderer::WGPURenderer, Canvas, Color, ImageFilter, Paint, Path};
+
+mod common;
+
+use common::{assert_pixel, headless_device, render, H, W, WHITE};
+
+/// Clears a white canvas, lets `draw` paint on it and returns the output
+/// pixels, row by row.
+fn render_on_white(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    draw: impl FnOnce(&mut Canvas<WGPURenderer>),
+) -> Vec<[u8; 4]> {
+    render(device, queue, |canvas| {
+        canvas.clear_rect(0, 0, W, H, Color::white());
+        draw(canvas);
+    })
+}
+
+// A wide bar over a narrow one in a single draw, so a flipped result is
+// caught.
+fn draw_bars(canvas: &mut Canvas<WGPURenderer>) {
+    let mut bars = Path::new();
+    bars.rect(8.0, 4.0, 16.0, 12.0);
+    bars.rect(8.0, 16.0, 4.0, 12.0);
+    canvas.fill_path(&bars, &Paint::color(Color::rgb(255, 0, 0)).with_anti_alias(false));
+}
+
+#[test]
+fn canvas_filter() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no wgpu adapter available");
+        return;
+    };
+
+    let out = render_on_white(&device, &queue, draw_bars);
+    assert_pixel(&out, 20, 8, [255, 0, 0], "unfiltered");
+    assert_pixel(&out, 20, 24, WHITE, "unfiltered");
+
+    // One color matrix pass: gray by luma, upright.
+    let out = render_on_white(&device, &queue, |canvas| {
+        canvas.set_filter(&ImageFilter::parse_css("grayscale(100%)").unwrap());
+        draw_bars(canvas);
+    });
+    assert_pixel(&out, 20, 8, [54, 54, 54], "grayscale");
+    assert_pixel(&out, 10, 24, [54, 54, 54], "grayscale");
+    assert_pixel(&out, 20, 24, WHITE, "grayscale");
+
+    // Two passes, applied in order, still upright.
+    let out = render_on_white(&device, &queue, |canvas| {
+        canvas.set_filter(&ImageFilter::parse_css("grayscale(1) brightness(200%)").unwrap());
+        draw_bars(canvas);
+    });
+    assert_pixel(&out, 20, 8, [108, 108, 108], "chain");
+    assert_pixel(&out, 20, 24, WHITE, "chain");
+
+    // The blur spreads past the shape, and the global alpha applies to the
+    // filtered result.
+    let out = render_on_white(&device, &queue, |canvas| {
+        canvas.set_filter(&[ImageFilter::GaussianBlur { sigma: 2.0 }]);
+        canvas.set_global_alpha(0.5);
+        let mut square = Path::new();
+        square.rect(8.0, 8.0, 16.0, 16.0);
+        canvas.fill_path(&square, &Paint::color(Color::rgb(255, 0, 0)));
+    });
+    assert_pixel(&out, 16, 16, [255, 128, 128], "blur");
+    let edge = out[16 * W as usize + 6];
+    assert!(
+        edge[1] < 250 && edge[1] > 200,
+        "blur must reach past the shape, got {edge:?}"
+    );
+
+    // A zero blur is skipped rather than wiping out the draw.
+    let out = render_on_white(&device, &queue, |canvas| {
+        canvas.set_filter(&ImageFilter::parse_css("blur(0px)").unwrap());
+        draw_bars(canvas);
+    });
+    assert_pixel(&out, 20, 8, [255, 0, 0], "zero blur");
+    assert_pixel(&out, 20, 24, WHITE, "zero blur");
+
+    // The filter is part of the saved state.
+    let out = render_on_white(&device, &queue, |canvas| {
+        canvas.save();
+        canvas.set_filter(&[ImageFilter::invert(1.0)]);
+        canvas.restore();
+        draw_bars(canvas);
+    });
+    assert_pixel(&out, 20, 8, [255, 0, 0], "restored");
+}
@@ -0,0 +1,182 @@
+//! Headless GPU tests for COLR color glyphs.
+#![cfg(feature = "wgpu")]
+
+use femtovg::{renderer::WGPURenderer, Canvas, Color, Paint, PositionedGlyph};
+
+const W: u32 = 400;
+const H: u32 = 100;
+const FONT: &[u8] = include_bytes!("../examples/assets/colr_1.ttf");
+
+fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::default();
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::default(),
+        force_fallback_adapter: false,
+        compatible_surface: None,
+        ..Default::default()
+    }))
+    .ok()?;
+    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        label: Some("femtovg color font test device"),
+        required_features: wgpu::Features::empty(),
+        required_limits: wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
+        experimental_features: wgpu::ExperimentalFeatures::disabled(),
+        memory_hints: wgpu::MemoryHints::MemoryUsage,
+        trace: wgpu::Trace::default(),
+    }))
+    .ok()?;
+    Some((device, queue))
+}
+
+/// Clears the canvas white, runs `draw` with the test font loaded, and returns
+/// the row-major `[r, g, b, a]` pixels.
+fn render(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    draw: impl FnOnce(&mut Canvas<WGPURenderer>, femtovg::FontId),
+) -> Vec<u8> {
+    let target = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("color font target"),
+        size: wgpu::Extent3d {
+            width: W,
+            height: H,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+
+    let renderer = WGPURenderer::new(device.clone(), queue.clone());
+    let mut canvas = Canvas::new(renderer).expect("failed to create canvas");
+    canvas.set_size(W, H, 1.0);
+    canvas.clear_rect(0, 0, W, H, Color::white());
+    let font = canvas.add_font_mem(FONT).expect("failed to load font");
+    draw(&mut canvas, font);
+
+    let commands = canvas.flush_to_output(&target);
+    queue.submit(commands);
+
+    let unpadded_bytes_per_row = W * 4;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("color font readback"),
+        size: (padded_bytes_per_row * H) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture: &target,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &readback,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_bytes_per_row),
+                rows_per_image: Some(H),
+            },
+        },
+        wgpu::Extent3d {
+            width: W,
+            height: H,
+            depth_or_array_layers: 1,
+        },
+    );
+    queue.submit(Some(encoder.finish()));
+
+    let slice = readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| {});
+    device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+    let mapped = slice.get_mapped_range().expect("mapped readback range");
+    let mut pixels = vec![0u8; (unpadded_bytes_per_row * H) as usize];
+    for row in 0..H as usize {
+        let src = row * padded_bytes_per_row as usize;
+        let dst = row * unpadded_bytes_per_row as usize;
+        pixels[dst..dst + unpadded_bytes_per_row as usize]
+            .copy_from_slice(&mapped[src..src + unpadded_bytes_per_row as usize]);
+    }
+    drop(mapped);
+    readback.unmap();
+    pixels
+}
+
+/// Draws `glyph_id` at the left edge of the canvas with an 80px font.
+fn render_glyph(device: &wgpu::Device, queue: &wgpu::Queue, glyph_id: u16, paint: &Paint) -> Vec<u8> {
+    render(device, queue, |canvas, font| {
+        let glyph = PositionedGlyph {
+            x: 10.0,
+            y: 85.0,
+            glyph_id,
+        };
+        canvas
+            .fill_glyph_run(font, &[], [glyph], &paint.clone().with_font_size(80.0))
+            .expect("fill_glyph_run failed");
+    })
+}
+
+fn pixel(pixels: &[u8], x: u32, y: u32) -> [u8; 4] {
+    let i = ((y * W + x) * 4) as usize;
+    [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+}
+
+#[test]
+fn palette_selects_layer_colors() {
+    let Some((device, queue)) = headless_device() else {
+        return;
+    };
+
+    // colored_circles_v0: a COLRv0 glyph whose layers use palette entries.
+    let paint = Paint::color(Color::black());
+    let first = render_glyph(&device, &queue, 168, &paint);
+    let second = render_glyph(&device, &queue, 168, &paint.clone().with_font_palette(1));
+
+    let a = pixel(&first, 45, 37);
+    let b = pixel(&second, 45, 37);
+    assert_ne!(a, [255, 255, 255, 255], "glyph not drawn");
+    assert_ne!(b, [255, 255, 255, 255], "glyph not drawn");
+    assert_ne!(a, b, "palette 1 should change layer colors");
+}
+
+#[test]
+fn foreground_layers_use_paint_color() {
+    let Some((device, queue)) = headless_device() else {
+        return;
+    };
+
+    // foreground_color solid at alpha 1.0.
+    let pixels = render_glyph(&device, &queue, 154, &Paint::color(Color::rgb(0, 160, 0)));
+    let [r, g, b, _] = pixel(&pixels, 40, 37);
+    assert!(r < 8 && (152..=168).contains(&g) && b < 8, "got {:?}", [r, g, b]);
+}
+
+#[test]
+fn gradient_paints_are_rendered() {
+    let Some((device, queue)) = headless_device() else {
+        return;
+    };
+
+    // Linear pad, radial pad and sweep gradients.
+    for glyph_id in [90, 93, 12] {
+        let pixels = render_glyph(&device, &queue, glyph_id, &Paint::color(Color::black()));
+        let mut colors: Vec<[u8; 4]> = (0..100)
+            .flat_map(|x| (0..100).map(move |y| (x, y)))
+            .map(|(x, y)| pixel(&pixels, x, y))
+            .collect();
+        colors.sort_unstable();
+        colors.dedup();
+        assert!(
+            colors.len() > 20,
+            "glyph {glyph_id} has only {} distinct colors",
+            colors.len()
+        );
+    }
+}
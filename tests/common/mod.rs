@@ -0,0 +1,149 @@
+//! Fixture shared by the headless GPU filter tests: a device, a 32x32 canvas
+//! flushed to an offscreen texture and read back, and per-pixel probes.
+#![allow(dead_code)]
+
+use femtovg::{renderer::WGPURenderer, Canvas, Color, ImageFlags, ImageId, Paint, Path, PixelFormat, RenderTarget};
+
+pub const W: u32 = 32;
+pub const H: u32 = 32;
+
+pub const RED: [u8; 3] = [255, 0, 0];
+pub const BLUE: [u8; 3] = [0, 0, 255];
+pub const WHITE: [u8; 3] = [255, 255, 255];
+
+pub fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::default();
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::default(),
+        force_fallback_adapter: false,
+        compatible_surface: None,
+        ..Default::default()
+    }))
+    .ok()?;
+    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        label: Some("femtovg filter test device"),
+        required_features: wgpu::Features::empty(),
+        required_limits: wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
+        experimental_features: wgpu::ExperimentalFeatures::disabled(),
+        memory_hints: wgpu::MemoryHints::MemoryUsage,
+        trace: wgpu::Trace::default(),
+    }))
+    .ok()?;
+    Some((device, queue))
+}
+
+/// Lets `draw` paint on a fresh canvas, flushes it to an offscreen texture
+/// and returns the output pixels, row by row.
+pub fn render(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    draw: impl FnOnce(&mut Canvas<WGPURenderer>),
+) -> Vec<[u8; 4]> {
+    let target = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("filter test out"),
+        size: wgpu::Extent3d {
+            width: W,
+            height: H,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+
+    let renderer = WGPURenderer::new(device.clone(), queue.clone());
+    let mut canvas = Canvas::new(renderer).expect("canvas");
+    canvas.set_size(W, H, 1.0);
+
+    draw(&mut canvas);
+
+    let commands = canvas.flush_to_output(&target);
+    queue.submit(commands);
+
+    let unpadded = W * 4;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let padded = unpadded.div_ceil(align) * align;
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size: (padded * H) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut enc = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    enc.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture: &target,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &readback,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded),
+                rows_per_image: Some(H),
+            },
+        },
+        wgpu::Extent3d {
+            width: W,
+            height: H,
+            depth_or_array_layers: 1,
+        },
+    );
+    queue.submit(Some(enc.finish()));
+    let slice = readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| {});
+    device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+    let mapped = slice.get_mapped_range().expect("readback");
+    (0..H as usize)
+        .flat_map(|y| (0..W as usize).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let i = y * padded as usize + x * 4;
+            [mapped[i], mapped[i + 1], mapped[i + 2], mapped[i + 3]]
+        })
+        .collect()
+}
+
+/// Creates a premultiplied, bottom-up image the size of the canvas, lets
+/// `draw` paint on it over transparent black and returns it.
+pub fn draw_source(canvas: &mut Canvas<WGPURenderer>, draw: impl FnOnce(&mut Canvas<WGPURenderer>)) -> ImageId {
+    let flags = ImageFlags::PREMULTIPLIED | ImageFlags::FLIP_Y;
+    let image = canvas
+        .create_image_empty(W as usize, H as usize, PixelFormat::Rgba8, flags)
+        .expect("source image");
+    canvas.set_render_target(RenderTarget::Image(image));
+    canvas.clear_rect(0, 0, W, H, Color::rgbaf(0.0, 0.0, 0.0, 0.0));
+    draw(canvas);
+    canvas.set_render_target(RenderTarget::Screen);
+    image
+}
+
+/// Fills the rect `[x, y, w, h]` with `color`, without antialiasing.
+pub fn fill_rect(canvas: &mut Canvas<WGPURenderer>, [x, y, w, h]: [f32; 4], color: Color) {
+    let mut rect = Path::new();
+    rect.rect(x, y, w, h);
+    canvas.fill_path(&rect, &Paint::color(color).with_anti_alias(false));
+}
+
+/// Clears the screen white and draws `image` over all of it.
+pub fn show_image(canvas: &mut Canvas<WGPURenderer>, image: ImageId) {
+    canvas.clear_rect(0, 0, W, H, Color::white());
+    let mut p = Path::new();
+    p.rect(0.0, 0.0, W as f32, H as f32);
+    canvas.fill_path(&p, &Paint::image(image, 0.0, 0.0, W as f32, H as f32, 0.0, 1.0));
+}
+
+pub fn assert_pixel(pixels: &[[u8; 4]], x: usize, y: usize, expected: [u8; 3], what: &str) {
+    let pixel = pixels[y * W as usize + x];
+    assert!(
+        pixel
+            .iter()
+            .zip(expected)
+            .all(|(a, b)| (*a as i32 - b as i32).abs() <= 3),
+        "{what}: pixel ({x}, {y}) is {pixel:?}, expected {expected:?}"
+    );
+}
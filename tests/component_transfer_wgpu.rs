@@ -0,0 +1,104 @@
+//! Headless GPU tests for `ImageFilter::ComponentTransfer`: a colored square
+//! is remapped through each kind of transfer function, the result drawn over
+//! white and probed. Skips when no GPU adapter is available.
+#![cfg(feature = "wgpu")]
+
+use femtovg::{Color, ComponentTransfer, ImageFilter, ImageFlags, PixelFormat, TransferFunction};
+
+mod common;
+
+use common::{assert_pixel, draw_source, fill_rect, headless_device, render, show_image, H, W, WHITE};
+
+/// Fills a square of a source image with `color`, remaps it through
+/// `transfer`, draws the result over white and returns the output pixels,
+/// row by row.
+fn run_filter(device: &wgpu::Device, queue: &wgpu::Queue, color: Color, transfer: &ComponentTransfer) -> Vec<[u8; 4]> {
+    render(device, queue, |canvas| {
+        let source = draw_source(canvas, |canvas| fill_rect(canvas, [8.0, 8.0, 16.0, 16.0], color));
+        // A single pass turns the bottom-up render target rows upright.
+        let filtered = canvas
+            .create_image_empty(W as usize, H as usize, PixelFormat::Rgba8, ImageFlags::PREMULTIPLIED)
+            .expect("target image");
+        let filter = ImageFilter::ComponentTransfer {
+            transfer: transfer.clone(),
+        };
+        canvas.filter_image(filtered, filter, source);
+        show_image(canvas, filtered);
+    })
+}
+
+#[test]
+fn component_transfer_filter() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no wgpu adapter available");
+        return;
+    };
+    let orange = Color::rgb(200, 100, 50);
+    let run = |color, transfer: ComponentTransfer| run_filter(&device, &queue, color, &transfer);
+
+    // Identity leaves the image unchanged, transparent pixels included.
+    let out = run(orange, ComponentTransfer::default());
+    assert_pixel(&out, 12, 12, [200, 100, 50], "identity");
+    assert_pixel(&out, 4, 4, WHITE, "identity");
+
+    // A two-value table inverts the colors.
+    let out = run(orange, ComponentTransfer::rgb(TransferFunction::Table(vec![1.0, 0.0])));
+    assert_pixel(&out, 12, 12, [55, 155, 205], "table");
+
+    // Two discrete steps posterize to a threshold at one half.
+    let out = run(
+        orange,
+        ComponentTransfer::rgb(TransferFunction::Discrete(vec![0.0, 1.0])),
+    );
+    assert_pixel(&out, 12, 12, [255, 0, 0], "discrete");
+
+    let out = run(
+        orange,
+        ComponentTransfer::rgb(TransferFunction::Linear {
+            slope: 0.5,
+            intercept: 0.25,
+        }),
+    );
+    assert_pixel(&out, 12, 12, [164, 114, 89], "linear");
+
+    let out = run(
+        orange,
+        ComponentTransfer::rgb(TransferFunction::Gamma {
+            amplitude: 1.0,
+            exponent: 2.0,
+            offset: 0.0,
+        }),
+    );
+    assert_pixel(&out, 12, 12, [157, 39, 10], "gamma");
+
+    // Each channel has its own function.
+    let out = run(
+        orange,
+        ComponentTransfer {
+            red: TransferFunction::Linear {
+                slope: 0.0,
+                intercept: 0.0,
+            },
+            blue: TransferFunction::Linear {
+                slope: 0.0,
+                intercept: 1.0,
+            },
+            ..Default::default()
+        },
+    );
+    assert_pixel(&out, 12, 12, [0, 100, 255], "per channel");
+
+    // Colors are remapped unpremultiplied: doubling the alpha of a half
+    // transparent square makes it opaque with its own color.
+    let out = run(
+        Color::rgba(200, 100, 50, 128),
+        ComponentTransfer {
+            alpha: TransferFunction::Linear {
+                slope: 2.0,
+                intercept: 0.0,
+            },
+            ..Default::default()
+        },
+    );
+    assert_pixel(&out, 12, 12, [200, 100, 50], "unpremultiplied");
+}
@@ -0,0 +1,112 @@
+//! Headless GPU tests for `ImageFilter::Convolve`: rectangles are convolved,
+//! the result drawn over white and probed per pixel. Shift kernels check the
+//! SVG kernel rotation, the orientation of the rows and the edge modes. Skips
+//! when no GPU adapter is available.
+#![cfg(feature = "wgpu")]
+
+use femtovg::{Color, EdgeMode, ImageFilter, ImageFlags, PixelFormat};
+
+mod common;
+
+use common::{assert_pixel, draw_source, fill_rect, headless_device, render, show_image, BLUE, H, RED, W, WHITE};
+
+/// Fills `rects` of a source image, runs `filter` on it, draws the result over
+/// white and returns the output pixels, row by row.
+fn run_filter(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    rects: &[([f32; 4], Color)],
+    filter: ImageFilter,
+) -> Vec<[u8; 4]> {
+    render(device, queue, |canvas| {
+        let source = draw_source(canvas, |canvas| {
+            for &(rect, color) in rects {
+                fill_rect(canvas, rect, color);
+            }
+        });
+        // A single pass turns the bottom-up render target rows upright.
+        let filtered = canvas
+            .create_image_empty(W as usize, H as usize, PixelFormat::Rgba8, ImageFlags::PREMULTIPLIED)
+            .expect("target image");
+        canvas.filter_image(filtered, filter, source);
+        show_image(canvas, filtered);
+    })
+}
+
+fn convolve(values: &[f32], size: (usize, usize), edge_mode: EdgeMode, bias: f32, preserve_alpha: bool) -> ImageFilter {
+    let mut kernel = [0.0; 25];
+    kernel[..values.len()].copy_from_slice(values);
+    ImageFilter::Convolve {
+        kernel,
+        size,
+        divisor: 0.0,
+        bias,
+        edge_mode,
+        preserve_alpha,
+    }
+}
+
+#[test]
+fn convolve_filter() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no wgpu adapter available");
+        return;
+    };
+    let red = Color::rgb(255, 0, 0);
+    let square = [([4.0, 4.0, 8.0, 8.0], red)];
+    let run = |rects: &[([f32; 4], Color)], filter| run_filter(&device, &queue, rects, filter);
+
+    // The kernel is rotated, so a weight in the first row samples the pixel
+    // below and moves the image up.
+    let out = run(&square, ImageFilter::convolve(&[1.0, 0.0, 0.0], 1, 3));
+    assert_pixel(&out, 5, 3, RED, "shift up");
+    assert_pixel(&out, 5, 10, RED, "shift up");
+    assert_pixel(&out, 5, 11, WHITE, "shift up");
+
+    // Likewise a weight in the first column moves it left.
+    let out = run(&square, ImageFilter::convolve(&[1.0, 0.0, 0.0], 3, 1));
+    assert_pixel(&out, 3, 5, RED, "shift left");
+    assert_pixel(&out, 11, 5, WHITE, "shift left");
+
+    // A sharpening kernel keeps flat areas unchanged.
+    let sharpen = [0.0, -1.0, 0.0, -1.0, 5.0, -1.0, 0.0, -1.0, 0.0];
+    let out = run(&square, ImageFilter::convolve(&sharpen, 3, 3));
+    assert_pixel(&out, 8, 8, RED, "sharpen");
+    assert_pixel(&out, 20, 20, WHITE, "sharpen");
+
+    // A box blur divides by the kernel sum by default: the square's corner
+    // keeps 4 of 9 samples.
+    let out = run(&square, ImageFilter::convolve(&[1.0; 9], 3, 3));
+    assert_pixel(&out, 4, 4, [255, 142, 142], "box blur");
+    assert_pixel(&out, 8, 8, RED, "box blur");
+
+    // preserve_alpha convolves the unpremultiplied colors only.
+    let out = run(&square, convolve(&[1.0; 9], (3, 3), EdgeMode::Duplicate, 0.0, true));
+    assert_pixel(&out, 4, 4, [113, 0, 0], "preserve alpha");
+    assert_pixel(&out, 3, 3, WHITE, "preserve alpha");
+
+    // The bias is added to every channel.
+    let out = run(&square, convolve(&[1.0; 9], (3, 3), EdgeMode::Duplicate, 0.5, true));
+    assert_pixel(&out, 8, 8, [255, 128, 128], "bias");
+
+    // Shifting right reads past the left edge.
+    let halves = [
+        ([0.0, 0.0, 16.0, H as f32], red),
+        ([16.0, 0.0, 16.0, H as f32], Color::rgb(0, 0, 255)),
+    ];
+    let shift_right = |edge_mode| convolve(&[0.0, 0.0, 1.0], (3, 1), edge_mode, 0.0, false);
+    let out = run(&halves, shift_right(EdgeMode::Duplicate));
+    assert_pixel(&out, 0, 5, RED, "duplicate");
+    assert_pixel(&out, 16, 5, RED, "duplicate");
+    assert_pixel(&out, 17, 5, BLUE, "duplicate");
+    let out = run(&halves, shift_right(EdgeMode::Wrap));
+    assert_pixel(&out, 0, 5, BLUE, "wrap");
+    assert_pixel(&out, 1, 5, RED, "wrap");
+    let out = run(&halves, shift_right(EdgeMode::None));
+    assert_pixel(&out, 0, 5, WHITE, "none");
+    assert_pixel(&out, 1, 5, RED, "none");
+
+    // Kernels with more than 25 values render transparent black.
+    let out = run(&square, ImageFilter::convolve(&[1.0; 25], 6, 5));
+    assert_pixel(&out, 8, 8, WHITE, "oversized kernel");
+}
@@ -0,0 +1,100 @@
+//! Headless GPU tests for `ImageFilter::DisplacementMap`: rectangles are
+//! displaced by uniform maps, the result drawn over white and probed per
+//! pixel. Skips when no GPU adapter is available.
+#![cfg(feature = "wgpu")]
+
+use femtovg::{Color, ColorChannel, ImageFilter, ImageFlags, ImageId, PixelFormat};
+
+mod common;
+
+use common::{assert_pixel, draw_source, fill_rect, headless_device, render, show_image, H, RED, W, WHITE};
+
+/// Fills `rects` of a source image and the whole map with `map_color`,
+/// displaces the source by the map, draws the result over white and returns
+/// the output pixels, row by row.
+fn run_filter(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    rects: &[[f32; 4]],
+    map_color: Color,
+    filter: impl Fn(ImageId) -> ImageFilter,
+) -> Vec<[u8; 4]> {
+    render(device, queue, |canvas| {
+        let source = draw_source(canvas, |canvas| {
+            for &rect in rects {
+                fill_rect(canvas, rect, Color::rgb(255, 0, 0));
+            }
+        });
+        let map = draw_source(canvas, |canvas| {
+            fill_rect(canvas, [0.0, 0.0, W as f32, H as f32], map_color)
+        });
+        // A single pass turns the bottom-up render target rows upright.
+        let filtered = canvas
+            .create_image_empty(W as usize, H as usize, PixelFormat::Rgba8, ImageFlags::PREMULTIPLIED)
+            .expect("target image");
+        canvas.filter_image(filtered, filter(map), source);
+        show_image(canvas, filtered);
+    })
+}
+
+fn displace(scale: f32, x_channel: ColorChannel, y_channel: ColorChannel) -> impl Fn(ImageId) -> ImageFilter {
+    move |map| ImageFilter::DisplacementMap {
+        map,
+        scale,
+        x_channel,
+        y_channel,
+    }
+}
+
+#[test]
+fn displacement_map_filter() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no wgpu adapter available");
+        return;
+    };
+    let square = [[8.0, 8.0, 8.0, 8.0]];
+    let run = |rects: &[[f32; 4]], map_color, filter| run_filter(&device, &queue, rects, map_color, filter);
+
+    // A red channel of 1 reads 4 pixels to the right, moving the square left;
+    // a blue channel of 0 reads 4 pixels up, moving it down.
+    let out = run(
+        &square,
+        Color::rgb(255, 128, 0),
+        displace(8.0, ColorChannel::Red, ColorChannel::Blue),
+    );
+    assert_pixel(&out, 4, 12, RED, "displaced");
+    assert_pixel(&out, 11, 19, RED, "displaced");
+    assert_pixel(&out, 12, 12, WHITE, "displaced");
+    assert_pixel(&out, 8, 11, WHITE, "displaced");
+
+    // A mid gray map leaves the image in place, whatever the scale.
+    let out = run(
+        &square,
+        Color::rgb(128, 128, 128),
+        displace(8.0, ColorChannel::Green, ColorChannel::Green),
+    );
+    assert_pixel(&out, 8, 8, RED, "neutral");
+    assert_pixel(&out, 14, 14, RED, "neutral");
+    assert_pixel(&out, 16, 16, WHITE, "neutral");
+
+    // The map is read unpremultiplied: half-transparent red still counts as
+    // a red channel of 1, while the alpha channel of 0.5 displaces nothing.
+    let out = run(
+        &square,
+        Color::rgba(255, 0, 0, 128),
+        displace(8.0, ColorChannel::Red, ColorChannel::Alpha),
+    );
+    assert_pixel(&out, 4, 8, RED, "unpremultiplied");
+    assert_pixel(&out, 12, 8, WHITE, "unpremultiplied");
+    assert_pixel(&out, 4, 6, WHITE, "unpremultiplied");
+
+    // Pixels displaced from beyond the edges are transparent.
+    let out = run(
+        &[[0.0, 0.0, W as f32, H as f32]],
+        Color::rgb(255, 255, 255),
+        displace(8.0, ColorChannel::Red, ColorChannel::Green),
+    );
+    assert_pixel(&out, 27, 27, RED, "edges");
+    assert_pixel(&out, 28, 5, WHITE, "edges");
+    assert_pixel(&out, 5, 28, WHITE, "edges");
+}
@@ -0,0 +1,250 @@
+//! Headless GPU tests for distance-field text rendering.
+//!
+//! Distance-field glyphs are generated once at a reference size and
+//! reconstructed by the `FillDistanceField` shader at any scale and rotation.
+//! These tests check that the reconstructed glyphs cover the same ink as the
+//! regular rasterizer, stay sharp when zoomed far past the reference size, and
+//! that the outline and glow effects paint around the glyphs. Each skips
+//! (prints and returns) when no GPU adapter is available.
+#![cfg(feature = "wgpu")]
+
+use femtovg::{renderer::WGPURenderer, Canvas, Color, Paint, TextEffects};
+
+const W: u32 = 400;
+const H: u32 = 200;
+const FONT: &[u8] = include_bytes!("../examples/assets/RobotoFlex-VariableFont.ttf");
+
+fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::default();
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::default(),
+        force_fallback_adapter: false,
+        compatible_surface: None,
+        ..Default::default()
+    }))
+    .ok()?;
+    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        label: Some("femtovg distance field text test device"),
+        required_features: wgpu::Features::empty(),
+        required_limits: wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
+        experimental_features: wgpu::ExperimentalFeatures::disabled(),
+        memory_hints: wgpu::MemoryHints::MemoryUsage,
+        trace: wgpu::Trace::default(),
+    }))
+    .ok()?;
+    Some((device, queue))
+}
+
+/// Clears the canvas white, runs `draw` with the test font loaded, and returns
+/// the row-major `[r, g, b, a]` pixels.
+fn render(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    draw: impl FnOnce(&mut Canvas<WGPURenderer>, femtovg::FontId),
+) -> Vec<u8> {
+    let target = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("distance field text target"),
+        size: wgpu::Extent3d {
+            width: W,
+            height: H,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+
+    let renderer = WGPURenderer::new(device.clone(), queue.clone());
+    let mut canvas = Canvas::new(renderer).expect("failed to create canvas");
+    canvas.set_size(W, H, 1.0);
+    canvas.clear_rect(0, 0, W, H, Color::white());
+    let font = canvas.add_font_mem(FONT).expect("failed to load font");
+    draw(&mut canvas, font);
+
+    let commands = canvas.flush_to_output(&target);
+    queue.submit(commands);
+
+    let unpadded_bytes_per_row = W * 4;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("distance field text readback"),
+        size: (padded_bytes_per_row * H) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture: &target,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &readback,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_bytes_per_row),
+                rows_per_image: Some(H),
+            },
+        },
+        wgpu::Extent3d {
+            width: W,
+            height: H,
+            depth_or_array_layers: 1,
+        },
+    );
+    queue.submit(Some(encoder.finish()));
+
+    let slice = readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| {});
+    device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+    let mapped = slice.get_mapped_range().expect("mapped readback range");
+    let mut pixels = vec![0u8; (unpadded_bytes_per_row * H) as usize];
+    for row in 0..H as usize {
+        let src = row * padded_bytes_per_row as usize;
+        let dst = row * unpadded_bytes_per_row as usize;
+        pixels[dst..dst + unpadded_bytes_per_row as usize]
+            .copy_from_slice(&mapped[src..src + unpadded_bytes_per_row as usize]);
+    }
+    drop(mapped);
+    readback.unmap();
+    pixels
+}
+
+fn luminance(pixel: &[u8]) -> u32 {
+    (u32::from(pixel[0]) + u32::from(pixel[1]) + u32::from(pixel[2])) / 3
+}
+
+/// Number of pixels darker than mid-gray.
+fn ink(pixels: &[u8]) -> usize {
+    pixels.chunks_exact(4).filter(|p| luminance(p) < 128).count()
+}
+
+/// Number of partially covered pixels, i.e. the antialiased fringe.
+fn fringe(pixels: &[u8]) -> usize {
+    pixels
+        .chunks_exact(4)
+        .filter(|p| (48..208).contains(&luminance(p)))
+        .count()
+}
+
+fn text_paint(font: femtovg::FontId, size: f32) -> Paint {
+    Paint::color(Color::black()).with_font(&[font]).with_font_size(size)
+}
+
+#[test]
+fn distance_field_text_covers_the_same_ink_as_regular_text() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no GPU adapter available");
+        return;
+    };
+    let draw = |distance_field: bool| {
+        move |canvas: &mut Canvas<WGPURenderer>, font| {
+            let paint = text_paint(font, 60.0).with_text_distance_field(distance_field);
+            canvas.fill_text(20.0, 100.0, "Rag&8W", &paint).unwrap();
+        }
+    };
+    let regular = ink(&render(&device, &queue, draw(false)));
+    let distance_field = ink(&render(&device, &queue, draw(true)));
+
+    assert!(regular > 1000, "regular text drew too little ink: {regular}");
+    let ratio = distance_field as f32 / regular as f32;
+    assert!(
+        (0.9..1.1).contains(&ratio),
+        "ink differs: {distance_field} vs {regular}"
+    );
+}
+
+#[test]
+fn distance_field_text_stays_sharp_when_zoomed() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no GPU adapter available");
+        return;
+    };
+    // 20px text magnified 8x is far larger than the field's reference size; a
+    // bitmap atlas would smear each edge across several pixels.
+    let pixels = render(&device, &queue, |canvas, font| {
+        canvas.scale(8.0, 8.0);
+        let paint = text_paint(font, 20.0).with_text_distance_field(true);
+        canvas.fill_text(0.0, 20.0, "Ra", &paint).unwrap();
+    });
+    let (ink, fringe) = (ink(&pixels), fringe(&pixels));
+
+    assert!(ink > 5000, "zoomed text drew too little ink: {ink}");
+    assert!(
+        fringe * 5 < ink,
+        "edges are blurry: {fringe} fringe pixels for {ink} ink"
+    );
+}
+
+#[test]
+fn distance_field_text_renders_rotated() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no GPU adapter available");
+        return;
+    };
+    let draw = |angle: f32| {
+        move |canvas: &mut Canvas<WGPURenderer>, font| {
+            canvas.translate(100.0, 150.0);
+            canvas.rotate(angle);
+            let paint = text_paint(font, 40.0).with_text_distance_field(true);
+            canvas.fill_text(0.0, 0.0, "Rotated", &paint).unwrap();
+        }
+    };
+    let upright = ink(&render(&device, &queue, draw(0.0)));
+    let rotated = ink(&render(&device, &queue, draw(-0.4)));
+
+    let ratio = rotated as f32 / upright as f32;
+    assert!(
+        (0.9..1.1).contains(&ratio),
+        "rotation changed ink: {rotated} vs {upright}"
+    );
+}
+
+#[test]
+fn distance_field_text_effects_paint_outline_and_glow() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no GPU adapter available");
+        return;
+    };
+    let draw = |effects: TextEffects| {
+        move |canvas: &mut Canvas<WGPURenderer>, font| {
+            let paint = Paint::color(Color::rgb(0, 0, 255))
+                .with_font(&[font])
+                .with_font_size(60.0)
+                .with_text_distance_field(true)
+                .with_text_effects(effects);
+            canvas.fill_text(20.0, 100.0, "Glow!", &paint).unwrap();
+        }
+    };
+    let count = |pixels: &[u8], pred: fn(&[u8]) -> bool| pixels.chunks_exact(4).filter(|p| pred(p)).count();
+    let is_blue = |p: &[u8]| p[2] > 200 && p[0] < 60 && p[1] < 60;
+    let is_black = |p: &[u8]| p[0] < 60 && p[1] < 60 && p[2] < 60;
+    let is_red = |p: &[u8]| p[0] > 200 && p[1] < 200 && p[2] < 200 && p[1] == p[2];
+
+    let plain = render(&device, &queue, draw(TextEffects::default()));
+    let styled = render(
+        &device,
+        &queue,
+        draw(TextEffects {
+            outline_width: 3.0,
+            outline_color: Color::black(),
+            glow_radius: 6.0,
+            glow_color: Color::rgb(255, 0, 0),
+        }),
+    );
+    assert!(count(&plain, is_blue) > 500);
+    assert_eq!(count(&plain, is_black), 0);
+    assert_eq!(count(&plain, is_red), 0);
+
+    // The fill is unchanged; the outline and glow add rings around it.
+    let ratio = count(&styled, is_blue) as f32 / count(&plain, is_blue) as f32;
+    assert!((0.9..1.1).contains(&ratio), "outline ate into the fill");
+    assert!(count(&styled, is_black) > 500, "no outline drawn");
+    assert!(count(&styled, is_red) > 500, "no glow drawn");
+}
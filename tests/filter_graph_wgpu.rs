@@ -0,0 +1,269 @@
+//! Headless GPU tests for `Canvas::filter_image_graph`: each SVG primitive is
+//! run on a source holding a red square at (4, 4)-(12, 12) in a 32x32 image,
+//! and the output is drawn over white and probed per pixel. Probes above and
+//! below the square guard the orientation of every intermediate image. Skips
+//! when no GPU adapter is available.
+#![cfg(feature = "wgpu")]
+
+use femtovg::{
+    BlendMode, Color, ColorChannel, ComponentTransfer, EdgeMode, FilterCompositeOperator, FilterEffect, FilterGraph,
+    FilterInput, FilterPrimitive, ImageFlags, MorphologyOperator, PixelFormat, TransferFunction, TurbulenceKind,
+};
+
+mod common;
+
+use common::{assert_pixel, draw_source, fill_rect, headless_device, render, show_image, H, RED, W, WHITE};
+
+/// Runs `graph` on the red-square source, draws the result over white and
+/// returns the output pixels, row by row.
+fn run_graph(device: &wgpu::Device, queue: &wgpu::Queue, graph: &FilterGraph) -> Vec<[u8; 4]> {
+    render(device, queue, |canvas| {
+        let source = draw_source(canvas, |canvas| {
+            fill_rect(canvas, [4.0, 4.0, 8.0, 8.0], Color::rgb(255, 0, 0))
+        });
+        let flags = ImageFlags::PREMULTIPLIED | ImageFlags::FLIP_Y;
+        let filtered = canvas
+            .create_image_empty(W as usize, H as usize, PixelFormat::Rgba8, flags)
+            .expect("target image");
+        canvas
+            .filter_image_graph(filtered, graph, source)
+            .expect("filter graph");
+        show_image(canvas, filtered);
+    })
+}
+
+#[test]
+fn filter_graph_primitives() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no wgpu adapter available");
+        return;
+    };
+    let run = |graph: FilterGraph| run_graph(&device, &queue, &graph);
+
+    // A lone SourceGraphic reference is the identity.
+    let out = run(FilterGraph::new().with_primitive(FilterEffect::Offset {
+        input: FilterInput::SourceGraphic,
+        dx: 0.0,
+        dy: 0.0,
+    }));
+    assert_pixel(&out, 5, 5, RED, "identity");
+    assert_pixel(&out, 5, 13, WHITE, "identity");
+    assert_pixel(&out, 13, 5, WHITE, "identity");
+
+    // feOffset moves right and down, without smearing the image edges.
+    let out = run(FilterGraph::new().with_primitive(FilterEffect::Offset {
+        input: FilterInput::SourceGraphic,
+        dx: 8.0,
+        dy: 4.0,
+    }));
+    assert_pixel(&out, 13, 9, RED, "offset");
+    assert_pixel(&out, 19, 15, RED, "offset");
+    assert_pixel(&out, 13, 7, WHITE, "offset");
+    assert_pixel(&out, 5, 5, WHITE, "offset");
+
+    // feFlood in a subregion, merged under the source.
+    let out = run(FilterGraph::new()
+        .with_primitive(
+            FilterPrimitive::new(FilterEffect::Flood {
+                color: Color::rgb(0, 0, 255),
+            })
+            .with_subregion(0.0, 0.0, 32.0, 8.0)
+            .with_result("band"),
+        )
+        .with_primitive(FilterEffect::Merge {
+            inputs: vec!["band".into(), FilterInput::SourceGraphic],
+        }));
+    assert_pixel(&out, 5, 5, RED, "merge");
+    assert_pixel(&out, 20, 5, [0, 0, 255], "merge");
+    assert_pixel(&out, 20, 10, WHITE, "merge");
+    assert_pixel(&out, 5, 10, RED, "merge");
+
+    // feComposite in: a green flood clipped to the source alpha.
+    let out = run(FilterGraph::new()
+        .with_primitive(FilterEffect::Flood {
+            color: Color::rgb(0, 255, 0),
+        })
+        .with_primitive(FilterEffect::Composite {
+            input: FilterInput::Previous,
+            input2: FilterInput::SourceAlpha,
+            operator: FilterCompositeOperator::In,
+        }));
+    assert_pixel(&out, 5, 5, [0, 255, 0], "composite in");
+    assert_pixel(&out, 20, 20, WHITE, "composite in");
+
+    // feComposite arithmetic: half the source plus half the flood.
+    let out = run(FilterGraph::new()
+        .with_primitive(FilterEffect::Flood {
+            color: Color::rgb(0, 0, 255),
+        })
+        .with_primitive(FilterEffect::Composite {
+            input: FilterInput::SourceGraphic,
+            input2: FilterInput::Previous,
+            operator: FilterCompositeOperator::Arithmetic {
+                k1: 0.0,
+                k2: 0.5,
+                k3: 0.5,
+                k4: 0.0,
+            },
+        }));
+    assert_pixel(&out, 5, 5, [128, 0, 128], "arithmetic");
+    assert_pixel(&out, 20, 20, [128, 128, 255], "arithmetic");
+
+    // feBlend multiply of the red source over a gray backdrop.
+    let out = run(FilterGraph::new()
+        .with_primitive(FilterEffect::Flood {
+            color: Color::rgb(128, 128, 128),
+        })
+        .with_primitive(FilterEffect::Blend {
+            input: FilterInput::SourceGraphic,
+            input2: FilterInput::Previous,
+            mode: BlendMode::Multiply,
+        }));
+    assert_pixel(&out, 5, 5, [128, 0, 0], "blend multiply");
+    assert_pixel(&out, 20, 20, [128, 128, 128], "blend multiply");
+    assert_pixel(&out, 5, 13, [128, 128, 128], "blend multiply");
+
+    // feBlend screen lightens.
+    let out = run(FilterGraph::new()
+        .with_primitive(FilterEffect::Flood {
+            color: Color::rgb(0, 0, 255),
+        })
+        .with_primitive(FilterEffect::Blend {
+            input: FilterInput::SourceGraphic,
+            input2: FilterInput::Previous,
+            mode: BlendMode::Screen,
+        }));
+    assert_pixel(&out, 5, 5, [255, 0, 255], "blend screen");
+
+    // feTile repeats the 16x16 top-left cell across the image.
+    let out = run(FilterGraph::new()
+        .with_primitive(
+            FilterPrimitive::new(FilterEffect::Offset {
+                input: FilterInput::SourceGraphic,
+                dx: 0.0,
+                dy: 0.0,
+            })
+            .with_subregion(0.0, 0.0, 16.0, 16.0),
+        )
+        .with_primitive(FilterEffect::Tile {
+            input: FilterInput::Previous,
+        }));
+    for (x, y) in [(5, 5), (21, 5), (5, 21), (21, 21)] {
+        assert_pixel(&out, x, y, RED, "tile");
+    }
+    for (x, y) in [(14, 5), (21, 14), (5, 30), (30, 30)] {
+        assert_pixel(&out, x, y, WHITE, "tile");
+    }
+
+    // The filter region clips every primitive.
+    let out = run(FilterGraph::new()
+        .with_region(0.0, 0.0, 32.0, 8.0)
+        .with_primitive(FilterEffect::Flood {
+            color: Color::rgb(0, 0, 255),
+        }));
+    assert_pixel(&out, 20, 5, [0, 0, 255], "region");
+    assert_pixel(&out, 20, 10, WHITE, "region");
+
+    // feMorphology grows the source outline into a halo behind it.
+    let out = run(FilterGraph::new()
+        .with_primitive(FilterEffect::Morphology {
+            input: FilterInput::SourceAlpha,
+            operator: MorphologyOperator::Dilate,
+            radius_x: 2.0,
+            radius_y: 2.0,
+        })
+        .with_primitive(FilterEffect::Merge {
+            inputs: vec![FilterInput::Previous, FilterInput::SourceGraphic],
+        }));
+    assert_pixel(&out, 5, 5, RED, "morphology halo");
+    assert_pixel(&out, 2, 13, [0, 0, 0], "morphology halo");
+    assert_pixel(&out, 1, 13, WHITE, "morphology halo");
+
+    // feConvolveMatrix reads the bottom-up intermediates the right way up.
+    let mut kernel = [0.0; 25];
+    kernel[0] = 1.0;
+    let out = run(FilterGraph::new().with_primitive(FilterEffect::Convolve {
+        input: FilterInput::SourceGraphic,
+        kernel,
+        size: (1, 3),
+        divisor: 0.0,
+        bias: 0.0,
+        edge_mode: EdgeMode::None,
+        preserve_alpha: false,
+    }));
+    assert_pixel(&out, 5, 3, RED, "convolve");
+    assert_pixel(&out, 5, 11, WHITE, "convolve");
+
+    // feTurbulence fills its subregion; at zero frequency every pixel sits on
+    // a lattice point, where the noise is zero: half-transparent mid gray.
+    let out = run(FilterGraph::new().with_primitive(
+        FilterPrimitive::new(FilterEffect::Turbulence {
+            kind: TurbulenceKind::FractalNoise,
+            base_frequency: (0.0, 0.0),
+            octaves: 2,
+            seed: 0.0,
+            stitch_tiles: false,
+        })
+        .with_subregion(0.0, 0.0, 16.0, 8.0),
+    ));
+    assert_pixel(&out, 2, 2, [191, 191, 191], "turbulence");
+    assert_pixel(&out, 2, 10, WHITE, "turbulence");
+    assert_pixel(&out, 20, 2, WHITE, "turbulence");
+
+    // feDisplacementMap reads both bottom-up intermediates the right way up:
+    // the flood moves the square 4 pixels left and down.
+    let out = run(FilterGraph::new()
+        .with_primitive(FilterEffect::Flood {
+            color: Color::rgb(255, 128, 0),
+        })
+        .with_primitive(FilterEffect::DisplacementMap {
+            input: FilterInput::SourceGraphic,
+            input2: FilterInput::Previous,
+            scale: 8.0,
+            x_channel: ColorChannel::Red,
+            y_channel: ColorChannel::Blue,
+        }));
+    assert_pixel(&out, 1, 9, RED, "displacement map");
+    assert_pixel(&out, 9, 9, WHITE, "displacement map");
+    assert_pixel(&out, 5, 5, WHITE, "displacement map");
+
+    // feComponentTransfer swaps the red square for a green one, in place.
+    let zero = TransferFunction::Linear {
+        slope: 0.0,
+        intercept: 0.0,
+    };
+    let one = TransferFunction::Linear {
+        slope: 0.0,
+        intercept: 1.0,
+    };
+    let out = run(FilterGraph::new().with_primitive(FilterEffect::ComponentTransfer {
+        input: FilterInput::SourceGraphic,
+        transfer: ComponentTransfer {
+            red: zero,
+            green: one,
+            ..Default::default()
+        },
+    }));
+    assert_pixel(&out, 4, 4, [0, 255, 0], "component transfer");
+    assert_pixel(&out, 11, 11, [0, 255, 0], "component transfer");
+    assert_pixel(&out, 12, 12, WHITE, "component transfer");
+
+    // feGaussianBlur and feColorMatrix keep the image upright.
+    let out = run(FilterGraph::new()
+        .with_primitive(FilterEffect::GaussianBlur {
+            input: FilterInput::SourceGraphic,
+            std_deviation: 1.0,
+        })
+        .with_primitive(FilterEffect::ColorMatrix {
+            input: FilterInput::Previous,
+            matrix: [
+                0.0, 0.0, 0.0, 0.0, 0.0, //
+                1.0, 0.0, 0.0, 0.0, 0.0, //
+                0.0, 0.0, 0.0, 0.0, 0.0, //
+                0.0, 0.0, 0.0, 1.0, 0.0,
+            ],
+        }));
+    assert_pixel(&out, 8, 8, [0, 255, 0], "blur and color matrix");
+    assert_pixel(&out, 8, 20, WHITE, "blur and color matrix");
+    assert_pixel(&out, 20, 8, WHITE, "blur and color matrix");
+}
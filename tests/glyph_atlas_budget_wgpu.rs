@@ -0,0 +1,149 @@
+//! Headless GPU test for the glyph atlas memory budget.
+//!
+//! With a budget, atlas pages are cleared and reused for new glyphs. Text
+//! drawn after its page was recycled must be rasterized again and look exactly
+//! as before. Skips (prints and returns) when no GPU adapter is available.
+#![cfg(feature = "wgpu")]
+
+use femtovg::{renderer::WGPURenderer, Canvas, Color, Paint};
+
+const W: u32 = 300;
+const H: u32 = 120;
+const FONT: &[u8] = include_bytes!("../examples/assets/RobotoFlex-VariableFont.ttf");
+
+fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::default();
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::default(),
+        force_fallback_adapter: false,
+        compatible_surface: None,
+        ..Default::default()
+    }))
+    .ok()?;
+    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        label: Some("femtovg subpixel text test device"),
+        required_features: wgpu::Features::empty(),
+        required_limits: wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
+        experimental_features: wgpu::ExperimentalFeatures::disabled(),
+        memory_hints: wgpu::MemoryHints::MemoryUsage,
+        trace: wgpu::Trace::default(),
+    }))
+    .ok()?;
+    Some((device, queue))
+}
+
+/// Flushes the canvas into `target` and returns its row-major `[r, g, b, a]` pixels.
+fn flush_and_read(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    canvas: &mut Canvas<WGPURenderer>,
+    target: &wgpu::Texture,
+) -> Vec<u8> {
+    let commands = canvas.flush_to_output(target);
+    queue.submit(commands);
+
+    let unpadded_bytes_per_row = W * 4;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("subpixel text readback"),
+        size: (padded_bytes_per_row * H) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture: target,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &readback,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_bytes_per_row),
+                rows_per_image: Some(H),
+            },
+        },
+        wgpu::Extent3d {
+            width: W,
+            height: H,
+            depth_or_array_layers: 1,
+        },
+    );
+    queue.submit(Some(encoder.finish()));
+
+    let slice = readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| {});
+    device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+    let mapped = slice.get_mapped_range().expect("mapped readback range");
+    let mut pixels = vec![0u8; (unpadded_bytes_per_row * H) as usize];
+    for row in 0..H as usize {
+        let src = row * padded_bytes_per_row as usize;
+        let dst = row * unpadded_bytes_per_row as usize;
+        pixels[dst..dst + unpadded_bytes_per_row as usize]
+            .copy_from_slice(&mapped[src..src + unpadded_bytes_per_row as usize]);
+    }
+    drop(mapped);
+    readback.unmap();
+    pixels
+}
+
+#[test]
+fn text_redraws_identically_after_its_page_was_recycled() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no GPU adapter available");
+        return;
+    };
+    let target = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("glyph atlas budget target"),
+        size: wgpu::Extent3d {
+            width: W,
+            height: H,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+
+    let renderer = WGPURenderer::new(device.clone(), queue.clone());
+    let mut canvas = Canvas::new(renderer).expect("failed to create canvas");
+    canvas.set_size(W, H, 1.0);
+    // Two pages of 512x512 RGBA texels.
+    canvas.set_glyph_atlas_budget(Some(2 * 512 * 512 * 4));
+    let font = canvas.add_font_mem(FONT).expect("failed to load font");
+
+    let frame = |canvas: &mut Canvas<WGPURenderer>, text: &str, font_size: f32| {
+        canvas.clear_rect(0, 0, W, H, Color::white());
+        let paint = Paint::color(Color::black())
+            .with_font(&[font])
+            .with_font_size(font_size);
+        canvas.fill_text(10.0, 80.0, text, &paint).unwrap();
+        flush_and_read(&device, &queue, canvas, &target)
+    };
+
+    let before = frame(&mut canvas, "Hello", 40.0);
+    // Fill both pages several times over: once they are full, the page not
+    // drawn from in the current frame is recycled.
+    for font_size in [60.0, 64.0, 68.0, 72.0, 76.0, 80.0, 84.0, 88.0] {
+        frame(
+            &mut canvas,
+            "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz",
+            font_size,
+        );
+    }
+    let stats = canvas.glyph_atlas_stats();
+    assert_eq!(stats.pages, 2);
+    assert!(stats.recycled_pages > 0, "pages were not recycled: {stats:?}");
+    assert!(stats.evicted_glyphs > 0, "glyphs were not evicted: {stats:?}");
+
+    let after = frame(&mut canvas, "Hello", 40.0);
+    assert!(before.chunks_exact(4).any(|p| p[0] < 128), "no text drawn");
+    assert!(before == after, "text changed after its glyphs were re-rasterized");
+}
@@ -915,3 +915,73 @@ fn zeroed_os2_script_metrics_fall_back() {
         );
     }
 }
+
+#[test]
+fn font_info_reports_names_and_named_instances() {
+    let text_context = femtovg::TextContext::default();
+
+    let font_id = text_context
+        .add_font_file("examples/assets/RobotoFlex-VariableFont.ttf")
+        .expect("Font not found");
+
+    let info = text_context.font_info(font_id).expect("font info");
+    assert_eq!(info.family.as_deref(), Some("Roboto Flex"));
+    assert_eq!(info.subfamily.as_deref(), Some("Regular"));
+    assert_eq!(info.postscript_name.as_deref(), Some("RobotoFlex-Regular"));
+
+    // One coordinate per fvar axis (wght, slnt).
+    let bold_italic = info
+        .named_instances
+        .iter()
+        .find(|instance| instance.name.as_deref() == Some("Bold Italic"))
+        .expect("Bold Italic instance");
+    assert_eq!(bold_italic.coordinates, vec![700.0, -10.0]);
+
+    let static_id = text_context
+        .add_font_file("examples/assets/amiri-regular.ttf")
+        .expect("Font not found");
+    let info = text_context.font_info(static_id).expect("font info");
+    assert_eq!(info.family.as_deref(), Some("Amiri"));
+    assert!(info.named_instances.is_empty());
+}
+
+#[test]
+fn query_font_matches_family_weight_and_style() {
+    use femtovg::FontStyle;
+
+    let text_context = femtovg::TextContext::default();
+
+    let amiri = text_context
+        .add_font_file("examples/assets/amiri-regular.ttf")
+        .expect("Font not found");
+    let roboto = text_context
+        .add_font_file("examples/assets/RobotoFlex-VariableFont.ttf")
+        .expect("Font not found");
+
+    // Family names compare case-insensitively.
+    assert_eq!(
+        text_context
+            .query_font("roboto flex", 400.0, FontStyle::Normal, 100.0)
+            .unwrap(),
+        roboto
+    );
+    // The variable font covers bold and oblique through its wght and slnt axes.
+    assert_eq!(
+        text_context
+            .query_font("Roboto Flex", 700.0, FontStyle::Oblique, 100.0)
+            .unwrap(),
+        roboto
+    );
+    // A static family still matches a style or weight it lacks.
+    assert_eq!(
+        text_context
+            .query_font("Amiri", 900.0, FontStyle::Italic, 100.0)
+            .unwrap(),
+        amiri
+    );
+
+    assert!(matches!(
+        text_context.query_font("Missing Family", 400.0, FontStyle::Normal, 100.0),
+        Err(femtovg::ErrorKind::NoFontFound)
+    ));
+}
@@ -0,0 +1,94 @@
+//! Headless GPU tests for `ImageFilter::Morphology`: a red rectangle is eroded
+//! or dilated, the result drawn over white and probed per pixel. The probes
+//! check the per-axis radii, that pixels outside the image count as
+//! transparent, and that the two passes keep the image upright. Skips when no
+//! GPU adapter is available.
+#![cfg(feature = "wgpu")]
+
+use femtovg::{Color, ImageFilter, ImageFlags, MorphologyOperator, PixelFormat};
+
+mod common;
+
+use common::{assert_pixel, draw_source, fill_rect, headless_device, render, show_image, H, RED, W, WHITE};
+
+/// Fills `square` of a source image with red, runs `filter` on it, draws the
+/// result over white and returns the output pixels, row by row.
+fn run_filter(device: &wgpu::Device, queue: &wgpu::Queue, square: [f32; 4], filter: ImageFilter) -> Vec<[u8; 4]> {
+    render(device, queue, |canvas| {
+        let source = draw_source(canvas, |canvas| fill_rect(canvas, square, Color::rgb(255, 0, 0)));
+        let flags = ImageFlags::PREMULTIPLIED | ImageFlags::FLIP_Y;
+        let filtered = canvas
+            .create_image_empty(W as usize, H as usize, PixelFormat::Rgba8, flags)
+            .expect("target image");
+        canvas.filter_image(filtered, filter, source);
+        show_image(canvas, filtered);
+    })
+}
+
+#[test]
+fn morphology_filter() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no wgpu adapter available");
+        return;
+    };
+    let square = [4.0, 4.0, 8.0, 8.0];
+    let morphology = |operator, radius_x, radius_y| ImageFilter::Morphology {
+        operator,
+        radius_x,
+        radius_y,
+    };
+
+    // Dilating only horizontally widens the square by the radius on each side.
+    let out = run_filter(
+        &device,
+        &queue,
+        square,
+        morphology(MorphologyOperator::Dilate, 2.0, 0.0),
+    );
+    assert_pixel(&out, 2, 5, RED, "dilate x");
+    assert_pixel(&out, 13, 10, RED, "dilate x");
+    assert_pixel(&out, 1, 5, WHITE, "dilate x");
+    assert_pixel(&out, 14, 5, WHITE, "dilate x");
+    assert_pixel(&out, 5, 3, WHITE, "dilate x");
+    assert_pixel(&out, 5, 25, WHITE, "dilate x");
+
+    // Dilating only vertically grows it up and down.
+    let out = run_filter(
+        &device,
+        &queue,
+        square,
+        morphology(MorphologyOperator::Dilate, 0.0, 3.0),
+    );
+    assert_pixel(&out, 5, 1, RED, "dilate y");
+    assert_pixel(&out, 5, 14, RED, "dilate y");
+    assert_pixel(&out, 5, 0, WHITE, "dilate y");
+    assert_pixel(&out, 5, 15, WHITE, "dilate y");
+    assert_pixel(&out, 2, 5, WHITE, "dilate y");
+
+    // Eroding shrinks it from all sides.
+    let out = run_filter(&device, &queue, square, morphology(MorphologyOperator::Erode, 1.0, 2.0));
+    assert_pixel(&out, 5, 6, RED, "erode");
+    assert_pixel(&out, 10, 9, RED, "erode");
+    assert_pixel(&out, 4, 8, WHITE, "erode");
+    assert_pixel(&out, 8, 5, WHITE, "erode");
+    assert_pixel(&out, 8, 10, WHITE, "erode");
+
+    // Outside the image is transparent, so a full-image fill erodes at the
+    // borders.
+    let full = [0.0, 0.0, W as f32, H as f32];
+    let out = run_filter(&device, &queue, full, morphology(MorphologyOperator::Erode, 2.0, 2.0));
+    assert_pixel(&out, 16, 16, RED, "erode borders");
+    for (x, y) in [(1, 16), (30, 16), (16, 1), (16, 30)] {
+        assert_pixel(&out, x, y, WHITE, "erode borders");
+    }
+    assert_pixel(&out, 2, 16, RED, "erode borders");
+
+    // Radii beyond the shader's loop bound are clamped to 32 pixels.
+    let out = run_filter(
+        &device,
+        &queue,
+        square,
+        morphology(MorphologyOperator::Dilate, 100.0, 0.0),
+    );
+    assert_pixel(&out, 31, 5, RED, "clamped radius");
+}
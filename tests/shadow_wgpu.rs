@@ -47,6 +47,23 @@ fn render_to_pixels(
     queue: &wgpu::Queue,
     draw: impl FnOnce(&mut Canvas<WGPURenderer>),
 ) -> Vec<u8> {
+    let mut draw = Some(draw);
+    render_frames_to_pixels(device, queue, 1, |canvas, _| {
+        if let Some(draw) = draw.take() {
+            draw(canvas);
+        }
+    })
+    .remove(0)
+}
+
+/// Render `frames` frames with the same canvas, each drawn by `draw` with the
+/// frame index, and read the pixels of each back.
+fn render_frames_to_pixels(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    frames: usize,
+    mut draw: impl FnMut(&mut Canvas<WGPURenderer>, usize),
+) -> Vec<Vec<u8>> {
     let target = device.create_texture(&wgpu::TextureDescriptor {
         label: Some("shadow test target"),
         size: wgpu::Extent3d {
@@ -65,13 +82,20 @@ fn render_to_pixels(
     let renderer = WGPURenderer::new(device.clone(), queue.clone());
     let mut canvas = Canvas::new(renderer).expect("failed to create canvas");
     canvas.set_size(W, H, 1.0);
-    canvas.clear_rect(0, 0, W, H, Color::rgba(0, 0, 0, 0));
-
-    draw(&mut canvas);
 
-    let commands = canvas.flush_to_output(&target);
-    queue.submit(commands);
+    (0..frames)
+        .map(|frame| {
+            canvas.clear_rect(0, 0, W, H, Color::rgba(0, 0, 0, 0));
+            draw(&mut canvas, frame);
+            let commands = canvas.flush_to_output(&target);
+            queue.submit(commands);
+            read_pixels(device, queue, &target)
+        })
+        .collect()
+}
 
+/// Read the pixels of `target` back as a row-major buffer of `[r, g, b, a]`.
+fn read_pixels(device: &wgpu::Device, queue: &wgpu::Queue, target: &wgpu::Texture) -> Vec<u8> {
     // Copy the target texture into a readback buffer. bytes_per_row must be a
     // multiple of 256.
     let unpadded_bytes_per_row = W * 4;
@@ -88,7 +112,7 @@ fn render_to_pixels(
     let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
     encoder.copy_texture_to_buffer(
         wgpu::TexelCopyTextureInfo {
-            texture: &target,
+            texture: target,
             mip_level: 0,
             origin: wgpu::Origin3d::ZERO,
             aspect: wgpu::TextureAspect::All,
@@ -693,3 +717,40 @@ fn decoration_shares_the_single_text_shadow() {
         );
     }
 }
+
+/// A shadow drawn again in later frames comes from the shadow cache and must
+/// look the same as one rendered afresh, including after the offset moved it.
+#[test]
+fn cached_shadow_matches_a_fresh_one() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no wgpu adapter available");
+        return;
+    };
+
+    let mut circle = Path::new();
+    circle.circle(40.0, 50.0, 20.0);
+    let draw = |canvas: &mut Canvas<WGPURenderer>, frame: usize| {
+        canvas.set_shadow_color(Color::rgba(0, 0, 255, 200));
+        canvas.set_shadow_blur(8.0);
+        canvas.set_shadow_offset(if frame == 2 { 30.0 } else { 20.0 }, 10.0);
+        canvas.fill_path(&circle, &Paint::color(Color::rgb(255, 0, 0)));
+    };
+
+    let cached = render_frames_to_pixels(&device, &queue, 3, draw);
+    let fresh = render_frames_to_pixels(&device, &queue, 3, |canvas, frame| {
+        canvas.set_shadow_cache_limit(0);
+        draw(canvas, frame);
+    });
+
+    assert!(
+        pixel(&cached[0], 75, 60)[3] > 100,
+        "the shadow must show past the circle"
+    );
+    for frame in 1..3 {
+        assert!(
+            cached[frame] == fresh[frame],
+            "frame {frame} differs from an uncached shadow"
+        );
+    }
+    assert!(cached[1] != cached[2], "the offset must move the cached shadow");
+}
@@ -0,0 +1,214 @@
+//! Headless GPU tests for subpixel (LCD) text antialiasing.
+//!
+//! Subpixel glyphs carry one coverage per color channel and are composited
+//! with component alpha in two passes. These tests check the resulting pixels:
+//! colored fringes that follow the subpixel order, fully inked stems, and a
+//! grayscale result when the option is off. Each skips (prints and returns)
+//! when no GPU adapter is available.
+#![cfg(feature = "wgpu")]
+
+use femtovg::{renderer::WGPURenderer, Canvas, Color, Paint, TextAntialiasing};
+
+const W: u32 = 200;
+const H: u32 = 60;
+const FONT: &[u8] = include_bytes!("../examples/assets/RobotoFlex-VariableFont.ttf");
+
+fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::default();
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::default(),
+        force_fallback_adapter: false,
+        compatible_surface: None,
+        ..Default::default()
+    }))
+    .ok()?;
+    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        label: Some("femtovg subpixel text test device"),
+        required_features: wgpu::Features::empty(),
+        required_limits: wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
+        experimental_features: wgpu::ExperimentalFeatures::disabled(),
+        memory_hints: wgpu::MemoryHints::MemoryUsage,
+        trace: wgpu::Trace::default(),
+    }))
+    .ok()?;
+    Some((device, queue))
+}
+
+/// Clears the canvas white, runs `draw` with the test font loaded, and returns
+/// the row-major `[r, g, b, a]` pixels.
+fn render(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    draw: impl FnOnce(&mut Canvas<WGPURenderer>, femtovg::FontId),
+) -> Vec<u8> {
+    let target = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("subpixel text target"),
+        size: wgpu::Extent3d {
+            width: W,
+            height: H,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+
+    let renderer = WGPURenderer::new(device.clone(), queue.clone());
+    let mut canvas = Canvas::new(renderer).expect("failed to create canvas");
+    canvas.set_size(W, H, 1.0);
+    canvas.clear_rect(0, 0, W, H, Color::white());
+    let font = canvas.add_font_mem(FONT).expect("failed to load font");
+    draw(&mut canvas, font);
+
+    let commands = canvas.flush_to_output(&target);
+    queue.submit(commands);
+
+    let unpadded_bytes_per_row = W * 4;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("subpixel text readback"),
+        size: (padded_bytes_per_row * H) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture: &target,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &readback,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_bytes_per_row),
+                rows_per_image: Some(H),
+            },
+        },
+        wgpu::Extent3d {
+            width: W,
+            height: H,
+            depth_or_array_layers: 1,
+        },
+    );
+    queue.submit(Some(encoder.finish()));
+
+    let slice = readback.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| {});
+    device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+    let mapped = slice.get_mapped_range().expect("mapped readback range");
+    let mut pixels = vec![0u8; (unpadded_bytes_per_row * H) as usize];
+    for row in 0..H as usize {
+        let src = row * padded_bytes_per_row as usize;
+        let dst = row * unpadded_bytes_per_row as usize;
+        pixels[dst..dst + unpadded_bytes_per_row as usize]
+            .copy_from_slice(&mapped[src..src + unpadded_bytes_per_row as usize]);
+    }
+    drop(mapped);
+    readback.unmap();
+    pixels
+}
+
+/// Draws a row of vertical stems, black on white, and returns the pixels.
+fn draw_stems(device: &wgpu::Device, queue: &wgpu::Queue, antialiasing: TextAntialiasing) -> Vec<u8> {
+    render(device, queue, |canvas, font| {
+        let paint = Paint::color(Color::black())
+            .with_font(&[font])
+            .with_font_size(24.0)
+            .with_text_antialiasing(antialiasing);
+        canvas.fill_text(10.3, 40.0, "llll IIII", &paint).unwrap();
+    })
+}
+
+/// Sums `red - blue` over the first non-white pixel of each row, i.e. over the
+/// left edge of the first stem.
+fn left_edge_tint(pixels: &[u8]) -> i32 {
+    (0..H as usize)
+        .filter_map(|y| {
+            let row = &pixels[y * W as usize * 4..(y + 1) * W as usize * 4];
+            row.chunks_exact(4).find(|p| p[..3].iter().any(|&c| c < 240))
+        })
+        .map(|p| i32::from(p[0]) - i32::from(p[2]))
+        .sum()
+}
+
+fn tinted_pixels(pixels: &[u8]) -> usize {
+    pixels
+        .chunks_exact(4)
+        .filter(|p| p[0].abs_diff(p[1]) > 16 || p[1].abs_diff(p[2]) > 16)
+        .count()
+}
+
+fn ink(pixels: &[u8]) -> u32 {
+    pixels
+        .chunks_exact(4)
+        .map(|p| 765 - (u32::from(p[0]) + u32::from(p[1]) + u32::from(p[2])))
+        .sum()
+}
+
+#[test]
+fn subpixel_fringes_follow_the_subpixel_order() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no GPU adapter available");
+        return;
+    };
+    let grayscale = draw_stems(&device, &queue, TextAntialiasing::Grayscale);
+    let rgb = draw_stems(&device, &queue, TextAntialiasing::SubpixelRgb);
+    let bgr = draw_stems(&device, &queue, TextAntialiasing::SubpixelBgr);
+
+    assert_eq!(tinted_pixels(&grayscale), 0, "grayscale text has colored pixels");
+    assert!(tinted_pixels(&rgb) > 10, "RGB text has no colored fringes");
+    assert!(tinted_pixels(&bgr) > 10, "BGR text has no colored fringes");
+
+    // Black text covers the red subpixel last on a left edge with RGB order,
+    // so the edge reads red; BGR mirrors it.
+    assert!(left_edge_tint(&rgb) > 0, "RGB left edges are not red-tinted");
+    assert!(left_edge_tint(&bgr) < 0, "BGR left edges are not blue-tinted");
+}
+
+#[test]
+fn subpixel_text_covers_the_same_ink_as_grayscale_text() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no GPU adapter available");
+        return;
+    };
+    let grayscale = ink(&draw_stems(&device, &queue, TextAntialiasing::Grayscale));
+    let rgb = ink(&draw_stems(&device, &queue, TextAntialiasing::SubpixelRgb));
+
+    assert!(grayscale > 0);
+    let ratio = rgb as f32 / grayscale as f32;
+    assert!((0.85..1.15).contains(&ratio), "ink differs: {rgb} vs {grayscale}");
+}
+
+#[test]
+fn subpixel_text_blends_its_color_over_the_background() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no GPU adapter available");
+        return;
+    };
+    // Red text at half opacity over a blue background: stem interiors are an
+    // even mix, and no channel of the background is lost outside the glyphs.
+    let pixels = render(&device, &queue, |canvas, font| {
+        canvas.clear_rect(0, 0, W, H, Color::rgb(0, 0, 255));
+        let paint = Paint::color(Color::rgba(255, 0, 0, 128))
+            .with_font(&[font])
+            .with_font_size(48.0)
+            .with_text_antialiasing(TextAntialiasing::SubpixelRgb);
+        canvas.fill_text(10.0, 50.0, "I", &paint).unwrap();
+    });
+
+    let center = pixels
+        .chunks_exact(4)
+        .filter(|p| p[0] > 100)
+        .max_by_key(|p| p[0])
+        .expect("no text drawn");
+    assert!(center[0].abs_diff(128) <= 3, "unexpected fill {center:?}");
+    assert!(center[2].abs_diff(127) <= 3, "unexpected fill {center:?}");
+    assert_eq!(&pixels[..4], &[0, 0, 255, 255], "background changed");
+}
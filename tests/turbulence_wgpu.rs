@@ -0,0 +1,247 @@
+//! Headless GPU tests for `ImageFilter::Turbulence`: noise is generated into
+//! images of both orientations, copied to the output and compared with the
+//! reference code of the Filter Effects spec, ported below. Skips when no GPU
+//! adapter is available.
+#![cfg(feature = "wgpu")]
+
+use femtovg::{Color, CompositeOperation, ImageFilter, ImageFlags, Paint, Path, PixelFormat, TurbulenceKind};
+
+mod common;
+
+use common::{headless_device, render, H, W};
+
+/// Generates `filter` into an image with `flags`, copies it to the output and
+/// returns the premultiplied output pixels, row by row.
+fn run_filter(device: &wgpu::Device, queue: &wgpu::Queue, filter: ImageFilter, flags: ImageFlags) -> Vec<[u8; 4]> {
+    render(device, queue, |canvas| {
+        // The source only sizes the pass.
+        let source = canvas
+            .create_image_empty(W as usize, H as usize, PixelFormat::Rgba8, ImageFlags::empty())
+            .expect("source image");
+        let noise = canvas
+            .create_image_empty(
+                W as usize,
+                H as usize,
+                PixelFormat::Rgba8,
+                flags | ImageFlags::PREMULTIPLIED,
+            )
+            .expect("target image");
+        canvas.filter_image(noise, filter, source);
+
+        canvas.clear_rect(0, 0, W, H, Color::rgbaf(0.0, 0.0, 0.0, 0.0));
+        canvas.global_composite_operation(CompositeOperation::Copy);
+        let mut p = Path::new();
+        p.rect(0.0, 0.0, W as f32, H as f32);
+        canvas.fill_path(&p, &Paint::image(noise, 0.0, 0.0, W as f32, H as f32, 0.0, 1.0));
+    })
+}
+
+/// The random lattice and `turbulence()` of the spec's reference code.
+struct Reference {
+    lattice: [usize; 512],
+    gradients: [[[f64; 2]; 512]; 4],
+}
+
+impl Reference {
+    fn new(seed: f32) -> Self {
+        fn random(seed: i64) -> i64 {
+            let result = 16807 * (seed % 127773) - 2836 * (seed / 127773);
+            if result <= 0 {
+                result + 2147483647
+            } else {
+                result
+            }
+        }
+        let mut seed = seed as i64;
+        if seed <= 0 {
+            seed = -(seed % 2147483646) + 1;
+        }
+        seed = seed.min(2147483646);
+
+        let mut lattice = [0; 512];
+        let mut gradients = [[[0.0; 2]; 512]; 4];
+        for channel in &mut gradients {
+            for i in 0..256 {
+                lattice[i] = i;
+                for value in &mut channel[i] {
+                    seed = random(seed);
+                    *value = ((seed % 512) - 256) as f64 / 256.0;
+                }
+                let s = (channel[i][0] * channel[i][0] + channel[i][1] * channel[i][1]).sqrt();
+                channel[i][0] /= s;
+                channel[i][1] /= s;
+            }
+        }
+        for i in (1..256).rev() {
+            seed = random(seed);
+            lattice.swap(i, seed as usize % 256);
+        }
+        for i in 0..256 {
+            lattice[256 + i] = lattice[i];
+            for channel in &mut gradients {
+                channel[256 + i] = channel[i];
+            }
+        }
+        Self { lattice, gradients }
+    }
+
+    fn noise2(&self, channel: usize, vec: [f64; 2], stitch: Option<[i64; 4]>) -> f64 {
+        let t = vec[0] + 4096.0;
+        let (mut bx0, rx0) = (t as i64, t.fract());
+        let mut bx1 = bx0 + 1;
+        let t = vec[1] + 4096.0;
+        let (mut by0, ry0) = (t as i64, t.fract());
+        let mut by1 = by0 + 1;
+        if let Some([width, height, wrap_x, wrap_y]) = stitch {
+            if bx0 >= wrap_x {
+                bx0 -= width;
+            }
+            if bx1 >= wrap_x {
+                bx1 -= width;
+            }
+            if by0 >= wrap_y {
+                by0 -= height;
+            }
+            if by1 >= wrap_y {
+                by1 -= height;
+            }
+        }
+        let (bx0, bx1, by0, by1) = (
+            bx0 as usize & 255,
+            bx1 as usize & 255,
+            by0 as usize & 255,
+            by1 as usize & 255,
+        );
+        let (rx1, ry1) = (rx0 - 1.0, ry0 - 1.0);
+        let i = self.lattice[bx0];
+        let j = self.lattice[bx1];
+        let g = |b: usize| self.gradients[channel][self.lattice[b]];
+        let lerp = |t: f64, a: f64, b: f64| a + t * (b - a);
+        let sx = rx0 * rx0 * (3.0 - 2.0 * rx0);
+        let sy = ry0 * ry0 * (3.0 - 2.0 * ry0);
+        let (q00, q10, q01, q11) = (g(i + by0), g(j + by0), g(i + by1), g(j + by1));
+        let a = lerp(sx, rx0 * q00[0] + ry0 * q00[1], rx1 * q10[0] + ry0 * q10[1]);
+        let b = lerp(sx, rx0 * q01[0] + ry1 * q01[1], rx1 * q11[0] + ry1 * q11[1]);
+        lerp(sy, a, b)
+    }
+
+    /// The premultiplied pixel at `(x, y)` of a `W` by `H` tile.
+    fn pixel(&self, filter: &ImageFilter, x: usize, y: usize) -> [u8; 4] {
+        let ImageFilter::Turbulence {
+            kind,
+            base_frequency: (mut fx, mut fy),
+            octaves,
+            stitch_tiles,
+            ..
+        } = *filter
+        else {
+            unreachable!()
+        };
+        let mut stitch = None;
+        if stitch_tiles {
+            let snap = |f: f64, size: f64| {
+                let low = (size * f).floor() / size;
+                let high = (size * f).ceil() / size;
+                if f / low < high / f {
+                    low
+                } else {
+                    high
+                }
+            };
+            fx = snap(fx as f64, W as f64) as f32;
+            fy = snap(fy as f64, H as f64) as f32;
+            let width = (W as f64 * fx as f64 + 0.5) as i64;
+            let height = (H as f64 * fy as f64 + 0.5) as i64;
+            stitch = Some([width, height, 4096 + width, 4096 + height]);
+        }
+        let mut color = [0.0; 4];
+        for (channel, value) in color.iter_mut().enumerate() {
+            let mut vec = [x as f64 * fx as f64, y as f64 * fy as f64];
+            let mut stitch = stitch;
+            let mut sum = 0.0;
+            let mut ratio = 1.0;
+            for _ in 0..octaves {
+                let noise = self.noise2(channel, vec, stitch);
+                sum += if kind == TurbulenceKind::FractalNoise {
+                    noise
+                } else {
+                    noise.abs()
+                } / ratio;
+                vec = [vec[0] * 2.0, vec[1] * 2.0];
+                ratio *= 2.0;
+                stitch = stitch.map(|[w, h, wx, wy]| [w * 2, h * 2, wx * 2 - 4096, wy * 2 - 4096]);
+            }
+            *value = if kind == TurbulenceKind::FractalNoise {
+                (sum + 1.0) / 2.0
+            } else {
+                sum
+            }
+            .clamp(0.0, 1.0);
+        }
+        let a = color[3];
+        [color[0] * a, color[1] * a, color[2] * a, a].map(|c| (c * 255.0).round() as u8)
+    }
+}
+
+fn turbulence(
+    kind: TurbulenceKind,
+    base_frequency: (f32, f32),
+    octaves: u32,
+    seed: f32,
+    stitch_tiles: bool,
+) -> ImageFilter {
+    ImageFilter::Turbulence {
+        kind,
+        base_frequency,
+        octaves,
+        seed,
+        stitch_tiles,
+    }
+}
+
+fn assert_matches_reference(pixels: &[[u8; 4]], filter: &ImageFilter, what: &str) {
+    let ImageFilter::Turbulence { seed, .. } = *filter else {
+        unreachable!()
+    };
+    let reference = Reference::new(seed);
+    for y in 0..H as usize {
+        for x in 0..W as usize {
+            let pixel = pixels[y * W as usize + x];
+            let expected = reference.pixel(filter, x, y);
+            assert!(
+                pixel
+                    .iter()
+                    .zip(expected)
+                    .all(|(a, b)| (*a as i32 - b as i32).abs() <= 3),
+                "{what}: pixel ({x}, {y}) is {pixel:?}, expected {expected:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn turbulence_filter() {
+    let Some((device, queue)) = headless_device() else {
+        eprintln!("skipping: no wgpu adapter available");
+        return;
+    };
+    let run = |filter: &ImageFilter, flags| run_filter(&device, &queue, filter.clone(), flags);
+
+    // The noise comes out upright in both orientations of the target.
+    let fractal = turbulence(TurbulenceKind::FractalNoise, (0.05, 0.08), 3, 2.0, false);
+    assert_matches_reference(&run(&fractal, ImageFlags::FLIP_Y), &fractal, "fractal noise, bottom-up");
+    assert_matches_reference(&run(&fractal, ImageFlags::empty()), &fractal, "fractal noise, top-down");
+
+    // Turbulence with a negative seed, and every octave the shader supports.
+    let creased = turbulence(TurbulenceKind::Turbulence, (0.1, 0.03), 10, -7.5, false);
+    assert_matches_reference(&run(&creased, ImageFlags::FLIP_Y), &creased, "turbulence");
+
+    // Stitched noise matches across opposite edges.
+    let stitched = turbulence(TurbulenceKind::FractalNoise, (0.07, 0.11), 4, 11.0, true);
+    assert_matches_reference(&run(&stitched, ImageFlags::FLIP_Y), &stitched, "stitched");
+
+    // Negative frequencies are an error in SVG and render transparent black.
+    let invalid = turbulence(TurbulenceKind::FractalNoise, (-0.1, 0.1), 3, 0.0, false);
+    let out = run(&invalid, ImageFlags::FLIP_Y);
+    assert!(out.iter().all(|pixel| *pixel == [0; 4]), "negative frequency");
+}